@@ -0,0 +1,271 @@
+use crate::signal::{Component, Signal};
+use crate::word::Word;
+use crate::{Change, Circuit};
+use adventofcode::graph::{bfs, topological_sort};
+use std::collections::{HashMap, HashSet};
+
+/// A circuit which keeps track of which wires read from which, so a schematic can be edited one signal at a time
+/// and only the wires reading from the edited wire are evaluated again
+pub struct IncrementalCircuit<W = u16> {
+    drivers: HashMap<String, Signal<W>>,
+    readers: HashMap<String, HashSet<String>>,
+    values: HashMap<String, W>,
+}
+
+impl<W> Default for IncrementalCircuit<W> {
+    fn default() -> Self {
+        IncrementalCircuit {
+            drivers: HashMap::new(),
+            readers: HashMap::new(),
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<W: Word> IncrementalCircuit<W> {
+    /// Adds a signal, replacing any signal already driving its output wire. Returns every wire whose value changed,
+    /// ordered by wire.
+    pub fn insert(&mut self, signal: Signal<W>) -> Vec<Change<W>> {
+        let id = match signal.output() {
+            Component::ID(id) => id.clone(),
+            Component::VALUE(_) => return vec![],
+        };
+
+        self.disconnect(&id);
+
+        for input in signal.inputs() {
+            if let Component::ID(input) = input {
+                self.readers
+                    .entry(input.clone())
+                    .or_default()
+                    .insert(id.clone());
+            }
+        }
+        self.drivers.insert(id.clone(), signal);
+
+        self.propagate(&id)
+    }
+
+    /// Removes the signal driving a wire, leaving it without a value. Returns every wire whose value changed,
+    /// ordered by wire.
+    pub fn remove(&mut self, id: &str) -> Vec<Change<W>> {
+        if self.disconnect(id).is_none() {
+            return vec![];
+        }
+
+        self.propagate(id)
+    }
+
+    /// Every signal in the circuit, in no particular order
+    pub fn signals(&self) -> impl Iterator<Item = &Signal<W>> {
+        self.drivers.values()
+    }
+
+    /// Takes away the driver of a wire, forgetting that it reads from its inputs
+    fn disconnect(&mut self, id: &str) -> Option<Signal<W>> {
+        let signal = self.drivers.remove(id)?;
+
+        for input in signal.inputs() {
+            if let Component::ID(input) = input {
+                if let Some(readers) = self.readers.get_mut(input) {
+                    readers.remove(id);
+                }
+            }
+        }
+
+        Some(signal)
+    }
+
+    /// Evaluates `id` again, followed by any wire reading from a wire whose value changed
+    fn propagate(&mut self, id: &str) -> Vec<Change<W>> {
+        let readers = |wire: &String| {
+            self.readers
+                .get(wire)
+                .map(|readers| readers.iter().cloned().collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+
+        // Wires fed by a loop can never be resolved, they are left until last and lose their value
+        let sorted =
+            topological_sort(&[id.to_string()], readers).unwrap_or_else(|cycle| cycle.sorted);
+        let looped: Vec<String> = {
+            let sorted: HashSet<&String> = sorted.iter().collect();
+            bfs(id.to_string(), readers)
+                .into_iter()
+                .filter(|wire| !sorted.contains(wire))
+                .collect()
+        };
+
+        let mut dirty = HashSet::from([id.to_string()]);
+        let mut changes = vec![];
+
+        for wire in sorted.into_iter().chain(looped.iter().cloned()) {
+            if !dirty.contains(&wire) {
+                continue;
+            }
+
+            let after = if looped.contains(&wire) {
+                None
+            } else {
+                let values = &self.values;
+                self.drivers
+                    .get(&wire)
+                    .and_then(|signal| signal.evaluate(|id| values.get(id).copied()))
+            };
+            let before = match after {
+                Some(value) => self.values.insert(wire.clone(), value),
+                None => self.values.remove(&wire),
+            };
+
+            if before != after {
+                dirty.extend(self.readers.get(&wire).into_iter().flatten().cloned());
+                changes.push(Change {
+                    wire,
+                    before,
+                    after,
+                });
+            }
+        }
+
+        changes.sort_by(|a, b| a.wire.cmp(&b.wire));
+        changes
+    }
+}
+
+impl<W: Word> Circuit<W> for IncrementalCircuit<W> {
+    fn new(schematic: &[Signal<W>]) -> Self {
+        let mut circuit = IncrementalCircuit::default();
+
+        for signal in schematic {
+            circuit.insert(signal.clone());
+        }
+
+        circuit
+    }
+
+    fn get(&self, id: &str) -> Option<W> {
+        self.values.get(id).copied()
+    }
+
+    fn replace(&mut self, signal: Signal<W>) -> Vec<Change<W>> {
+        self.insert(signal)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod incremental_circuit {
+        use super::super::*;
+        use crate::SimpleCircuit;
+
+        fn signal(raw: &str) -> Signal {
+            Signal::parse(raw).unwrap()
+        }
+
+        fn change(wire: &str, before: Option<u16>, after: Option<u16>) -> Change {
+            Change {
+                wire: wire.to_string(),
+                before,
+                after,
+            }
+        }
+
+        #[test]
+        fn test_insert_out_of_order() {
+            let mut c = IncrementalCircuit::default();
+
+            assert_eq!(c.insert(signal("x AND y -> z")), vec![]);
+            assert_eq!(
+                c.insert(signal("12 -> x")),
+                vec![change("x", None, Some(12))]
+            );
+            assert_eq!(
+                c.insert(signal("10 -> y")),
+                vec![change("y", None, Some(10)), change("z", None, Some(8))]
+            );
+        }
+
+        #[test]
+        fn test_remove() {
+            let mut c = IncrementalCircuit::new(&[
+                signal("1 -> a"),
+                signal("a -> b"),
+                signal("NOT b -> c"),
+            ]);
+
+            assert_eq!(
+                c.remove("a"),
+                vec![
+                    change("a", Some(1), None),
+                    change("b", Some(1), None),
+                    change("c", Some(65534), None),
+                ]
+            );
+            assert_eq!(c.remove("a"), vec![]);
+            assert_eq!(c.signals().count(), 2);
+
+            c.insert(signal("0 -> a"));
+            assert_eq!(c.get("c"), Some(65535));
+        }
+
+        #[test]
+        fn test_unchanged_values_stop_propagating() {
+            let mut c = IncrementalCircuit::new(&[
+                signal("5 -> a"),
+                signal("a AND 1 -> b"),
+                signal("b LSHIFT 3 -> c"),
+            ]);
+
+            assert_eq!(
+                c.insert(signal("7 -> a")),
+                vec![change("a", Some(5), Some(7))]
+            );
+            assert_eq!(c.get("c"), Some(8));
+        }
+
+        #[test]
+        fn test_loops() {
+            let mut c = IncrementalCircuit::new(&[
+                signal("1 -> a"),
+                signal("a -> b"),
+                signal("b OR 2 -> c"),
+            ]);
+
+            assert_eq!(
+                c.insert(signal("c -> a")),
+                vec![
+                    change("a", Some(1), None),
+                    change("b", Some(1), None),
+                    change("c", Some(3), None),
+                ]
+            );
+
+            c.insert(signal("4 -> a"));
+            assert_eq!(c.get("c"), Some(6));
+        }
+
+        #[test]
+        fn test_matches_simple_circuit() {
+            let schematic: Vec<Signal> = [
+                "x AND y -> d",
+                "x OR y -> e",
+                "x LSHIFT 2 -> f",
+                "y RSHIFT 2 -> g",
+                "NOT x -> h",
+                "NOT y -> i",
+                "123 -> x",
+                "456 -> y",
+            ]
+            .iter()
+            .map(|raw| signal(raw))
+            .collect();
+            let mut c = IncrementalCircuit::new(&schematic);
+            let mut s = SimpleCircuit::new(&schematic);
+
+            assert_eq!(c.set("x", 9), s.set("x", 9));
+            for wire in ["d", "e", "f", "g", "h", "i", "x", "y"] {
+                assert_eq!(c.get(wire), s.get(wire), "wire {}", wire);
+            }
+        }
+    }
+}
@@ -1,190 +1,290 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod incremental;
+pub mod schematic;
 pub mod signal;
-
-use std::collections::HashMap;
+pub mod simplify;
+pub mod solve;
+pub mod tape;
+pub mod validate;
+pub mod word;
+
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::graph::{bfs, topological_sort};
+use adventofcode::solution::Solution;
 use signal::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use tape::Tape;
+use validate::{validate, Report};
+use word::Word;
+
+/// How the value of a wire moved when a circuit was changed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change<W = u16> {
+    pub wire: String,
+    pub before: Option<W>,
+    pub after: Option<W>,
+}
 
-pub trait Circuit {
-    fn new(schematic: &Vec<Signal>) -> Self;
-    fn get(&self, id: &str) -> Option<u16>;
+/// A circuit carrying words of type `W`
+pub trait Circuit<W: Word = u16> {
+    fn new(schematic: &[Signal<W>]) -> Self;
+    fn get(&self, id: &str) -> Option<W>;
+
+    /// Makes `signal` the only driver of its output wire, re-evaluating the wires which read from it. Returns every
+    /// wire whose value changed, ordered by wire.
+    fn replace(&mut self, signal: Signal<W>) -> Vec<Change<W>>;
+
+    /// Holds a wire at a fixed value, whatever drove it before
+    fn set(&mut self, id: &str, value: W) -> Vec<Change<W>> {
+        self.replace(Signal::VALUE(
+            Component::ID(id.to_string()),
+            Component::VALUE(value),
+        ))
+    }
+
+    /// Builds the circuit, provided every wire can be given a value and no wire has more than one driver
+    fn build(schematic: &[Signal<W>]) -> Result<Self, Report<W>>
+    where
+        Self: Sized,
+    {
+        validate(schematic)?;
+        Ok(Self::new(schematic))
+    }
 }
 
-pub struct SimpleCircuit {
-    signals: HashMap<String, u16>,
+/// A circuit compiled to a [`Tape`] and run once, keeping the value of every wire in a register
+pub struct SimpleCircuit<W = u16> {
+    tape: Tape<W>,
+    registers: Vec<Option<W>>,
+    drivers: HashMap<String, Signal<W>>,
 }
 
-impl Circuit for SimpleCircuit {
-    fn new(schematic: &Vec<Signal>) -> Self {
-        let mut to_resolve: Vec<Signal> = schematic.clone();
-        let mut signals = HashMap::<String, u16>::new();
+impl<W: Word> SimpleCircuit<W> {
+    /// The value of every wire which has one
+    pub fn values(&self) -> HashMap<String, W> {
+        self.registers
+            .iter()
+            .enumerate()
+            .filter_map(|(register, value)| Some((self.tape.wire(register).to_string(), (*value)?)))
+            .collect()
+    }
+}
 
-        let mut last_to_resolve_count = to_resolve.len() + 1;
+impl<W: Word> Circuit<W> for SimpleCircuit<W> {
+    fn new(schematic: &[Signal<W>]) -> Self {
+        let tape = Tape::compile(schematic, &[]);
+        let registers = tape.evaluate(&[]);
+        let drivers = schematic
+            .iter()
+            .filter_map(|signal| match signal.output() {
+                Component::ID(id) => Some((id.clone(), signal.clone())),
+                Component::VALUE(_) => None,
+            })
+            .collect();
+
+        SimpleCircuit {
+            tape,
+            registers,
+            drivers,
+        }
+    }
 
-        loop {
-            if to_resolve.len() == 0 || last_to_resolve_count == to_resolve.len() {
-                break;
-            } else {
-                last_to_resolve_count = to_resolve.len();
-            }
+    fn get(&self, id: &str) -> Option<W> {
+        self.registers[self.tape.register(id)?]
+    }
+
+    fn replace(&mut self, signal: Signal<W>) -> Vec<Change<W>> {
+        let id = match signal.output() {
+            Component::ID(id) => id.clone(),
+            Component::VALUE(_) => return vec![],
+        };
+        self.drivers.insert(id.clone(), signal);
+
+        let (cone, sorted) = downstream(&self.drivers, &id);
+        let before: Vec<Option<W>> = cone.iter().map(|wire| self.get(wire)).collect();
+
+        // Only the instructions writing to the wires reading from `id` change, so the tape is patched rather than
+        // compiled again and only those instructions are run
+        let signals: Vec<(&str, Option<&Signal<W>>)> = cone
+            .iter()
+            .enumerate()
+            .map(|(n, wire)| (wire.as_str(), self.drivers.get(wire).filter(|_| n < sorted)))
+            .collect();
+        let positions = self.tape.replace(&signals);
+
+        self.registers.resize(self.tape.len(), None);
+        for wire in &cone {
+            let register = self
+                .tape
+                .register(wire)
+                .expect("Every wire in the cone is given a register");
+            self.registers[register] = None;
+        }
+
+        for position in positions {
+            let instruction = self.tape.instructions()[position];
+            self.registers[instruction.output()] = instruction.execute(&self.registers);
+        }
 
-            to_resolve = to_resolve
-                .into_iter()
-                .filter(|signal| {
-                    match signal {
-                        Signal::VALUE(Component::ID(id), Component::VALUE(val)) => {
-                            signals.insert(id.clone(), val.clone());
-                            false
-                        }
-                        Signal::VALUE(Component::ID(id), Component::ID(src_id)) => {
-                            if let Some(val) = signals.get(&src_id.clone()) {
-                                let val = val.clone();
-                                signals.insert(id.clone(), val);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        Signal::NOT(Component::ID(id), Component::ID(src)) => {
-                            if let Some(val) = signals.get(&src.clone()) {
-                                let val = val.clone();
-                                signals.insert(id.clone(), !val);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        Signal::AND(Component::ID(id), Component::ID(in1), Component::ID(in2)) => {
-                            if let (Some(a), Some(b)) =
-                                (signals.get(&in1.clone()), signals.get(&in2.clone()))
-                            {
-                                let a = a.clone();
-                                let b = b.clone();
-                                signals.insert(id.clone(), a & b);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        Signal::AND(Component::ID(id), Component::VALUE(a), Component::ID(in2)) => {
-                            if let Some(b) =
-                                signals.get(&in2.clone())
-                            {
-                                let a = a.clone();
-                                let b = b.clone();
-
-                                signals.insert(id.clone(), a & b);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        Signal::OR(Component::ID(id), Component::ID(in1), Component::ID(in2)) => {
-                            if let (Some(a), Some(b)) =
-                                (signals.get(&in1.clone()), signals.get(&in2.clone()))
-                            {
-                                let a  = a.clone();
-                                let b = b.clone();
-
-                                signals.insert(id.clone(), a | b);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        Signal::XOR(Component::ID(id), Component::ID(in1), Component::ID(in2)) => {
-                            if let (Some(a), Some(b)) =
-                                (signals.get(&in1.clone()), signals.get(&in2.clone()))
-                            {
-                                let a  = a.clone();
-                                let b = b.clone();
-
-                                signals.insert(id.clone(), a ^ b);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        Signal::LSHIFT(
-                            Component::ID(id),
-                            Component::ID(input_id),
-                            Component::VALUE(places),
-                        ) => {
-                            if let Some(input) = signals.get(&input_id.clone()) {
-                                let input = input.clone();
-                                let places = places.clone();
-
-                                signals.insert(id.clone(), input << places);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        Signal::RSHIFT(
-                            Component::ID(id),
-                            Component::ID(input_id),
-                            Component::VALUE(places),
-                        ) => {
-                            if let Some(input) = signals.get(&input_id.clone()) {
-                                let input = input.clone();
-                                let places = places.clone();
-                                
-                                signals.insert(id.clone(), input >> places);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        s => {
-                            panic!("Encountered unhandled signal: {:?}", s);
-                        }
-                    }
-                })
-                .collect();
-        }
-
-        SimpleCircuit { signals }
+        changes(cone, before, |wire| self.get(wire))
     }
+}
+
+/// Every wire reading from `id`, directly or through other wires, `id` included, along with how many of them are
+/// not fed by a loop. Each wire comes after the wires it reads from, with wires fed by a loop last.
+fn downstream<W>(drivers: &HashMap<String, Signal<W>>, id: &str) -> (Vec<String>, usize) {
+    let mut readers = HashMap::<&str, Vec<String>>::new();
 
-    fn get(&self, id: &str) -> Option<u16> {
-        self.signals.get(&id.to_string()).map(|v| v.clone())
+    for (wire, signal) in drivers {
+        for input in signal.inputs() {
+            if let Component::ID(input) = input {
+                readers.entry(input).or_default().push(wire.clone());
+            }
+        }
     }
+
+    let outputs = |wire: &String| readers.get(wire.as_str()).cloned().unwrap_or_default();
+    let mut order =
+        topological_sort(&[id.to_string()], outputs).unwrap_or_else(|cycle| cycle.sorted);
+    let sorted: HashSet<String> = order.iter().cloned().collect();
+
+    order.extend(
+        bfs(id.to_string(), outputs)
+            .into_iter()
+            .filter(|wire| !sorted.contains(wire)),
+    );
+    (order, sorted.len())
 }
 
-pub struct GraphCircuit {
-    signals: HashMap<String, Signal>
+/// Compares the values of `wires` from before a change with their values now, keeping those which moved
+fn changes<W: Word>(
+    wires: Vec<String>,
+    before: Vec<Option<W>>,
+    after: impl Fn(&str) -> Option<W>,
+) -> Vec<Change<W>> {
+    let mut changes: Vec<Change<W>> = wires
+        .into_iter()
+        .zip(before)
+        .map(|(wire, before)| Change {
+            after: after(&wire),
+            wire,
+            before,
+        })
+        .filter(|change| change.before != change.after)
+        .collect();
+
+    changes.sort_by(|a, b| a.wire.cmp(&b.wire));
+    changes
 }
 
-impl Circuit for GraphCircuit {
-    fn new(schematic: &Vec<Signal>) -> Self {
-        let mut signals = HashMap::new();
-
-        schematic.iter().for_each(|signal| {
-            match signal.clone() {
-                Signal::VALUE(Component::ID(id), _) => {signals.insert(id.clone(), signal.clone());},
-                Signal::NOT(Component::ID(id), _) => {signals.insert(id.clone(), signal.clone());},
-                Signal::AND(Component::ID(id), _, _) => {signals.insert(id.clone(), signal.clone());},
-                Signal::OR(Component::ID(id), _, _) => {signals.insert(id.clone(), signal.clone());},
-                Signal::XOR(Component::ID(id), _, _) => {signals.insert(id.clone(), signal.clone());},
-                Signal::LSHIFT(Component::ID(id), _, _) => {signals.insert(id.clone(), signal.clone());},
-                Signal::RSHIFT(Component::ID(id), _, _) => {signals.insert(id.clone(), signal.clone());},
-                _ => panic!("Encountered unhandled signal {:?}", signal)
-            }
-        });
+/// A circuit which only evaluates a wire when it is asked for, remembering every value it has computed
+pub struct GraphCircuit<W = u16> {
+    signals: HashMap<String, Signal<W>>,
+    cache: RefCell<HashMap<String, W>>,
+}
+
+impl<W: Word> GraphCircuit<W> {
+    /// Evaluates a wire by first evaluating the wires it reads from. `visiting` holds the wires currently being
+    /// evaluated, so a wire fed by a loop has no value rather than recursing forever.
+    fn resolve(&self, id: &str, visiting: &mut HashSet<String>) -> Option<W> {
+        if let Some(value) = self.cache.borrow().get(id) {
+            return Some(*value);
+        }
+
+        let signal = self.signals.get(id)?;
+        if !visiting.insert(id.to_string()) {
+            return None;
+        }
+
+        let value = self.evaluate(signal, visiting);
+        visiting.remove(id);
+
+        if let Some(value) = value {
+            self.cache.borrow_mut().insert(id.to_string(), value);
+        }
 
-        GraphCircuit { signals }
+        value
     }
 
-    fn get(&self, id: &str) -> Option<u16> {
-        if let Some(signal) = self.signals.get(&id.to_string()) {
-            match signal.clone() {
-                Signal::VALUE(_, Component::VALUE(val)) => Some(val),
-                _ => panic!("Encountered unhandled signal {:?}", signal)
-            }
-        } else {
-            None
+    fn evaluate(&self, signal: &Signal<W>, visiting: &mut HashSet<String>) -> Option<W> {
+        signal.evaluate(|id| self.resolve(id, visiting))
+    }
+}
+
+impl<W: Word> Circuit<W> for GraphCircuit<W> {
+    fn new(schematic: &[Signal<W>]) -> Self {
+        let signals = schematic
+            .iter()
+            .filter_map(|signal| match signal.output() {
+                Component::ID(id) => Some((id.clone(), signal.clone())),
+                Component::VALUE(_) => None,
+            })
+            .collect();
+
+        GraphCircuit {
+            signals,
+            cache: RefCell::default(),
+        }
+    }
+
+    fn get(&self, id: &str) -> Option<W> {
+        self.resolve(id, &mut HashSet::new())
+    }
+
+    fn replace(&mut self, signal: Signal<W>) -> Vec<Change<W>> {
+        let id = match signal.output() {
+            Component::ID(id) => id.clone(),
+            Component::VALUE(_) => return vec![],
+        };
+
+        // Replacing the driver of a wire never changes which wires read from it
+        let (cone, _) = downstream(&self.signals, &id);
+        let before = cone.iter().map(|wire| self.get(wire)).collect();
+
+        self.signals.insert(id, signal);
+        let cache = self.cache.get_mut();
+        for wire in &cone {
+            cache.remove(wire);
         }
+
+        changes(cone, before, |wire| self.get(wire))
+    }
+}
+
+/// Day 7: Some Assembly Required
+pub struct AssemblyRequired;
+
+impl Solution for AssemblyRequired {
+    const YEAR: u16 = 2015;
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "Some Assembly Required";
+
+    type Input = Vec<Signal>;
+    type Answer = u16;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_lines(input.trim(), Signal::parse)
+    }
+
+    fn part1(schematic: &Self::Input) -> Self::Answer {
+        SimpleCircuit::new(schematic)
+            .get("a")
+            .expect("Could not find value for wire \"a\".")
+    }
+
+    fn part2(schematic: &Self::Input) -> Self::Answer {
+        let mut circuit = SimpleCircuit::new(schematic);
+        let a = circuit
+            .get("a")
+            .expect("Could not find value for wire \"a\".");
+        circuit.set("b", a);
+
+        circuit
+            .get("a")
+            .expect("Could not find value for wire \"a\".")
     }
 }
 
@@ -195,9 +295,9 @@ mod test {
 
         #[test]
         fn test_processing_signal_value() {
-            let a = Signal::parse("0 -> foo");
-            let b = Signal::parse("1 -> bar");
-            let g = GraphCircuit::new(&vec![a.clone(), b.clone()]);
+            let a = Signal::parse("0 -> foo").unwrap();
+            let b = Signal::parse("1 -> bar").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("foo".to_string(), a);
@@ -205,9 +305,9 @@ mod test {
 
             assert_eq!(expected, g.signals);
 
-            let a = Signal::parse("0 -> foo");
-            let b = Signal::parse("foo -> bar");
-            let g = GraphCircuit::new(&vec![a.clone(), b.clone()]);
+            let a = Signal::parse("0 -> foo").unwrap();
+            let b = Signal::parse("foo -> bar").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("foo".to_string(), a);
@@ -218,9 +318,9 @@ mod test {
 
         #[test]
         fn test_processing_signal_not() {
-            let a = Signal::parse("0 -> a");
-            let b = Signal::parse("NOT a -> b");
-            let g = GraphCircuit::new(&vec![a.clone(), b.clone()]);
+            let a = Signal::parse("0 -> a").unwrap();
+            let b = Signal::parse("NOT a -> b").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), a);
@@ -231,14 +331,10 @@ mod test {
 
         #[test]
         fn test_processing_signal_and() {
-            let a = Signal::parse("0 -> a");
-            let b = Signal::parse("1 -> b");
-            let c = Signal::parse("a AND b -> c");
-            let g = GraphCircuit::new(&vec![
-                a.clone(),
-                b.clone(),
-                c.clone(),
-            ]);
+            let a = Signal::parse("0 -> a").unwrap();
+            let b = Signal::parse("1 -> b").unwrap();
+            let c = Signal::parse("a AND b -> c").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone(), c.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), a);
@@ -247,12 +343,9 @@ mod test {
 
             assert_eq!(expected, g.signals);
 
-            let a = Signal::parse("0 -> a");
-            let b = Signal::parse("1 AND a -> b");
-            let g = GraphCircuit::new(&vec![
-                a.clone(),
-                b.clone(),
-            ]);
+            let a = Signal::parse("0 -> a").unwrap();
+            let b = Signal::parse("1 AND a -> b").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), a);
@@ -263,14 +356,10 @@ mod test {
 
         #[test]
         fn test_processing_signal_or() {
-            let a = Signal::parse("0 -> a");
-            let b = Signal::parse("1 -> b");
-            let c = Signal::parse("a OR b -> c");
-            let g = GraphCircuit::new(&vec![
-                a.clone(),
-                b.clone(),
-                c.clone(),
-            ]);
+            let a = Signal::parse("0 -> a").unwrap();
+            let b = Signal::parse("1 -> b").unwrap();
+            let c = Signal::parse("a OR b -> c").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone(), c.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), a);
@@ -282,14 +371,10 @@ mod test {
 
         #[test]
         fn test_processing_signal_xor() {
-            let a = Signal::parse("0 -> a");
-            let b = Signal::parse("1 -> b");
-            let c = Signal::parse("a XOR b -> c");
-            let g = GraphCircuit::new(&vec![
-                a.clone(),
-                b.clone(),
-                c.clone(),
-            ]);
+            let a = Signal::parse("0 -> a").unwrap();
+            let b = Signal::parse("1 -> b").unwrap();
+            let c = Signal::parse("a XOR b -> c").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone(), c.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), a);
@@ -301,12 +386,9 @@ mod test {
 
         #[test]
         fn test_processing_signal_lshift() {
-            let a = Signal::parse("1 -> a");
-            let b = Signal::parse("a LSHIFT 2 -> b");
-            let g = GraphCircuit::new(&vec![
-                a.clone(),
-                b.clone(),
-            ]);
+            let a = Signal::parse("1 -> a").unwrap();
+            let b = Signal::parse("a LSHIFT 2 -> b").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), a);
@@ -317,12 +399,9 @@ mod test {
 
         #[test]
         fn test_processing_signal_rshift() {
-            let a = Signal::parse("1 -> a");
-            let b = Signal::parse("a RSHIFT 2 -> b");
-            let g = GraphCircuit::new(&vec![
-                a.clone(),
-                b.clone(),
-            ]);
+            let a = Signal::parse("1 -> a").unwrap();
+            let b = Signal::parse("a RSHIFT 2 -> b").unwrap();
+            let g = GraphCircuit::new(&[a.clone(), b.clone()]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), a);
@@ -332,45 +411,108 @@ mod test {
         }
     }
 
+    mod graph_circuit_get {
+        use super::super::*;
+
+        fn circuit(schematic: &[&str]) -> Vec<Signal> {
+            schematic
+                .iter()
+                .map(|raw| Signal::parse(raw).unwrap())
+                .collect()
+        }
+
+        #[test]
+        fn test_matches_simple_circuit() {
+            let schematic = circuit(&[
+                "123 -> x",
+                "456 -> y",
+                "x AND y -> d",
+                "x OR y -> e",
+                "x XOR y -> f",
+                "x LSHIFT 2 -> g",
+                "y RSHIFT 2 -> h",
+                "NOT x -> i",
+                "NOT y -> j",
+                "1 AND y -> k",
+                "k -> l",
+            ]);
+            let g = GraphCircuit::new(&schematic);
+            let s = SimpleCircuit::new(&schematic);
+
+            for wire in ["x", "y", "d", "e", "f", "g", "h", "i", "j", "k", "l"] {
+                assert_eq!(g.get(wire), s.get(wire), "wire {}", wire);
+            }
+            assert_eq!(g.get("i"), Some(65412));
+        }
+
+        #[test]
+        fn test_memoized() {
+            let g = GraphCircuit::new(&circuit(&["b -> a", "c AND d -> b", "3 -> c", "5 -> d"]));
+
+            assert_eq!(g.get("a"), Some(1));
+            assert_eq!(g.cache.borrow().len(), 4);
+            assert_eq!(g.get("d"), Some(5));
+        }
+
+        #[test]
+        fn test_unresolvable() {
+            let g = GraphCircuit::new(&circuit(&["b -> a", "a OR c -> b", "1 -> c", "z -> y"]));
+
+            assert_eq!(g.get("a"), None);
+            assert_eq!(g.get("y"), None);
+            assert_eq!(g.get("c"), Some(1));
+            assert_eq!(g.get("missing"), None);
+        }
+    }
+
     mod simple_circuit {
         use super::super::*;
 
         #[test]
         fn test_processing_signal_value() {
-            let c = SimpleCircuit::new(&vec![Signal::parse("0 -> foo"), Signal::parse("1 -> bar")]);
+            let c = SimpleCircuit::new(&[
+                Signal::parse("0 -> foo").unwrap(),
+                Signal::parse("1 -> bar").unwrap(),
+            ]);
 
             let mut expected = HashMap::new();
             expected.insert("foo".to_string(), 0);
             expected.insert("bar".to_string(), 1);
 
-            assert_eq!(expected, c.signals);
+            assert_eq!(expected, c.values());
 
-            let c = SimpleCircuit::new(&vec![Signal::parse("0 -> foo"), Signal::parse("foo -> bar")]);
+            let c = SimpleCircuit::new(&[
+                Signal::parse("0 -> foo").unwrap(),
+                Signal::parse("foo -> bar").unwrap(),
+            ]);
 
             let mut expected = HashMap::new();
             expected.insert("foo".to_string(), 0);
             expected.insert("bar".to_string(), 0);
 
-            assert_eq!(expected, c.signals);
+            assert_eq!(expected, c.values());
         }
 
         #[test]
         fn test_processing_signal_not() {
-            let c = SimpleCircuit::new(&vec![Signal::parse("0 -> a"), Signal::parse("NOT a -> b")]);
+            let c = SimpleCircuit::new(&[
+                Signal::parse("0 -> a").unwrap(),
+                Signal::parse("NOT a -> b").unwrap(),
+            ]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), 0);
             expected.insert("b".to_string(), 65535);
 
-            assert_eq!(expected, c.signals)
+            assert_eq!(expected, c.values())
         }
 
         #[test]
         fn test_processing_signal_and() {
-            let c = SimpleCircuit::new(&vec![
-                Signal::parse("0 -> a"),
-                Signal::parse("1 -> b"),
-                Signal::parse("a AND b -> c"),
+            let c = SimpleCircuit::new(&[
+                Signal::parse("0 -> a").unwrap(),
+                Signal::parse("1 -> b").unwrap(),
+                Signal::parse("a AND b -> c").unwrap(),
             ]);
 
             let mut expected = HashMap::new();
@@ -378,26 +520,26 @@ mod test {
             expected.insert("b".to_string(), 1);
             expected.insert("c".to_string(), 0);
 
-            assert_eq!(expected, c.signals);
+            assert_eq!(expected, c.values());
 
-            let c = SimpleCircuit::new(&vec![
-                Signal::parse("0 -> a"),
-                Signal::parse("1 AND a -> b"),
+            let c = SimpleCircuit::new(&[
+                Signal::parse("0 -> a").unwrap(),
+                Signal::parse("1 AND a -> b").unwrap(),
             ]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), 0);
             expected.insert("b".to_string(), 0);
 
-            assert_eq!(expected, c.signals);
+            assert_eq!(expected, c.values());
         }
 
         #[test]
         fn test_processing_signal_or() {
-            let c = SimpleCircuit::new(&vec![
-                Signal::parse("0 -> a"),
-                Signal::parse("1 -> b"),
-                Signal::parse("a OR b -> c"),
+            let c = SimpleCircuit::new(&[
+                Signal::parse("0 -> a").unwrap(),
+                Signal::parse("1 -> b").unwrap(),
+                Signal::parse("a OR b -> c").unwrap(),
             ]);
 
             let mut expected = HashMap::new();
@@ -405,15 +547,15 @@ mod test {
             expected.insert("b".to_string(), 1);
             expected.insert("c".to_string(), 1);
 
-            assert_eq!(expected, c.signals)
+            assert_eq!(expected, c.values())
         }
 
         #[test]
         fn test_processing_signal_xor() {
-            let c = SimpleCircuit::new(&vec![
-                Signal::parse("0 -> a"),
-                Signal::parse("1 -> b"),
-                Signal::parse("a XOR b -> c"),
+            let c = SimpleCircuit::new(&[
+                Signal::parse("0 -> a").unwrap(),
+                Signal::parse("1 -> b").unwrap(),
+                Signal::parse("a XOR b -> c").unwrap(),
             ]);
 
             let mut expected = HashMap::new();
@@ -421,43 +563,313 @@ mod test {
             expected.insert("b".to_string(), 1);
             expected.insert("c".to_string(), 1);
 
-            assert_eq!(expected, c.signals)
+            assert_eq!(expected, c.values())
         }
 
         #[test]
         fn test_processing_signal_lshift() {
-            let c = SimpleCircuit::new(&vec![
-                Signal::parse("1 -> a"),
-                Signal::parse("a LSHIFT 2 -> b"),
+            let c = SimpleCircuit::new(&[
+                Signal::parse("1 -> a").unwrap(),
+                Signal::parse("a LSHIFT 2 -> b").unwrap(),
             ]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), 1);
             expected.insert("b".to_string(), 4);
 
-            assert_eq!(expected, c.signals)
+            assert_eq!(expected, c.values())
         }
 
         #[test]
         fn test_processing_signal_rshift() {
-            let c = SimpleCircuit::new(&vec![
-                Signal::parse("1 -> a"),
-                Signal::parse("a RSHIFT 2 -> b"),
+            let c = SimpleCircuit::new(&[
+                Signal::parse("1 -> a").unwrap(),
+                Signal::parse("a RSHIFT 2 -> b").unwrap(),
             ]);
 
             let mut expected = HashMap::new();
             expected.insert("a".to_string(), 1);
             expected.insert("b".to_string(), 0);
 
-            assert_eq!(expected, c.signals)
+            assert_eq!(expected, c.values())
+        }
+
+        #[test]
+        fn test_resolution_order() {
+            let c = SimpleCircuit::new(&[
+                Signal::parse("b LSHIFT 2 -> c").unwrap(),
+                Signal::parse("a -> b").unwrap(),
+                Signal::parse("3 -> a").unwrap(),
+                Signal::parse("x AND c -> y").unwrap(),
+                Signal::parse("y -> x").unwrap(),
+            ]);
+
+            assert_eq!(c.get("c"), Some(12));
+            assert_eq!(c.get("x"), None);
+            assert_eq!(c.get("y"), None);
         }
 
         #[test]
         fn test_get_signal() {
-            let c = SimpleCircuit::new(&vec![Signal::parse("1 -> a")]);
+            let c = SimpleCircuit::new(&[Signal::parse("1 -> a").unwrap()]);
 
             let expected = Some(1_u16);
             assert_eq!(expected, c.get("a"))
         }
+
+        #[test]
+        fn test_build() {
+            let c = SimpleCircuit::build(&[Signal::parse("1 -> a").unwrap()]).unwrap();
+            assert_eq!(c.get("a"), Some(1));
+
+            let report = SimpleCircuit::build(&[Signal::parse("b -> a").unwrap()])
+                .err()
+                .unwrap();
+            assert_eq!(report.unresolved.len(), 2);
+        }
+    }
+
+    mod operands {
+        use super::super::*;
+
+        /// Every form the signal regular expressions accept, with `w` = 12 and `v` = 10, alongside its value
+        const FORMS: [(&str, u16); 21] = [
+            ("7 -> o", 7),
+            ("w -> o", 12),
+            ("NOT 0 -> o", 65535),
+            ("NOT w -> o", 65523),
+            ("w AND v -> o", 8),
+            ("3 AND v -> o", 2),
+            ("w AND 5 -> o", 4),
+            ("6 AND 3 -> o", 2),
+            ("w OR v -> o", 14),
+            ("1 OR v -> o", 11),
+            ("w OR 1 -> o", 13),
+            ("6 OR 3 -> o", 7),
+            ("w XOR v -> o", 6),
+            ("1 XOR v -> o", 11),
+            ("w XOR 4 -> o", 8),
+            ("6 XOR 3 -> o", 5),
+            ("w LSHIFT 2 -> o", 48),
+            ("3 LSHIFT 1 -> o", 6),
+            ("w RSHIFT 2 -> o", 3),
+            ("1 RSHIFT 0 -> o", 1),
+            ("w RSHIFT 16 -> o", 0),
+        ];
+
+        fn schematic(form: &str) -> Vec<Signal> {
+            ["12 -> w", "10 -> v", form]
+                .iter()
+                .map(|raw| Signal::parse(raw).unwrap())
+                .collect()
+        }
+
+        #[test]
+        fn test_simple_circuit() {
+            for (form, value) in FORMS {
+                assert_eq!(
+                    SimpleCircuit::new(&schematic(form)).get("o"),
+                    Some(value),
+                    "{}",
+                    form
+                );
+            }
+        }
+
+        #[test]
+        fn test_graph_circuit() {
+            for (form, value) in FORMS {
+                assert_eq!(
+                    GraphCircuit::new(&schematic(form)).get("o"),
+                    Some(value),
+                    "{}",
+                    form
+                );
+            }
+        }
+
+        #[test]
+        fn test_shift_overflow() {
+            let c = SimpleCircuit::new(&schematic("w LSHIFT 20 -> o"));
+            assert_eq!(c.get("o"), Some(0));
+        }
+    }
+
+    mod overrides {
+        use super::super::*;
+        use crate::incremental::IncrementalCircuit;
+
+        fn circuit<C: Circuit>() -> C {
+            let schematic: Vec<Signal> = [
+                "b AND c -> a",
+                "3 -> b",
+                "d -> c",
+                "6 -> d",
+                "NOT e -> f",
+                "7 -> e",
+            ]
+            .iter()
+            .map(|raw| Signal::parse(raw).unwrap())
+            .collect();
+
+            C::new(&schematic)
+        }
+
+        fn change(wire: &str, before: Option<u16>, after: Option<u16>) -> Change {
+            Change {
+                wire: wire.to_string(),
+                before,
+                after,
+            }
+        }
+
+        fn check_set<C: Circuit>() {
+            let mut c: C = circuit();
+            assert_eq!(c.get("a"), Some(2));
+
+            let diff = c.set("d", 7);
+            assert_eq!(
+                diff,
+                vec![
+                    change("a", Some(2), Some(3)),
+                    change("c", Some(6), Some(7)),
+                    change("d", Some(6), Some(7)),
+                ]
+            );
+            assert_eq!(c.get("a"), Some(3));
+            assert_eq!(c.get("f"), Some(65528));
+
+            assert_eq!(c.set("d", 7), vec![]);
+            assert_eq!(
+                c.set("b", 1),
+                vec![change("a", Some(3), Some(1)), change("b", Some(3), Some(1))]
+            );
+        }
+
+        fn check_replace<C: Circuit>() {
+            let mut c: C = circuit();
+
+            let diff = c.replace(Signal::parse("a OR e -> d").unwrap());
+            assert_eq!(
+                diff,
+                vec![
+                    change("a", Some(2), None),
+                    change("c", Some(6), None),
+                    change("d", Some(6), None),
+                ]
+            );
+            assert_eq!(c.get("f"), Some(65528));
+
+            c.replace(Signal::parse("e -> d").unwrap());
+            assert_eq!(c.get("a"), Some(3));
+
+            assert_eq!(c.set("g", 1), vec![change("g", None, Some(1))]);
+        }
+
+        #[test]
+        fn test_simple_circuit() {
+            check_set::<SimpleCircuit>();
+            check_replace::<SimpleCircuit>();
+        }
+
+        #[test]
+        fn test_simple_circuit_matches_compiled() {
+            let mut c: SimpleCircuit = circuit();
+            let replacements = [
+                "a OR e -> d",
+                "f -> e",
+                "1 -> e",
+                "h AND b -> g",
+                "g -> h",
+                "d -> b",
+                "2 -> d",
+                "NOT g -> b",
+            ];
+
+            for raw in replacements {
+                c.replace(Signal::parse(raw).unwrap());
+
+                let schematic: Vec<Signal> = c.drivers.values().cloned().collect();
+                assert_eq!(c.values(), SimpleCircuit::new(&schematic).values(), "{}", raw);
+            }
+        }
+
+        #[test]
+        fn test_graph_circuit() {
+            check_set::<GraphCircuit>();
+            check_replace::<GraphCircuit>();
+        }
+
+        #[test]
+        fn test_incremental_circuit() {
+            check_set::<IncrementalCircuit>();
+            check_replace::<IncrementalCircuit>();
+        }
+    }
+
+    mod widths {
+        use super::super::*;
+        use crate::incremental::IncrementalCircuit;
+        use crate::word::Masked;
+
+        fn schematic<W: Word>() -> Vec<Signal<W>> {
+            [
+                "200 -> x",
+                "3 -> y",
+                "NOT x -> n",
+                "x LSHIFT 3 -> l",
+                "x RSHIFT 3 -> r",
+                "x OR y -> o",
+            ]
+            .iter()
+            .map(|raw| raw.parse().unwrap())
+            .collect()
+        }
+
+        fn values<W: Word, C: Circuit<W>>() -> Vec<Option<W>> {
+            let c = C::new(&schematic());
+            ["n", "l", "r", "o"]
+                .iter()
+                .map(|wire| c.get(wire))
+                .collect()
+        }
+
+        #[test]
+        fn test_u8() {
+            let expected = vec![Some(55), Some(64), Some(25), Some(203)];
+
+            assert_eq!(values::<u8, SimpleCircuit<u8>>(), expected);
+            assert_eq!(values::<u8, GraphCircuit<u8>>(), expected);
+            assert_eq!(values::<u8, IncrementalCircuit<u8>>(), expected);
+        }
+
+        #[test]
+        fn test_u32() {
+            let expected = vec![Some(4294967095), Some(1600), Some(25), Some(203)];
+
+            assert_eq!(values::<u32, SimpleCircuit<u32>>(), expected);
+        }
+
+        #[test]
+        fn test_masked() {
+            let expected = [55, 64, 25, 203].map(|v| Some(Masked::<8>::truncate(v)));
+            assert_eq!(values::<Masked<8>, SimpleCircuit<Masked<8>>>(), expected);
+
+            let expected = [823, 576, 25, 203].map(|v| Some(Masked::<10>::truncate(v)));
+            assert_eq!(values::<Masked<10>, GraphCircuit<Masked<10>>>(), expected);
+        }
+
+        #[test]
+        fn test_literal_too_wide() {
+            let error = "a OR 256 -> b".parse::<Signal<u8>>().unwrap_err();
+            assert_eq!(
+                (error.column, error.expected.as_str()),
+                (6, "a wire ID or a 8-bit value")
+            );
+
+            let error = "1024 -> b".parse::<Signal<Masked<10>>>().unwrap_err();
+            assert_eq!(error.expected, "a wire ID or a 10-bit value");
+            assert!("1023 -> b".parse::<Signal<Masked<10>>>().is_ok());
+        }
     }
 }
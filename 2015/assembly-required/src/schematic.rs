@@ -0,0 +1,146 @@
+use crate::signal::{Component, Signal};
+use std::fmt::{self, Write};
+
+/// Orders wires the way the puzzle names them, shorter names first so `z` comes before `aa`
+fn wire_order<W>(signal: &Signal<W>) -> (usize, &str) {
+    match signal.output() {
+        Component::ID(id) => (id.len(), id.as_str()),
+        Component::VALUE(_) => (0, ""),
+    }
+}
+
+/// The signals ordered by the wire they drive
+fn sorted<W>(schematic: &[Signal<W>]) -> Vec<&Signal<W>> {
+    let mut sorted: Vec<&Signal<W>> = schematic.iter().collect();
+    sorted.sort_by(|a, b| wire_order(a).cmp(&wire_order(b)));
+    sorted
+}
+
+/// Writes the schematic back out in the puzzle syntax, one signal per line ordered by the wire it drives
+///
+/// # Examples
+///
+/// ```rust
+/// # use assembly_required::schematic::format;
+/// # use assembly_required::signal::Signal;
+/// let schematic = ["x AND y -> aa", "123 -> x", "NOT x -> y"].map(|raw| Signal::parse(raw).unwrap());
+/// assert_eq!(format(&schematic), "123 -> x\nNOT x -> y\nx AND y -> aa\n");
+/// ```
+pub fn format<W: fmt::Display>(schematic: &[Signal<W>]) -> String {
+    sorted(schematic)
+        .iter()
+        .map(|signal| format!("{}\n", signal))
+        .collect()
+}
+
+/// Draws the schematic as a Graphviz DOT graph, with an ellipse for each wire, a box for each gate and plain text
+/// for each literal value
+pub fn to_dot<W: fmt::Display>(schematic: &[Signal<W>]) -> String {
+    let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+    let node = |dot: &mut String, id: &str, component: &Component<W>| -> String {
+        match component {
+            Component::ID(wire) => format!("{:?}", wire),
+            Component::VALUE(value) => {
+                let literal = format!("\"{}\"", id);
+                writeln!(
+                    dot,
+                    "    {} [shape=plaintext, label=\"{}\"];",
+                    literal, value
+                )
+                .unwrap();
+                literal
+            }
+        }
+    };
+
+    for (i, signal) in sorted(schematic).into_iter().enumerate() {
+        let output = match signal.output() {
+            Component::ID(wire) => format!("{:?}", wire),
+            Component::VALUE(_) => continue,
+        };
+        writeln!(dot, "    {} [shape=ellipse];", output).unwrap();
+
+        let operator = match signal {
+            Signal::VALUE(_, _) => None,
+            Signal::NOT(_, _) => Some("NOT"),
+            Signal::AND(_, _, _) => Some("AND"),
+            Signal::OR(_, _, _) => Some("OR"),
+            Signal::XOR(_, _, _) => Some("XOR"),
+            Signal::LSHIFT(_, _, _) => Some("LSHIFT"),
+            Signal::RSHIFT(_, _, _) => Some("RSHIFT"),
+        };
+        let inputs: Vec<String> = signal
+            .inputs()
+            .into_iter()
+            .enumerate()
+            .map(|(n, input)| node(&mut dot, &format!("v{}_{}", i, n), input))
+            .collect();
+
+        match operator {
+            Some(operator) => {
+                let gate = format!("\"g{}\"", i);
+                writeln!(dot, "    {} [shape=box, label=\"{}\"];", gate, operator).unwrap();
+
+                for input in inputs {
+                    writeln!(dot, "    {} -> {};", input, gate).unwrap();
+                }
+                writeln!(dot, "    {} -> {};", gate, output).unwrap();
+            }
+            None => {
+                for input in inputs {
+                    writeln!(dot, "    {} -> {};", input, output).unwrap();
+                }
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod test {
+    mod schematic {
+        use super::super::*;
+
+        fn parse(raw: &str) -> Vec<Signal> {
+            raw.lines()
+                .map(|line| Signal::parse(line).unwrap())
+                .collect()
+        }
+
+        #[test]
+        fn test_format_round_trip() {
+            let raw = "NOT b -> ab\n1 OR aa -> z\nc LSHIFT 2 -> b\n44 -> c\nb -> aa\n";
+            let formatted = format(&parse(raw));
+
+            assert_eq!(
+                formatted,
+                "c LSHIFT 2 -> b\n44 -> c\n1 OR aa -> z\nb -> aa\nNOT b -> ab\n"
+            );
+            assert_eq!(format(&parse(&formatted)), formatted);
+        }
+
+        #[test]
+        fn test_to_dot() {
+            let dot = to_dot(&parse("x AND 3 -> y\n5 -> x\nx -> z"));
+
+            assert_eq!(
+                dot,
+                "digraph circuit {\n    rankdir=LR;\n    \
+                     \"x\" [shape=ellipse];\n    \
+                     \"v0_0\" [shape=plaintext, label=\"5\"];\n    \
+                     \"v0_0\" -> \"x\";\n    \
+                     \"y\" [shape=ellipse];\n    \
+                     \"v1_1\" [shape=plaintext, label=\"3\"];\n    \
+                     \"g1\" [shape=box, label=\"AND\"];\n    \
+                     \"x\" -> \"g1\";\n    \
+                     \"v1_1\" -> \"g1\";\n    \
+                     \"g1\" -> \"y\";\n    \
+                     \"z\" [shape=ellipse];\n    \
+                     \"x\" -> \"z\";\n\
+                 }\n"
+            );
+        }
+    }
+}
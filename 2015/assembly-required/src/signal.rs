@@ -1,144 +1,220 @@
-use regex::Regex;
+use crate::word::Word;
+use adventofcode::error::ParseError;
+use regex::{Captures, Regex};
+use std::fmt;
+use std::str::FromStr;
 
+/// A gate or value driving a wire, carrying words of type `W`
 #[derive(Clone, Debug, PartialEq)]
-pub enum Signal {
-    NOT(Component, Component),
-    AND(Component, Component, Component),
-    OR(Component, Component, Component),
-    XOR(Component, Component, Component),
-    LSHIFT(Component, Component, Component),
-    RSHIFT(Component, Component, Component),
-    VALUE(Component, Component),
+pub enum Signal<W = u16> {
+    NOT(Component<W>, Component<W>),
+    AND(Component<W>, Component<W>, Component<W>),
+    OR(Component<W>, Component<W>, Component<W>),
+    XOR(Component<W>, Component<W>, Component<W>),
+    LSHIFT(Component<W>, Component<W>, Component<W>),
+    RSHIFT(Component<W>, Component<W>, Component<W>),
+    VALUE(Component<W>, Component<W>),
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub enum Component {
+pub enum Component<W = u16> {
     ID(String),
-    VALUE(u16),
+    VALUE(W),
 }
 
 impl Signal {
-    pub fn parse(raw: &str) -> Signal {
+    /// Parses a signal carrying 16-bit words, use [`str::parse`] for any other width
+    pub fn parse(raw: &str) -> Result<Signal, ParseError> {
+        raw.parse()
+    }
+}
+
+impl<W: Word> FromStr for Signal<W> {
+    type Err = ParseError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
         lazy_static! {
-            static ref VALUE_RE: Regex = Regex::new(r"^([a-z0-9]+) -> ([a-z]+)$")
+            static ref VALUE_RE: Regex = Regex::new(r"^([a-z0-9]+) -> ([a-z0-9]+)$")
                 .expect("Could not compile value regular expression");
-            static ref NOT_GATE_RE: Regex = Regex::new(r"^NOT ([a-z0-9]+) -> ([a-z]+)$")
+            static ref NOT_GATE_RE: Regex = Regex::new(r"^NOT ([a-z0-9]+) -> ([a-z0-9]+)$")
                 .expect("Could not compile NOT gate regular expression");
             static ref AND_OR_XOR_RE: Regex =
-                Regex::new(r"^([a-z0-9]+) (AND|OR|XOR) ([a-z0-9]+) -> ([a-z]+)$")
+                Regex::new(r"^([a-z0-9]+) (AND|OR|XOR) ([a-z0-9]+) -> ([a-z0-9]+)$")
                     .expect("Could not compile AND, OR, XOR regular expression");
             static ref LSHIFT_RSHIFT_RE: Regex =
-                Regex::new(r"^([a-z0-9]+) (LSHIFT|RSHIFT) (\d+) -> ([a-z]+)$")
+                Regex::new(r"^([a-z0-9]+) (LSHIFT|RSHIFT) (\d+) -> ([a-z0-9]+)$")
                     .expect("Could not compile LSHIFT and RSHIFT regular expression");
         }
 
+        let component = |cap: &Captures, i: usize| {
+            let m = cap.get(i).expect("Could not find component");
+            m.as_str()
+                .parse::<Component<W>>()
+                .map_err(|e| e.offset(m.start()))
+        };
+        let wire = |cap: &Captures, i: usize| match component(cap, i)? {
+            Component::ID(id) => Ok(Component::ID(id)),
+            Component::VALUE(_) => {
+                let m = cap.get(i).expect("Could not find output wire ID");
+                Err(ParseError::new(m.start() + 1, m.as_str(), "a wire ID"))
+            }
+        };
+
         if let Some(cap) = VALUE_RE.captures(raw) {
-            return Signal::VALUE(
-                Component::parse(cap.get(2).expect("Could not find wire ID").as_str()),
-                Component::parse(cap.get(1).expect("Could not find value u16").as_str()),
-            );
+            return Ok(Signal::VALUE(wire(&cap, 2)?, component(&cap, 1)?));
         };
 
         if let Some(cap) = NOT_GATE_RE.captures(raw) {
-            return Signal::NOT(
-                Component::parse(cap.get(2).expect("Could not find wire ID").as_str()),
-                Component::parse(cap.get(1).expect("Could not find source wire ID").as_str()),
-            );
+            return Ok(Signal::NOT(wire(&cap, 2)?, component(&cap, 1)?));
         };
 
         if let Some(cap) = AND_OR_XOR_RE.captures(raw) {
-            match cap.get(2).expect("Could not find operation").as_str() {
-                "AND" => {
-                    return Signal::AND(
-                        Component::parse(
-                            cap.get(4).expect("Could not find output wire ID").as_str(),
-                        ),
-                        Component::parse(
-                            cap.get(1).expect("Could not find source wire ID").as_str(),
-                        ),
-                        Component::parse(
-                            cap.get(3).expect("Could not find source wire ID").as_str(),
-                        ),
-                    )
-                }
-                "OR" => {
-                    return Signal::OR(
-                        Component::parse(
-                            cap.get(4).expect("Could not find output wire ID").as_str(),
-                        ),
-                        Component::parse(
-                            cap.get(1).expect("Could not find source wire ID").as_str(),
-                        ),
-                        Component::parse(
-                            cap.get(3).expect("Could not find source wire ID").as_str(),
-                        ),
-                    )
-                }
-                "XOR" => {
-                    return Signal::XOR(
-                        Component::parse(
-                            cap.get(4).expect("Could not find output wire ID").as_str(),
-                        ),
-                        Component::parse(
-                            cap.get(1).expect("Could not find source wire ID").as_str(),
-                        ),
-                        Component::parse(
-                            cap.get(3).expect("Could not find source wire ID").as_str(),
-                        ),
-                    )
-                }
-                op => panic!("Encountered unexpected operation: {}", op),
-            }
+            let (id, a, b) = (wire(&cap, 4)?, component(&cap, 1)?, component(&cap, 3)?);
+
+            return Ok(
+                match cap.get(2).expect("Could not find operation").as_str() {
+                    "AND" => Signal::AND(id, a, b),
+                    "OR" => Signal::OR(id, a, b),
+                    _ => Signal::XOR(id, a, b),
+                },
+            );
         };
 
         if let Some(cap) = LSHIFT_RSHIFT_RE.captures(raw) {
-            match cap.get(2).expect("Could not find operation").as_str() {
-                "LSHIFT" => {
-                    return Signal::LSHIFT(
-                        Component::parse(
-                            cap.get(4).expect("Could not find output wire ID").as_str(),
-                        ),
-                        Component::parse(
-                            cap.get(1).expect("Could not find source wire ID").as_str(),
-                        ),
-                        Component::parse(cap.get(3).expect("Could not find value usize").as_str()),
-                    )
-                }
-                "RSHIFT" => {
-                    return Signal::RSHIFT(
-                        Component::parse(
-                            cap.get(4).expect("Could not find output wire ID").as_str(),
-                        ),
-                        Component::parse(
-                            cap.get(1).expect("Could not find source wire ID").as_str(),
-                        ),
-                        Component::parse(cap.get(3).expect("Could not find value usize").as_str()),
-                    )
-                }
-                op => panic!("Encountered unexpected operation: {}", op),
-            }
+            let (id, input, places) = (wire(&cap, 4)?, component(&cap, 1)?, component(&cap, 3)?);
+
+            return Ok(
+                match cap.get(2).expect("Could not find operation").as_str() {
+                    "LSHIFT" => Signal::LSHIFT(id, input, places),
+                    _ => Signal::RSHIFT(id, input, places),
+                },
+            );
         };
 
-        panic!("Could not parse signal: {}", raw);
+        Err(match raw.find(" -> ") {
+            Some(arrow) => ParseError::new(
+                1,
+                &raw[..arrow],
+                "a value, NOT, AND, OR, XOR, LSHIFT or RSHIFT expression",
+            ),
+            None => ParseError::invalid(raw, "a signal of the form \"<expression> -> <wire>\""),
+        })
+    }
+}
+
+impl<W> Signal<W> {
+    /// The component driven by the signal
+    pub fn output(&self) -> &Component<W> {
+        match self {
+            Signal::NOT(output, _)
+            | Signal::VALUE(output, _)
+            | Signal::AND(output, _, _)
+            | Signal::OR(output, _, _)
+            | Signal::XOR(output, _, _)
+            | Signal::LSHIFT(output, _, _)
+            | Signal::RSHIFT(output, _, _) => output,
+        }
+    }
+
+    /// The components the signal reads from, in the order they appear in the schematic
+    pub fn inputs(&self) -> Vec<&Component<W>> {
+        match self {
+            Signal::NOT(_, a) | Signal::VALUE(_, a) => vec![a],
+            Signal::AND(_, a, b)
+            | Signal::OR(_, a, b)
+            | Signal::XOR(_, a, b)
+            | Signal::LSHIFT(_, a, b)
+            | Signal::RSHIFT(_, a, b) => vec![a, b],
+        }
+    }
+}
+
+impl<W: Clone> Signal<W> {
+    /// The same signal with each of its inputs replaced by the result of `f`
+    pub fn map_inputs(&self, mut f: impl FnMut(&Component<W>) -> Component<W>) -> Signal<W> {
+        match self {
+            Signal::VALUE(output, a) => Signal::VALUE(output.clone(), f(a)),
+            Signal::NOT(output, a) => Signal::NOT(output.clone(), f(a)),
+            Signal::AND(output, a, b) => Signal::AND(output.clone(), f(a), f(b)),
+            Signal::OR(output, a, b) => Signal::OR(output.clone(), f(a), f(b)),
+            Signal::XOR(output, a, b) => Signal::XOR(output.clone(), f(a), f(b)),
+            Signal::LSHIFT(output, a, b) => Signal::LSHIFT(output.clone(), f(a), f(b)),
+            Signal::RSHIFT(output, a, b) => Signal::RSHIFT(output.clone(), f(a), f(b)),
+        }
+    }
+}
+
+impl<W: fmt::Display> fmt::Display for Signal<W> {
+    /// Formats the signal the way it is written in a schematic
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Signal::VALUE(output, a) => write!(f, "{} -> {}", a, output),
+            Signal::NOT(output, a) => write!(f, "NOT {} -> {}", a, output),
+            Signal::AND(output, a, b) => write!(f, "{} AND {} -> {}", a, b, output),
+            Signal::OR(output, a, b) => write!(f, "{} OR {} -> {}", a, b, output),
+            Signal::XOR(output, a, b) => write!(f, "{} XOR {} -> {}", a, b, output),
+            Signal::LSHIFT(output, a, b) => write!(f, "{} LSHIFT {} -> {}", a, b, output),
+            Signal::RSHIFT(output, a, b) => write!(f, "{} RSHIFT {} -> {}", a, b, output),
+        }
+    }
+}
+
+impl<W: Word> Signal<W> {
+    /// Computes the value driven by the signal, looking up the value of any wire it reads from with `wire`.
+    /// Literals and wires are accepted in every operand position.
+    pub fn evaluate(&self, mut wire: impl FnMut(&str) -> Option<W>) -> Option<W> {
+        let mut operand = |component: &Component<W>| match component {
+            Component::VALUE(value) => Some(*value),
+            Component::ID(id) => wire(id),
+        };
+
+        match self {
+            Signal::VALUE(_, a) => operand(a),
+            Signal::NOT(_, a) => operand(a).map(W::not),
+            Signal::AND(_, a, b) => Some(operand(a)?.and(operand(b)?)),
+            Signal::OR(_, a, b) => Some(operand(a)?.or(operand(b)?)),
+            Signal::XOR(_, a, b) => Some(operand(a)?.xor(operand(b)?)),
+            Signal::LSHIFT(_, a, b) => Some(operand(a)?.shl(operand(b)?)),
+            Signal::RSHIFT(_, a, b) => Some(operand(a)?.shr(operand(b)?)),
+        }
+    }
+}
+
+impl<W: fmt::Display> fmt::Display for Component<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Component::ID(id) => write!(f, "{}", id),
+            Component::VALUE(value) => write!(f, "{}", value),
+        }
     }
 }
 
 impl Component {
-    fn parse(raw: &str) -> Component {
+    /// Parses a wire ID or a 16-bit value, use [`str::parse`] for any other width
+    pub fn parse(raw: &str) -> Result<Component, ParseError> {
+        raw.parse()
+    }
+}
+
+impl<W: Word> FromStr for Component<W> {
+    type Err = ParseError;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
         lazy_static! {
-            static ref COMPONENT_RE: Regex =
-                Regex::new(r"[a-z0-9]+").expect("Could not compile component regular expression");
+            static ref ID_RE: Regex =
+                Regex::new(r"^[a-z]+$").expect("Could not compile ID regular expression");
         }
 
-        if let Some(caps) = COMPONENT_RE.captures(raw) {
-            let cap = caps.get(0).expect("Could not match ID or VALUE").as_str();
-            if let Ok(value) = cap.parse::<u16>() {
-                Component::VALUE(value)
-            } else {
-                Component::ID(cap.to_string())
-            }
+        if ID_RE.is_match(raw) {
+            Ok(Component::ID(raw.to_string()))
         } else {
-            panic!("Failed to match ID or VALUE");
+            raw.parse::<u64>()
+                .ok()
+                .and_then(W::from_u64)
+                .map(Component::VALUE)
+                .ok_or_else(|| {
+                    ParseError::invalid(raw, format!("a wire ID or a {}-bit value", W::BITS))
+                })
         }
     }
 }
@@ -150,148 +226,185 @@ mod test {
 
     #[test]
     fn test_parse_value() {
-        let result = Signal::parse("0 -> foo");
-        let expected = Signal::VALUE(Component::parse("foo"), Component::parse("0"));
+        let result = Signal::parse("0 -> foo").unwrap();
+        let expected = Signal::VALUE(Component::parse("foo").unwrap(), Component::parse("0").unwrap());
         assert_eq!(expected, result);
 
-        let result = Signal::parse("1 -> bar");
-        let expected = Signal::VALUE(Component::parse("bar"), Component::parse("1"));
+        let result = Signal::parse("1 -> bar").unwrap();
+        let expected = Signal::VALUE(Component::parse("bar").unwrap(), Component::parse("1").unwrap());
         assert_eq!(expected, result);
 
-        let result = Signal::parse("foo -> bar");
-        let expected = Signal::VALUE(Component::parse("bar"), Component::parse("foo"));
+        let result = Signal::parse("foo -> bar").unwrap();
+        let expected = Signal::VALUE(Component::parse("bar").unwrap(), Component::parse("foo").unwrap());
         assert_eq!(expected, result);
     }
 
     #[test]
     fn test_parse_not() {
-        let result = Signal::parse("NOT foo -> bar");
-        let expected = Signal::NOT(Component::parse("bar"), Component::parse("foo"));
+        let result = Signal::parse("NOT foo -> bar").unwrap();
+        let expected = Signal::NOT(Component::parse("bar").unwrap(), Component::parse("foo").unwrap());
         assert_eq!(expected, result);
 
-        let result = Signal::parse("NOT 1 -> bar");
-        let expected = Signal::NOT(Component::parse("bar"), Component::parse("1"));
+        let result = Signal::parse("NOT 1 -> bar").unwrap();
+        let expected = Signal::NOT(Component::parse("bar").unwrap(), Component::parse("1").unwrap());
         assert_eq!(expected, result);
     }
 
     #[test]
     fn test_parse_and() {
-        let result = Signal::parse("a AND b -> c");
+        let result = Signal::parse("a AND b -> c").unwrap();
         let expected = Signal::AND(
-            Component::parse("c"),
-            Component::parse("a"),
-            Component::parse("b"),
+            Component::parse("c").unwrap(),
+            Component::parse("a").unwrap(),
+            Component::parse("b").unwrap(),
         );
         assert_eq!(expected, result);
 
-        let result = Signal::parse("1 AND b -> c");
+        let result = Signal::parse("1 AND b -> c").unwrap();
         let expected = Signal::AND(
-            Component::parse("c"),
-            Component::parse("1"),
-            Component::parse("b"),
+            Component::parse("c").unwrap(),
+            Component::parse("1").unwrap(),
+            Component::parse("b").unwrap(),
         );
         assert_eq!(expected, result);
 
-        let result = Signal::parse("a AND 1 -> c");
+        let result = Signal::parse("a AND 1 -> c").unwrap();
         let expected = Signal::AND(
-            Component::parse("c"),
-            Component::parse("a"),
-            Component::parse("1"),
+            Component::parse("c").unwrap(),
+            Component::parse("a").unwrap(),
+            Component::parse("1").unwrap(),
         );
         assert_eq!(expected, result);
     }
 
     #[test]
     fn test_parse_or() {
-        let result = Signal::parse("a OR b -> c");
+        let result = Signal::parse("a OR b -> c").unwrap();
         let expected = Signal::OR(
-            Component::parse("c"),
-            Component::parse("a"),
-            Component::parse("b"),
+            Component::parse("c").unwrap(),
+            Component::parse("a").unwrap(),
+            Component::parse("b").unwrap(),
         );
         assert_eq!(expected, result);
 
-        let result = Signal::parse("1 OR b -> c");
+        let result = Signal::parse("1 OR b -> c").unwrap();
         let expected = Signal::OR(
-            Component::parse("c"),
-            Component::parse("1"),
-            Component::parse("b"),
+            Component::parse("c").unwrap(),
+            Component::parse("1").unwrap(),
+            Component::parse("b").unwrap(),
         );
         assert_eq!(expected, result);
 
-        let result = Signal::parse("a OR 1 -> c");
+        let result = Signal::parse("a OR 1 -> c").unwrap();
         let expected = Signal::OR(
-            Component::parse("c"),
-            Component::parse("a"),
-            Component::parse("1"),
+            Component::parse("c").unwrap(),
+            Component::parse("a").unwrap(),
+            Component::parse("1").unwrap(),
         );
         assert_eq!(expected, result);
     }
 
     #[test]
     fn test_parse_xor() {
-        let result = Signal::parse("a XOR b -> c");
+        let result = Signal::parse("a XOR b -> c").unwrap();
         let expected = Signal::XOR(
-            Component::parse("c"),
-            Component::parse("a"),
-            Component::parse("b"),
+            Component::parse("c").unwrap(),
+            Component::parse("a").unwrap(),
+            Component::parse("b").unwrap(),
         );
         assert_eq!(expected, result);
 
-        let result = Signal::parse("1 XOR b -> c");
+        let result = Signal::parse("1 XOR b -> c").unwrap();
         let expected = Signal::XOR(
-            Component::parse("c"),
-            Component::parse("1"),
-            Component::parse("b"),
+            Component::parse("c").unwrap(),
+            Component::parse("1").unwrap(),
+            Component::parse("b").unwrap(),
         );
         assert_eq!(expected, result);
 
-        let result = Signal::parse("a XOR 1 -> c");
+        let result = Signal::parse("a XOR 1 -> c").unwrap();
         let expected = Signal::XOR(
-            Component::parse("c"),
-            Component::parse("a"),
-            Component::parse("1"),
+            Component::parse("c").unwrap(),
+            Component::parse("a").unwrap(),
+            Component::parse("1").unwrap(),
         );
         assert_eq!(expected, result);
     }
 
     #[test]
     fn test_parse_lshift() {
-        let result = Signal::parse("a LSHIFT 1 -> b");
+        let result = Signal::parse("a LSHIFT 1 -> b").unwrap();
         let expected = Signal::LSHIFT(
-            Component::parse("b"),
-            Component::parse("a"),
-            Component::parse("1"),
+            Component::parse("b").unwrap(),
+            Component::parse("a").unwrap(),
+            Component::parse("1").unwrap(),
         );
         assert_eq!(expected, result);
 
-        let result = Signal::parse("1 LSHIFT 1 -> b");
+        let result = Signal::parse("1 LSHIFT 1 -> b").unwrap();
         let expected = Signal::LSHIFT(
-            Component::parse("b"),
-            Component::parse("1"),
-            Component::parse("1"),
+            Component::parse("b").unwrap(),
+            Component::parse("1").unwrap(),
+            Component::parse("1").unwrap(),
         );
         assert_eq!(expected, result);
     }
 
     #[test]
     fn test_parse_rshift() {
-        let result = Signal::parse("a RSHIFT 1 -> b");
+        let result = Signal::parse("a RSHIFT 1 -> b").unwrap();
         let expected = Signal::RSHIFT(
-            Component::parse("b"),
-            Component::parse("a"),
-            Component::parse("1"),
+            Component::parse("b").unwrap(),
+            Component::parse("a").unwrap(),
+            Component::parse("1").unwrap(),
         );
         assert_eq!(expected, result);
 
-        let result = Signal::parse("1 RSHIFT 1 -> b");
+        let result = Signal::parse("1 RSHIFT 1 -> b").unwrap();
         let expected = Signal::RSHIFT(
-            Component::parse("b"),
-            Component::parse("1"),
-            Component::parse("1"),
+            Component::parse("b").unwrap(),
+            Component::parse("1").unwrap(),
+            Component::parse("1").unwrap(),
         );
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn test_parse_invalid() {
+        let error = Signal::parse("a NAND b -> c").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "a NAND b"));
+
+        let error = Signal::parse("a AND 70000 -> c").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (7, "70000"));
+
+        let error = Signal::parse("a AND b -> 1").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (12, "1"));
+
+        let error = Signal::parse("a AND b").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "a AND b"));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let forms = [
+            "0 -> foo",
+            "foo -> bar",
+            "NOT 1 -> b",
+            "NOT a -> b",
+            "a AND b -> c",
+            "1 AND b -> c",
+            "a OR 1 -> c",
+            "a XOR b -> c",
+            "a LSHIFT 2 -> b",
+            "1 RSHIFT 15 -> b",
+        ];
+
+        for raw in forms {
+            let signal = Signal::parse(raw).unwrap();
+            assert_eq!(signal.to_string(), raw);
+            assert_eq!(Signal::parse(&signal.to_string()), Ok(signal));
+        }
+    }
 }
 
 mod component {
@@ -299,7 +412,7 @@ mod component {
 
     #[test]
     fn test_parse_id() {
-        let result = Component::parse("foo");
+        let result = Component::parse("foo").unwrap();
         let expected = Component::ID("foo".to_string());
 
         assert_eq!(expected, result);
@@ -307,7 +420,7 @@ mod component {
 
     #[test]
     fn test_parse_value() {
-        let result = Component::parse("1");
+        let result = Component::parse("1").unwrap();
         let expected = Component::VALUE(1);
 
         assert_eq!(expected, result);
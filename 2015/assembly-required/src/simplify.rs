@@ -0,0 +1,489 @@
+use crate::signal::{Component, Signal};
+use crate::solve::BRUTE_FORCE_BITS;
+use crate::tape::Tape;
+use crate::word::Word;
+use adventofcode::graph::{bfs, Graph};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// The kind of rewrite made to a signal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// A wire with a constant value was replaced by that value where it is read
+    Constant,
+    /// A wire copying another wire was replaced by the wire it copies where it is read
+    Alias,
+    /// A gate reading only literal values was replaced by the value it computes
+    Fold,
+    /// A gate was replaced using an algebraic identity such as `x AND 0 = 0`
+    Identity,
+    /// A signal was removed as none of the requested outputs read from it
+    Dead,
+}
+
+/// A single rewrite of the signal driving a wire
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step<W = u16> {
+    pub wire: String,
+    pub rule: Rule,
+    pub before: Signal<W>,
+    /// The signal driving the wire after the rewrite, none when the signal was removed
+    pub after: Option<Signal<W>>,
+}
+
+/// The requested outputs of the original and simplified schematics with the undriven wires held at `inputs`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trial<W = u16> {
+    pub inputs: Vec<(String, W)>,
+    /// Each requested output along with its value in the original and the simplified schematic
+    pub outputs: Vec<(String, Option<W>, Option<W>)>,
+}
+
+/// Evidence the simplified schematic drives the requested outputs exactly as the original did. It only amounts to
+/// a proof when the trials are exhaustive, otherwise a sample of the assignments of the undriven wires was tried.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evidence<W = u16> {
+    /// Every rewrite in the order it was made, each of which leaves the value of every requested output alone
+    pub steps: Vec<Step<W>>,
+    /// Both schematics evaluated side by side, once per assignment of the undriven wires tried
+    pub trials: Vec<Trial<W>>,
+    /// Whether the trials cover every assignment of the undriven wires
+    pub exhaustive: bool,
+}
+
+impl<W: PartialEq> Evidence<W> {
+    /// Whether every output matched in every trial
+    pub fn holds(&self) -> bool {
+        self.trials.iter().all(|trial| {
+            trial
+                .outputs
+                .iter()
+                .all(|(_, original, simplified)| original == simplified)
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simplified<W = u16> {
+    pub schematic: Vec<Signal<W>>,
+    pub evidence: Evidence<W>,
+}
+
+/// Number of assignments of pseudo random values to the undriven wires tried, on top of all zeros and all ones,
+/// when there are too many to try them all
+const RANDOM_TRIALS: u64 = 8;
+
+/// Shrinks a schematic without changing the value of any of `outputs`. Constants are propagated and folded, copies
+/// of wires collapsed, gates simplified by algebraic identities and any signal the outputs do not read from dropped.
+pub fn simplify<W: Word>(schematic: &[Signal<W>], outputs: &[&str]) -> Simplified<W> {
+    let mut drivers = HashMap::new();
+    let mut dependencies = Graph::new();
+
+    for signal in schematic {
+        if let Component::ID(id) = signal.output() {
+            dependencies.add_node(id.clone());
+
+            for input in signal.inputs() {
+                if let Component::ID(input) = input {
+                    dependencies.add_edge(input.clone(), id.clone(), ());
+                }
+            }
+
+            drivers.insert(id.clone(), signal.clone());
+        }
+    }
+
+    // Wires fed by a loop are left as they are
+    let order = dependencies
+        .topological_sort()
+        .unwrap_or_else(|cycle| cycle.sorted);
+    let mut steps = vec![];
+    let mut constants = HashMap::<String, W>::new();
+    let mut aliases = HashMap::<String, String>::new();
+
+    for wire in order {
+        let mut signal = match drivers.get(&wire) {
+            Some(signal) => signal.clone(),
+            None => continue,
+        };
+        let mut rewrite = |signal: &mut Signal<W>, rule, after: Signal<W>| {
+            if *signal != after {
+                steps.push(Step {
+                    wire: wire.clone(),
+                    rule,
+                    before: signal.clone(),
+                    after: Some(after.clone()),
+                });
+                *signal = after;
+            }
+        };
+
+        let after = signal.map_inputs(|input| match input {
+            Component::ID(id) if constants.contains_key(id) => Component::VALUE(constants[id]),
+            input => input.clone(),
+        });
+        rewrite(&mut signal, Rule::Constant, after);
+
+        let after = signal.map_inputs(|input| match input {
+            Component::ID(id) if aliases.contains_key(id) => Component::ID(aliases[id].clone()),
+            input => input.clone(),
+        });
+        rewrite(&mut signal, Rule::Alias, after);
+
+        let output = signal.output().clone();
+        let literal = |component: &&Component<W>| matches!(component, Component::VALUE(_));
+
+        if !matches!(signal, Signal::VALUE(_, _)) && signal.inputs().iter().all(literal) {
+            let value = signal
+                .evaluate(|_| None)
+                .expect("Literals always have a value");
+            rewrite(
+                &mut signal,
+                Rule::Fold,
+                Signal::VALUE(output, Component::VALUE(value)),
+            );
+        } else if let Some(component) = identity(&signal, &drivers) {
+            rewrite(
+                &mut signal,
+                Rule::Identity,
+                Signal::VALUE(output, component),
+            );
+        }
+
+        match &signal {
+            Signal::VALUE(_, Component::VALUE(value)) => {
+                constants.insert(wire.clone(), *value);
+            }
+            Signal::VALUE(_, Component::ID(source)) => {
+                aliases.insert(wire.clone(), source.clone());
+            }
+            _ => {}
+        }
+
+        drivers.insert(wire, signal);
+    }
+
+    let inputs = |wire: &String| -> Vec<String> {
+        drivers
+            .get(wire)
+            .map(|signal| {
+                signal
+                    .inputs()
+                    .into_iter()
+                    .filter_map(|input| match input {
+                        Component::ID(id) => Some(id.clone()),
+                        Component::VALUE(_) => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let live: HashSet<String> = outputs
+        .iter()
+        .flat_map(|output| bfs(output.to_string(), inputs))
+        .collect();
+
+    let mut simplified = vec![];
+    let mut seen = HashSet::new();
+
+    for signal in schematic {
+        let wire = match signal.output() {
+            Component::ID(wire) => wire,
+            Component::VALUE(_) => continue,
+        };
+
+        if !seen.insert(wire) {
+            continue;
+        }
+
+        if live.contains(wire) {
+            simplified.push(drivers[wire].clone());
+        } else {
+            steps.push(Step {
+                wire: wire.clone(),
+                rule: Rule::Dead,
+                before: drivers[wire].clone(),
+                after: None,
+            });
+        }
+    }
+
+    let (trials, exhaustive) = trials(schematic, &simplified, outputs);
+
+    Simplified {
+        schematic: simplified,
+        evidence: Evidence {
+            steps,
+            trials,
+            exhaustive,
+        },
+    }
+}
+
+/// The component a gate always passes on, whatever the value of the wires it reads from
+fn identity<W: Word>(
+    signal: &Signal<W>,
+    drivers: &HashMap<String, Signal<W>>,
+) -> Option<Component<W>> {
+    let zero = Component::VALUE(W::truncate(0));
+    let ones = Component::VALUE(W::ones());
+    let shift_out = |places: &Component<W>| match places {
+        Component::VALUE(places) => places.to_u64() >= W::BITS as u64,
+        Component::ID(_) => false,
+    };
+
+    match signal {
+        Signal::NOT(_, Component::ID(id)) => match drivers.get(id) {
+            Some(Signal::NOT(_, inner)) => Some(inner.clone()),
+            _ => None,
+        },
+        Signal::AND(_, a, b) if *a == zero || *b == zero => Some(zero),
+        Signal::AND(_, a, b) | Signal::OR(_, a, b) if a == b => Some(a.clone()),
+        Signal::AND(_, a, b) if *a == ones => Some(b.clone()),
+        Signal::AND(_, a, b) if *b == ones => Some(a.clone()),
+        Signal::OR(_, a, b) if *a == ones || *b == ones => Some(ones),
+        Signal::OR(_, a, b) | Signal::XOR(_, a, b) if *a == zero => Some(b.clone()),
+        Signal::OR(_, a, b) | Signal::XOR(_, a, b) if *b == zero => Some(a.clone()),
+        Signal::XOR(_, a, b) if a == b => Some(zero),
+        Signal::LSHIFT(_, a, b) | Signal::RSHIFT(_, a, b) if *a == zero || shift_out(b) => {
+            Some(zero)
+        }
+        Signal::LSHIFT(_, a, b) | Signal::RSHIFT(_, a, b) if *b == zero => Some(a.clone()),
+        _ => None,
+    }
+}
+
+/// Evaluates both schematics with their undriven wires held at every possible value when they hold at most
+/// [`BRUTE_FORCE_BITS`] bits between them, otherwise at all zeros, all ones and a handful of pseudo random values.
+/// Returns the trials along with whether they were exhaustive.
+fn trials<W: Word>(
+    original: &[Signal<W>],
+    simplified: &[Signal<W>],
+    outputs: &[&str],
+) -> (Vec<Trial<W>>, bool) {
+    let wire = |component: &Component<W>| match component {
+        Component::ID(id) => Some(id.clone()),
+        Component::VALUE(_) => None,
+    };
+    let driven: HashSet<String> = original
+        .iter()
+        .filter_map(|signal| wire(signal.output()))
+        .collect();
+    let undriven: BTreeSet<String> = original
+        .iter()
+        .flat_map(|signal| signal.inputs())
+        .filter_map(wire)
+        .filter(|input| !driven.contains(input))
+        .collect();
+
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    let mut random = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        W::truncate(seed)
+    };
+
+    let bits = undriven.len() as u64 * W::BITS as u64;
+    let exhaustive = bits <= BRUTE_FORCE_BITS as u64;
+
+    let mut assignments = if exhaustive {
+        (0..1u64 << bits)
+            .map(|n| {
+                undriven
+                    .iter()
+                    .enumerate()
+                    .map(|(i, wire)| (wire.clone(), W::truncate(n >> (i as u32 * W::BITS))))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    } else {
+        vec![
+            undriven
+                .iter()
+                .map(|wire| (wire.clone(), W::truncate(0)))
+                .collect::<Vec<_>>(),
+            undriven
+                .iter()
+                .map(|wire| (wire.clone(), W::ones()))
+                .collect(),
+        ]
+    };
+    if !exhaustive {
+        for _ in 0..RANDOM_TRIALS {
+            assignments.push(
+                undriven
+                    .iter()
+                    .map(|wire| (wire.clone(), random()))
+                    .collect(),
+            );
+        }
+    }
+
+    // Each schematic is compiled once, with the undriven wires filled in before each run
+    let inputs: Vec<&str> = undriven.iter().map(String::as_str).collect();
+    let (original, simplified) = (
+        Tape::compile(original, &inputs),
+        Tape::compile(simplified, &inputs),
+    );
+    let evaluate = |tape: &Tape<W>, inputs: &[(String, W)]| {
+        let inputs: Vec<(&str, W)> = inputs
+            .iter()
+            .map(|(wire, value)| (wire.as_str(), *value))
+            .collect();
+        let registers = tape.evaluate(&inputs);
+
+        outputs
+            .iter()
+            .map(|output| registers[tape.register(output)?])
+            .collect::<Vec<_>>()
+    };
+
+    let trials = assignments
+        .into_iter()
+        .map(|inputs| {
+            let before = evaluate(&original, &inputs);
+            let after = evaluate(&simplified, &inputs);
+
+            Trial {
+                outputs: outputs
+                    .iter()
+                    .zip(before.into_iter().zip(after))
+                    .map(|(output, (before, after))| (output.to_string(), before, after))
+                    .collect(),
+                inputs,
+            }
+        })
+        .collect();
+
+    (trials, exhaustive)
+}
+
+#[cfg(test)]
+mod test {
+    mod simplify {
+        use super::super::*;
+
+        fn parse(raw: &[&str]) -> Vec<Signal> {
+            raw.iter().map(|raw| Signal::parse(raw).unwrap()).collect()
+        }
+
+        #[test]
+        fn test_fold_constants() {
+            let schematic = parse(&[
+                "123 -> x",
+                "456 -> y",
+                "x AND y -> d",
+                "x OR y -> e",
+                "d LSHIFT 2 -> f",
+                "NOT f -> g",
+            ]);
+            let result = simplify(&schematic, &["g"]);
+
+            assert_eq!(result.schematic, parse(&["65247 -> g"]));
+            assert!(result.evidence.holds());
+            assert_eq!(result.evidence.trials.len(), 1);
+            assert!(result.evidence.exhaustive);
+
+            let dead: Vec<&str> = result
+                .evidence
+                .steps
+                .iter()
+                .filter(|step| step.rule == Rule::Dead)
+                .map(|step| step.wire.as_str())
+                .collect();
+            assert_eq!(dead, vec!["x", "y", "d", "e", "f"]);
+        }
+
+        #[test]
+        fn test_identities() {
+            let schematic = parse(&[
+                "x AND 0 -> a",
+                "x OR 65535 -> b",
+                "x LSHIFT 0 -> c",
+                "c -> e",
+                "e AND y -> f",
+                "NOT x -> n",
+                "NOT n -> m",
+                "y XOR y -> z",
+                "z OR x -> p",
+                "x RSHIFT 16 -> q",
+            ]);
+            let result = simplify(&schematic, &["a", "b", "f", "m", "p", "q"]);
+
+            assert_eq!(
+                result.schematic,
+                parse(&[
+                    "0 -> a",
+                    "65535 -> b",
+                    "x AND y -> f",
+                    "x -> m",
+                    "x -> p",
+                    "0 -> q",
+                ])
+            );
+            assert!(result.evidence.holds());
+            assert_eq!(result.evidence.trials.len(), 2 + RANDOM_TRIALS as usize);
+            assert!(!result.evidence.exhaustive);
+        }
+
+        #[test]
+        fn test_exhaustive() {
+            let result = simplify(&parse(&["x OR 0 -> a", "a RSHIFT 0 -> b"]), &["b"]);
+
+            assert_eq!(result.schematic, parse(&["x -> b"]));
+            assert!(result.evidence.exhaustive);
+            assert!(result.evidence.holds());
+            assert_eq!(result.evidence.trials.len(), 65536);
+            assert_eq!(
+                result.evidence.trials[1234],
+                Trial {
+                    inputs: vec![("x".to_string(), 1234)],
+                    outputs: vec![("b".to_string(), Some(1234), Some(1234))],
+                }
+            );
+        }
+
+        #[test]
+        fn test_steps() {
+            let result = simplify(
+                &parse(&["3 -> a", "a -> b", "b AND c -> d", "d -> e"]),
+                &["e"],
+            );
+
+            let steps: Vec<(&str, Rule)> = result
+                .evidence
+                .steps
+                .iter()
+                .map(|step| (step.wire.as_str(), step.rule))
+                .collect();
+            assert_eq!(
+                steps,
+                vec![
+                    ("b", Rule::Constant),
+                    ("d", Rule::Constant),
+                    ("a", Rule::Dead),
+                    ("b", Rule::Dead),
+                ]
+            );
+            assert_eq!(result.schematic, parse(&["3 AND c -> d", "d -> e"]));
+        }
+
+        #[test]
+        fn test_loops_kept() {
+            let schematic = parse(&["b -> a", "a AND 1 -> b", "a OR 0 -> c"]);
+            let result = simplify(&schematic, &["c"]);
+
+            assert_eq!(result.schematic, schematic);
+            assert!(result.evidence.holds());
+        }
+
+        #[test]
+        fn test_evidence_detects_difference() {
+            let mut result = simplify(&parse(&["x AND 1 -> a"]), &["a"]);
+            assert!(result.evidence.holds());
+
+            result.evidence.trials[0].outputs[0].2 = Some(7);
+            assert!(!result.evidence.holds());
+        }
+    }
+}
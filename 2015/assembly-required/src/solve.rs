@@ -0,0 +1,453 @@
+use crate::signal::Signal;
+use crate::tape::{Instruction, Operand, Tape};
+use crate::word::Word;
+
+/// Number of gates between the free wires and the target above which the solver stops propagating constraints and
+/// tries every value instead, provided there are few enough free bits
+const FAN_IN_LIMIT: usize = 256;
+
+/// Most free bits worth trying every value of
+pub(crate) const BRUTE_FORCE_BITS: u32 = 16;
+
+/// How the solver searches for values of the free wires
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Assigns the free bits one at a time, propagating the known bits through every gate to rule out any partial
+    /// assignment which can no longer reach the target
+    Constraints,
+    /// Evaluates the circuit for every value of the free wires
+    BruteForce,
+}
+
+/// Some bits of a word known, the rest unknown
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Bits {
+    known: u64,
+    value: u64,
+}
+
+impl Bits {
+    fn exactly(value: u64) -> Bits {
+        Bits {
+            known: u64::MAX,
+            value,
+        }
+    }
+
+    /// Keeps only the bits within a word of `mask`, treating those beyond it as known zeros
+    fn masked(self, mask: u64) -> Bits {
+        Bits {
+            known: self.known | !mask,
+            value: self.value & self.known & mask,
+        }
+    }
+}
+
+/// Finds values of some free wires which give another wire a target value
+///
+/// # Examples
+///
+/// ```rust
+/// # use assembly_required::signal::Signal;
+/// # use assembly_required::solve::Solver;
+/// let schematic = ["b AND 12 -> c", "c OR 1 -> a"].map(|raw| Signal::parse(raw).unwrap());
+/// let solver = Solver::new(&schematic, &["b"], "a", 9);
+///
+/// assert_eq!(solver.count(), 16384);
+/// assert_eq!(solver.solutions(2), vec![vec![8], vec![9]]);
+/// ```
+pub struct Solver<W = u16> {
+    tape: Tape<W>,
+    free: Vec<usize>,
+    target: Option<usize>,
+    value: W,
+    strategy: Strategy,
+}
+
+impl<W: Word> Solver<W> {
+    /// Sets up a search for values of `free` which drive `wire` to `value`. Any signal driving a free wire is
+    /// ignored.
+    pub fn new(schematic: &[Signal<W>], free: &[&str], wire: &str, value: W) -> Solver<W> {
+        let tape = Tape::compile(schematic, free);
+        let free: Vec<usize> = free
+            .iter()
+            .map(|wire| {
+                tape.register(wire)
+                    .expect("Free wires are always given a register")
+            })
+            .collect();
+        let target = tape.register(wire);
+
+        let mut solver = Solver {
+            tape,
+            free,
+            target,
+            value,
+            strategy: Strategy::Constraints,
+        };
+
+        if solver.fan_in() > FAN_IN_LIMIT && solver.bits() <= BRUTE_FORCE_BITS {
+            solver.strategy = Strategy::BruteForce;
+        }
+
+        solver
+    }
+
+    /// Searches with the given strategy rather than the one picked for the circuit. Trying every value of more
+    /// than 64 free bits is not supported.
+    pub fn with_strategy(mut self, strategy: Strategy) -> Solver<W> {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// Number of assignments of the free wires giving the target value, saturating at `u128::MAX`
+    pub fn count(&self) -> u128 {
+        let mut count = 0u128;
+
+        self.search(|_, remaining| {
+            count = count.saturating_add(1u128.checked_shl(remaining).unwrap_or(u128::MAX));
+            true
+        });
+
+        count
+    }
+
+    /// Up to `limit` assignments giving the target value, each listing the values of the free wires in the order
+    /// they were given. Assignments are in ascending order, comparing the first free wire first.
+    pub fn solutions(&self, limit: usize) -> Vec<Vec<W>> {
+        let mut solutions = vec![];
+        let bits = self.bits();
+
+        self.search(|partial, remaining| {
+            // Every completion of the bits left to assign works, the unknown bits are the last `remaining` bits
+            // in search order so counting through them keeps the assignments in order
+            let completions = 1u64.checked_shl(remaining).unwrap_or(0).wrapping_sub(1);
+
+            for completion in 0..=completions {
+                if solutions.len() == limit {
+                    return false;
+                }
+
+                let mut words = partial.to_vec();
+                for n in 0..remaining {
+                    let (word, bit) = self.position(bits - remaining + n);
+                    let set = completion.checked_shr(remaining - 1 - n).unwrap_or(0) & 1 == 1;
+                    words[word].value |= (set as u64) << bit;
+                }
+                solutions.push(words.iter().map(|bits| W::truncate(bits.value)).collect());
+            }
+
+            true
+        });
+
+        solutions
+    }
+
+    /// Number of free bits
+    fn bits(&self) -> u32 {
+        self.free.len() as u32 * W::BITS
+    }
+
+    /// The free wire and bit within it assigned at step `n` of the search, most significant bits first
+    fn position(&self, n: u32) -> (usize, u32) {
+        ((n / W::BITS) as usize, W::BITS - 1 - n % W::BITS)
+    }
+
+    /// Number of gates reading, directly or indirectly, from a free wire and feeding the target
+    fn fan_in(&self) -> usize {
+        let target = match self.target {
+            Some(target) => target,
+            None => return 0,
+        };
+        let reads = |instruction: &Instruction<W>, marked: &[bool]| {
+            instruction.operands().iter().any(|operand| match operand {
+                Operand::Register(register) => marked[*register],
+                Operand::Literal(_) => false,
+            })
+        };
+
+        let mut downstream = vec![false; self.tape.len()];
+        self.free
+            .iter()
+            .for_each(|&register| downstream[register] = true);
+        let mut cone = vec![];
+
+        for instruction in self.tape.instructions() {
+            if reads(instruction, &downstream) {
+                downstream[instruction.output()] = true;
+                cone.push(instruction);
+            }
+        }
+
+        let mut upstream = vec![false; self.tape.len()];
+        upstream[target] = true;
+        let mut fan_in = 0;
+
+        for instruction in cone.into_iter().rev() {
+            if upstream[instruction.output()] {
+                fan_in += 1;
+
+                for operand in instruction.operands() {
+                    if let Operand::Register(register) = operand {
+                        upstream[register] = true;
+                    }
+                }
+            }
+        }
+
+        fan_in
+    }
+
+    /// Calls `found` with each partial assignment of the free wires for which every way of assigning the last
+    /// `remaining` bits gives the target value, stopping early if it returns false
+    fn search(&self, mut found: impl FnMut(&[Bits], u32) -> bool) {
+        let target = match self.target {
+            Some(target) => target,
+            None => return,
+        };
+        let mut partial = vec![Bits { known: 0, value: 0 }; self.free.len()];
+
+        match self.strategy {
+            Strategy::Constraints => {
+                self.constrain(target, &mut partial, 0, &mut found);
+            }
+            Strategy::BruteForce => {
+                let mut registers = self.tape.blank();
+
+                for assignment in 0..=(u64::MAX >> (64 - self.bits().clamp(1, 64))) {
+                    for (word, &register) in self.free.iter().enumerate() {
+                        let shift = (self.free.len() - 1 - word) as u32 * W::BITS;
+                        let value = W::truncate(assignment.checked_shr(shift).unwrap_or(0));
+
+                        registers[register] = Some(value);
+                        partial[word] = Bits::exactly(value.to_u64());
+                    }
+
+                    self.tape.run(&mut registers);
+
+                    if registers[target] == Some(self.value) && !found(&partial, 0) {
+                        return;
+                    }
+                    if self.free.is_empty() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Depth first search over the free bits from bit `n` on, returning false once `found` asks to stop
+    fn constrain(
+        &self,
+        target: usize,
+        partial: &mut [Bits],
+        n: u32,
+        found: &mut impl FnMut(&[Bits], u32) -> bool,
+    ) -> bool {
+        let mask = W::ones().to_u64();
+        let reached = match self.propagate(partial)[target] {
+            Some(reached) => reached.masked(mask),
+            None => return true,
+        };
+
+        if (reached.value ^ self.value.to_u64()) & reached.known & mask != 0 {
+            return true;
+        }
+
+        if reached.known == u64::MAX {
+            return found(partial, self.bits() - n);
+        }
+
+        let (word, bit) = self.position(n);
+        for set in [false, true] {
+            partial[word].known |= 1 << bit;
+            partial[word].value = partial[word].value & !(1 << bit) | (set as u64) << bit;
+
+            if !self.constrain(target, partial, n + 1, found) {
+                return false;
+            }
+        }
+
+        partial[word].known &= !(1 << bit);
+        partial[word].value &= !(1 << bit);
+        true
+    }
+
+    /// Runs the tape on partially known words, working out which bits of each wire are known
+    fn propagate(&self, partial: &[Bits]) -> Vec<Option<Bits>> {
+        let mask = W::ones().to_u64();
+        let mut registers = vec![None; self.tape.len()];
+
+        for (&register, bits) in self.free.iter().zip(partial) {
+            registers[register] = Some(bits.masked(mask));
+        }
+
+        for instruction in self.tape.instructions() {
+            let read = |operand: Operand<W>| match operand {
+                Operand::Register(register) => registers[register],
+                Operand::Literal(value) => Some(Bits::exactly(value.to_u64())),
+            };
+            let both = |a, b| Some((read(a)?, read(b)?));
+
+            let bits = match *instruction {
+                Instruction::Copy(_, a) => read(a),
+                Instruction::Not(_, a) => read(a).map(|a| Bits {
+                    known: a.known,
+                    value: !a.value,
+                }),
+                Instruction::And(_, a, b) => both(a, b).map(|(a, b)| Bits {
+                    known: (a.known & b.known) | (a.known & !a.value) | (b.known & !b.value),
+                    value: a.value & b.value,
+                }),
+                Instruction::Or(_, a, b) => both(a, b).map(|(a, b)| Bits {
+                    known: (a.known & b.known) | (a.known & a.value) | (b.known & b.value),
+                    value: a.value | b.value,
+                }),
+                Instruction::Xor(_, a, b) => both(a, b).map(|(a, b)| Bits {
+                    known: a.known & b.known,
+                    value: a.value ^ b.value,
+                }),
+                Instruction::LShift(_, a, b) => both(a, b).map(|(a, b)| match shift(b, mask) {
+                    Some(places) if places < 64 => Bits {
+                        known: a.known << places | !(u64::MAX << places),
+                        value: a.value << places,
+                    },
+                    Some(_) => Bits::exactly(0),
+                    None => Bits { known: 0, value: 0 },
+                }),
+                Instruction::RShift(_, a, b) => both(a, b).map(|(a, b)| match shift(b, mask) {
+                    // Bits shifted in from beyond the word are zeros
+                    Some(places) if places < 64 => Bits {
+                        known: a.known >> places | !(u64::MAX >> places),
+                        value: a.value >> places,
+                    },
+                    Some(_) => Bits::exactly(0),
+                    None => Bits { known: 0, value: 0 },
+                }),
+            };
+
+            registers[instruction.output()] = bits.map(|bits| bits.masked(mask));
+        }
+
+        registers
+    }
+}
+
+/// The number of places to shift by, once every bit of it is known
+fn shift(places: Bits, mask: u64) -> Option<u64> {
+    (places.known | !mask == u64::MAX).then_some(places.value & mask)
+}
+
+#[cfg(test)]
+mod test {
+    mod solver {
+        use super::super::*;
+        use crate::word::Masked;
+
+        fn parse(raw: &[&str]) -> Vec<Signal> {
+            raw.iter().map(|raw| Signal::parse(raw).unwrap()).collect()
+        }
+
+        /// Both strategies should agree with each other on every test
+        fn check(solver: Solver, count: u128, first: &[Vec<u16>]) {
+            assert_eq!(solver.count(), count);
+            assert_eq!(solver.solutions(first.len()), first);
+
+            let solver = solver.with_strategy(Strategy::BruteForce);
+            assert_eq!(solver.count(), count);
+            assert_eq!(solver.solutions(first.len()), first);
+        }
+
+        #[test]
+        fn test_invertible() {
+            let schematic = parse(&["NOT b -> c", "c XOR 255 -> d", "d RSHIFT 4 -> a"]);
+            let solver = Solver::new(&schematic, &["b"], "a", 0x0abc);
+
+            assert_eq!(solver.strategy(), Strategy::Constraints);
+            check(solver, 16, &[vec![0x54c0], vec![0x54c1], vec![0x54c2]]);
+        }
+
+        #[test]
+        fn test_gates() {
+            let schematic = parse(&[
+                "b AND 240 -> c",
+                "b OR 3 -> d",
+                "c XOR d -> e",
+                "e LSHIFT 2 -> a",
+            ]);
+
+            check(
+                Solver::new(&schematic, &["b"], "a", 0x041c),
+                256,
+                &[vec![0x0104], vec![0x0105]],
+            );
+            check(Solver::new(&schematic, &["b"], "a", 1), 0, &[]);
+        }
+
+        #[test]
+        fn test_reconvergent() {
+            let schematic = parse(&["b XOR c -> d", "d XOR c -> a", "7 -> c"]);
+
+            check(Solver::new(&schematic, &["b"], "a", 42), 1, &[vec![42]]);
+        }
+
+        #[test]
+        fn test_several_free_wires() {
+            let schematic = parse(&["x AND y -> a"]);
+            let solver = Solver::new(&schematic, &["x", "y"], "a", 65535);
+
+            assert_eq!(solver.count(), 1);
+            assert_eq!(solver.solutions(5), vec![vec![65535, 65535]]);
+
+            let solver = Solver::new(&schematic, &["x", "y"], "a", 65534);
+            assert_eq!(solver.count(), 3);
+            assert_eq!(
+                solver.solutions(5),
+                vec![vec![65534, 65534], vec![65534, 65535], vec![65535, 65534]]
+            );
+        }
+
+        #[test]
+        fn test_unreachable() {
+            let schematic = parse(&["b AND c -> a", "b -> c", "c -> d"]);
+
+            assert_eq!(Solver::new(&schematic, &["b"], "a", 3).count(), 1);
+            assert_eq!(Solver::new(&schematic, &["b"], "z", 3).count(), 0);
+            assert_eq!(Solver::new(&schematic, &["b"], "e", 3).count(), 0);
+        }
+
+        #[test]
+        fn test_fan_in_falls_back() {
+            // Two letter wire names, since the puzzle syntax has no digits in them
+            let wire = |n: u32| {
+                let letter = |n: u32| char::from_u32('a' as u32 + n).unwrap();
+                format!("{}{}", letter(2 + n / 26), letter(n % 26))
+            };
+            let mut raw = vec![format!("b -> {}", wire(0))];
+            raw.extend((0..300).map(|n| format!("{} XOR {} -> {}", wire(n), n % 7, wire(n + 1))));
+            raw.push(format!("{} -> a", wire(300)));
+            let schematic: Vec<Signal> =
+                raw.iter().map(|raw| Signal::parse(raw).unwrap()).collect();
+
+            let solver = Solver::new(&schematic, &["b"], "a", 9);
+            assert_eq!(solver.strategy(), Strategy::BruteForce);
+            assert_eq!(solver.count(), 1);
+        }
+
+        #[test]
+        fn test_other_widths() {
+            let schematic: Vec<Signal<Masked<6>>> = ["NOT b -> c", "c LSHIFT 3 -> a"]
+                .iter()
+                .map(|raw| raw.parse().unwrap())
+                .collect();
+            let solver = Solver::new(&schematic, &["b"], "a", Masked::truncate(0b101000));
+
+            assert_eq!(solver.count(), 8);
+            assert_eq!(solver.solutions(1), vec![vec![Masked::truncate(0b000010)]]);
+        }
+    }
+}
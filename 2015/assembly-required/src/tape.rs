@@ -0,0 +1,410 @@
+use crate::signal::{Component, Signal};
+use crate::word::Word;
+use adventofcode::graph::Graph;
+use std::collections::{HashMap, HashSet};
+
+/// Where an instruction reads a word from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand<W = u16> {
+    Register(usize),
+    Literal(W),
+}
+
+/// A single gate, writing to the register given first
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction<W = u16> {
+    Copy(usize, Operand<W>),
+    Not(usize, Operand<W>),
+    And(usize, Operand<W>, Operand<W>),
+    Or(usize, Operand<W>, Operand<W>),
+    Xor(usize, Operand<W>, Operand<W>),
+    LShift(usize, Operand<W>, Operand<W>),
+    RShift(usize, Operand<W>, Operand<W>),
+}
+
+impl<W: Word> Instruction<W> {
+    /// The register the instruction writes to
+    pub fn output(&self) -> usize {
+        match *self {
+            Instruction::Copy(output, _)
+            | Instruction::Not(output, _)
+            | Instruction::And(output, _, _)
+            | Instruction::Or(output, _, _)
+            | Instruction::Xor(output, _, _)
+            | Instruction::LShift(output, _, _)
+            | Instruction::RShift(output, _, _) => output,
+        }
+    }
+
+    /// The operands the instruction reads from
+    pub fn operands(&self) -> Vec<Operand<W>> {
+        match *self {
+            Instruction::Copy(_, a) | Instruction::Not(_, a) => vec![a],
+            Instruction::And(_, a, b)
+            | Instruction::Or(_, a, b)
+            | Instruction::Xor(_, a, b)
+            | Instruction::LShift(_, a, b)
+            | Instruction::RShift(_, a, b) => vec![a, b],
+        }
+    }
+
+    /// The word the instruction writes, provided every register it reads from holds a word
+    pub fn execute(&self, registers: &[Option<W>]) -> Option<W> {
+        let read = |operand: Operand<W>| match operand {
+            Operand::Register(register) => registers[register],
+            Operand::Literal(value) => Some(value),
+        };
+
+        match *self {
+            Instruction::Copy(_, a) => read(a),
+            Instruction::Not(_, a) => read(a).map(W::not),
+            Instruction::And(_, a, b) => Some(read(a)?.and(read(b)?)),
+            Instruction::Or(_, a, b) => Some(read(a)?.or(read(b)?)),
+            Instruction::Xor(_, a, b) => Some(read(a)?.xor(read(b)?)),
+            Instruction::LShift(_, a, b) => Some(read(a)?.shl(read(b)?)),
+            Instruction::RShift(_, a, b) => Some(read(a)?.shr(read(b)?)),
+        }
+    }
+}
+
+/// A schematic compiled to a list of instructions over numbered registers, one register per wire, ordered so
+/// every instruction comes after the instructions writing the registers it reads from
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tape<W = u16> {
+    instructions: Vec<Instruction<W>>,
+    registers: HashMap<String, usize>,
+    wires: Vec<String>,
+    /// Position of the instruction writing to each register, if any
+    writers: Vec<Option<usize>>,
+}
+
+impl<W: Word> Tape<W> {
+    /// Compiles a schematic, leaving out the signals driving any of `inputs` so their registers can be filled in
+    /// before each run. Wires fed by a loop, and wires with no driver, have no instruction writing to them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use assembly_required::signal::Signal;
+    /// # use assembly_required::tape::Tape;
+    /// let schematic = ["x AND y -> z", "3 -> x", "6 -> y"].map(|raw| Signal::parse(raw).unwrap());
+    /// let tape = Tape::compile(&schematic, &["y"]);
+    ///
+    /// let z = tape.register("z").unwrap();
+    /// assert_eq!(tape.evaluate(&[("y", 5)])[z], Some(1));
+    /// assert_eq!(tape.evaluate(&[("y", 2)])[z], Some(2));
+    /// ```
+    pub fn compile(schematic: &[Signal<W>], inputs: &[&str]) -> Tape<W> {
+        let mut tape = Tape {
+            instructions: vec![],
+            registers: HashMap::new(),
+            wires: vec![],
+            writers: vec![],
+        };
+        let mut drivers = HashMap::new();
+        let mut dependencies = Graph::new();
+
+        for signal in schematic {
+            let id = match signal.output() {
+                Component::ID(id) => id,
+                Component::VALUE(_) => continue,
+            };
+            tape.allocate(id);
+            dependencies.add_node(id.clone());
+
+            for input in signal.inputs() {
+                if let Component::ID(input) = input {
+                    tape.allocate(input);
+                }
+            }
+
+            if inputs.contains(&id.as_str()) {
+                continue;
+            }
+
+            for input in signal.inputs() {
+                if let Component::ID(input) = input {
+                    dependencies.add_edge(input.clone(), id.clone(), ());
+                }
+            }
+
+            drivers.insert(id.clone(), signal);
+        }
+        inputs.iter().for_each(|input| {
+            tape.allocate(input);
+        });
+
+        let order = dependencies
+            .topological_sort()
+            .unwrap_or_else(|cycle| cycle.sorted);
+
+        for wire in order {
+            if let Some(signal) = drivers.get(&wire) {
+                let instruction = tape.instruction(signal);
+                tape.instructions.push(instruction);
+            }
+        }
+        tape.index();
+
+        tape
+    }
+
+    /// Swaps in new instructions for the wires in `cone` without compiling the schematic again, giving a register
+    /// to any wire new to the tape. `cone` pairs every wire reading from its first wire, directly or through other
+    /// wires, with the signal now driving it, or none for a wire fed by a loop, each wire after the wires it reads
+    /// from. The instructions are overwritten in place when their wires are still written in a valid order,
+    /// otherwise the instructions for the cone move to the end of the tape in the order given, which is safe as no
+    /// instruction outside the cone reads from them. Returns the position of every instruction writing to a wire
+    /// in the cone, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use assembly_required::signal::Signal;
+    /// # use assembly_required::tape::Tape;
+    /// let schematic = ["x AND 3 -> z", "6 -> x", "1 -> y"].map(|raw| Signal::parse(raw).unwrap());
+    /// let mut tape = Tape::compile(&schematic, &[]);
+    /// let z = tape.register("z").unwrap();
+    ///
+    /// let (x, y) = (Signal::parse("5 -> x").unwrap(), Signal::parse("y -> x").unwrap());
+    /// assert_eq!(tape.replace(&[("x", Some(&x)), ("z", Some(&schematic[0]))]), vec![0, 2]);
+    /// // `y` is written after `x`, so the instructions for `x` and `z` move to the end
+    /// assert_eq!(tape.replace(&[("x", Some(&y)), ("z", Some(&schematic[0]))]), vec![1, 2]);
+    /// assert_eq!(tape.evaluate(&[])[z], Some(1));
+    /// ```
+    pub fn replace(&mut self, cone: &[(&str, Option<&Signal<W>>)]) -> Vec<usize> {
+        for (wire, signal) in cone {
+            self.allocate(wire);
+
+            for input in signal.iter().flat_map(|signal| signal.inputs()) {
+                if let Component::ID(input) = input {
+                    self.allocate(input);
+                }
+            }
+        }
+        self.writers.resize(self.wires.len(), None);
+
+        let stale: HashSet<usize> = cone.iter().map(|(wire, _)| self.registers[*wire]).collect();
+        let instructions: Vec<Instruction<W>> = cone
+            .iter()
+            .filter_map(|(_, signal)| Some(self.instruction((*signal)?)))
+            .collect();
+
+        let written = cone.iter().all(|(wire, signal)| {
+            self.writers[self.registers[*wire]].is_some() == signal.is_some()
+        });
+        let ordered = || {
+            instructions.iter().all(|instruction| {
+                // A register with no instruction writing to it orders before every position
+                let position = self.writers[instruction.output()];
+                instruction.operands().iter().all(|operand| match *operand {
+                    Operand::Register(register) => self.writers[register] < position,
+                    Operand::Literal(_) => true,
+                })
+            })
+        };
+
+        if written && ordered() {
+            let mut positions: Vec<usize> = instructions
+                .into_iter()
+                .map(|instruction| {
+                    let position = self.writers[instruction.output()]
+                        .expect("Every wire in the cone with a driver is already written");
+                    self.instructions[position] = instruction;
+                    position
+                })
+                .collect();
+            positions.sort_unstable();
+            return positions;
+        }
+
+        self.instructions
+            .retain(|instruction| !stale.contains(&instruction.output()));
+        let kept = self.instructions.len();
+        self.instructions.extend(instructions);
+        self.index();
+
+        (kept..self.instructions.len()).collect()
+    }
+
+    /// The register holding a wire, if the wire appears in the schematic
+    pub fn register(&self, wire: &str) -> Option<usize> {
+        self.registers.get(wire).copied()
+    }
+
+    /// The wire held in a register
+    pub fn wire(&self, register: usize) -> &str {
+        &self.wires[register]
+    }
+
+    /// Number of registers, one per wire
+    pub fn len(&self) -> usize {
+        self.wires.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wires.is_empty()
+    }
+
+    pub fn instructions(&self) -> &[Instruction<W>] {
+        &self.instructions
+    }
+
+    /// A register file with every register empty
+    pub fn blank(&self) -> Vec<Option<W>> {
+        vec![None; self.len()]
+    }
+
+    /// Runs every instruction in order against the register file
+    pub fn run(&self, registers: &mut [Option<W>]) {
+        for instruction in &self.instructions {
+            registers[instruction.output()] = instruction.execute(registers);
+        }
+    }
+
+    /// Runs the tape with the given wires filled in, returning the register file
+    pub fn evaluate(&self, inputs: &[(&str, W)]) -> Vec<Option<W>> {
+        let mut registers = self.blank();
+
+        for (wire, value) in inputs {
+            if let Some(register) = self.register(wire) {
+                registers[register] = Some(*value);
+            }
+        }
+
+        self.run(&mut registers);
+        registers
+    }
+
+    /// Records the position of the instruction writing to each register
+    fn index(&mut self) {
+        self.writers = vec![None; self.wires.len()];
+
+        for (position, instruction) in self.instructions.iter().enumerate() {
+            self.writers[instruction.output()] = Some(position);
+        }
+    }
+
+    fn allocate(&mut self, wire: &str) -> usize {
+        if let Some(register) = self.register(wire) {
+            return register;
+        }
+
+        self.wires.push(wire.to_string());
+        self.registers
+            .insert(wire.to_string(), self.wires.len() - 1);
+        self.wires.len() - 1
+    }
+
+    /// Translates a signal whose wires all have registers
+    fn instruction(&self, signal: &Signal<W>) -> Instruction<W> {
+        let operand = |component: &Component<W>| match component {
+            Component::ID(id) => Operand::Register(self.registers[id]),
+            Component::VALUE(value) => Operand::Literal(*value),
+        };
+        let o = match signal.output() {
+            Component::ID(id) => self.registers[id],
+            Component::VALUE(_) => unreachable!("Signals driving a literal are never compiled"),
+        };
+
+        match signal {
+            Signal::VALUE(_, a) => Instruction::Copy(o, operand(a)),
+            Signal::NOT(_, a) => Instruction::Not(o, operand(a)),
+            Signal::AND(_, a, b) => Instruction::And(o, operand(a), operand(b)),
+            Signal::OR(_, a, b) => Instruction::Or(o, operand(a), operand(b)),
+            Signal::XOR(_, a, b) => Instruction::Xor(o, operand(a), operand(b)),
+            Signal::LSHIFT(_, a, b) => Instruction::LShift(o, operand(a), operand(b)),
+            Signal::RSHIFT(_, a, b) => Instruction::RShift(o, operand(a), operand(b)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod tape {
+        use super::super::*;
+
+        fn parse(raw: &[&str]) -> Vec<Signal> {
+            raw.iter().map(|raw| Signal::parse(raw).unwrap()).collect()
+        }
+
+        #[test]
+        fn test_compile() {
+            let tape = Tape::compile(&parse(&["x AND 7 -> z", "NOT y -> x", "5 -> y"]), &[]);
+            let (x, y, z) = (
+                tape.register("x").unwrap(),
+                tape.register("y").unwrap(),
+                tape.register("z").unwrap(),
+            );
+
+            assert_eq!(tape.len(), 3);
+            assert_eq!(tape.wire(y), "y");
+            assert_eq!(
+                tape.instructions(),
+                &[
+                    Instruction::Copy(y, Operand::Literal(5)),
+                    Instruction::Not(x, Operand::Register(y)),
+                    Instruction::And(z, Operand::Register(x), Operand::Literal(7)),
+                ]
+            );
+            assert_eq!(tape.evaluate(&[])[z], Some(2));
+        }
+
+        #[test]
+        fn test_inputs() {
+            let tape = Tape::compile(
+                &parse(&["1 -> b", "b LSHIFT 2 -> a", "a OR c -> d"]),
+                &["b", "c"],
+            );
+            let d = tape.register("d").unwrap();
+
+            assert_eq!(tape.instructions().len(), 2);
+            for b in 0..4 {
+                assert_eq!(tape.evaluate(&[("b", b), ("c", 1)])[d], Some(b << 2 | 1));
+            }
+            assert_eq!(tape.evaluate(&[("b", 1)])[d], None);
+        }
+
+        #[test]
+        fn test_loops() {
+            let tape = Tape::compile(&parse(&["b -> a", "a OR 1 -> b", "b -> c", "3 -> d"]), &[]);
+            let registers = tape.evaluate(&[]);
+
+            assert_eq!(tape.instructions().len(), 1);
+            assert_eq!(registers[tape.register("c").unwrap()], None);
+            assert_eq!(registers[tape.register("d").unwrap()], Some(3));
+        }
+
+        #[test]
+        fn test_replace() {
+            let schematic = parse(&[
+                "b -> a",
+                "a OR 1 -> b",
+                "b -> c",
+                "3 -> d",
+                "d -> a",
+                "c -> a",
+            ]);
+            let mut tape = Tape::compile(&schematic[..4], &[]);
+
+            let cone = |a: usize, looped: bool| {
+                let signals = [&schematic[a], &schematic[1], &schematic[2]];
+                ["a", "b", "c"]
+                    .into_iter()
+                    .zip(signals)
+                    .map(|(wire, signal)| (wire, Some(signal).filter(|_| !looped)))
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(tape.replace(&cone(4, false)), vec![1, 2, 3]);
+            assert_eq!(
+                tape.instructions()[0],
+                Instruction::Copy(3, Operand::Literal(3))
+            );
+            assert_eq!(tape.evaluate(&[])[tape.register("c").unwrap()], Some(3));
+
+            assert_eq!(tape.replace(&cone(5, true)), vec![]);
+            assert_eq!(tape.instructions().len(), 1);
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use crate::signal::{Component, Signal};
+use crate::word::Word;
+use adventofcode::graph::{bfs, bfs_path, Graph};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+/// Why a wire can never be given a value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Blocker {
+    /// The wire is read from but no signal drives it
+    Undriven,
+    /// The wire feeds back into itself
+    Cycle,
+}
+
+/// A wire which can never be given a value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unresolved {
+    pub wire: String,
+    /// The wires read from on the way to the blocking wire, starting with `wire` and ending with the blocking wire
+    pub chain: Vec<String>,
+    pub blocker: Blocker,
+}
+
+/// A wire driven by more than one signal
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict<W = u16> {
+    pub wire: String,
+    /// Every signal driving the wire, in schematic order
+    pub drivers: Vec<Signal<W>>,
+}
+
+/// Everything preventing a schematic from being evaluated, each list is ordered by wire
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report<W = u16> {
+    pub unresolved: Vec<Unresolved>,
+    /// The wires making up every loop which passes through no wire twice, each wire feeding the next and the last
+    /// feeding the first. Each loop starts at its first wire.
+    pub cycles: Vec<Vec<String>>,
+    pub conflicts: Vec<Conflict<W>>,
+}
+
+impl<W> Report<W> {
+    pub fn is_empty(&self) -> bool {
+        self.unresolved.is_empty() && self.cycles.is_empty() && self.conflicts.is_empty()
+    }
+}
+
+impl<W> fmt::Display for Report<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for conflict in &self.conflicts {
+            writeln!(
+                f,
+                "wire {} is driven by {} signals",
+                conflict.wire,
+                conflict.drivers.len()
+            )?;
+        }
+
+        for cycle in &self.cycles {
+            writeln!(f, "cycle {} -> {}", cycle.join(" -> "), cycle[0])?;
+        }
+
+        for unresolved in &self.unresolved {
+            let blocker = unresolved.chain.last().unwrap_or(&unresolved.wire);
+            let reason = match unresolved.blocker {
+                Blocker::Undriven => "is never driven",
+                Blocker::Cycle => "is part of a cycle",
+            };
+
+            if unresolved.chain.len() > 1 {
+                writeln!(
+                    f,
+                    "wire {} is unresolved, it reads {} where {} {}",
+                    unresolved.wire,
+                    unresolved.chain[1..].join(" <- "),
+                    blocker,
+                    reason
+                )?;
+            } else {
+                writeln!(f, "wire {} is unresolved, it {}", unresolved.wire, reason)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks every wire of the schematic can be given a value, reporting any that cannot along with any wire with
+/// more than one driver
+pub fn validate<W: Word>(schematic: &[Signal<W>]) -> Result<(), Report<W>> {
+    let mut drivers = BTreeMap::<&str, Vec<Signal<W>>>::new();
+    let mut dependencies = Graph::new();
+
+    for signal in schematic {
+        if let Component::ID(id) = signal.output() {
+            dependencies.add_node(id.as_str());
+
+            for input in signal.inputs() {
+                if let Component::ID(input) = input {
+                    dependencies.add_edge(input.as_str(), id.as_str(), ());
+                }
+            }
+
+            drivers.entry(id).or_default().push(signal.clone());
+        }
+    }
+
+    let mut report = Report {
+        conflicts: drivers
+            .iter()
+            .filter(|(_, signals)| signals.len() > 1)
+            .map(|(wire, signals)| Conflict {
+                wire: wire.to_string(),
+                drivers: signals.clone(),
+            })
+            .collect(),
+        unresolved: vec![],
+        cycles: vec![],
+    };
+
+    let mut blockers = BTreeMap::new();
+
+    for wire in dependencies.nodes() {
+        if !drivers.contains_key(wire) {
+            blockers.insert(*wire, Blocker::Undriven);
+        }
+    }
+
+    for component in dependencies.strongly_connected_components() {
+        let cycles = cycles(&dependencies, &component);
+
+        if !cycles.is_empty() {
+            for wire in &component {
+                blockers.insert(*wire, Blocker::Cycle);
+            }
+
+            report.cycles.extend(
+                cycles
+                    .into_iter()
+                    .map(|cycle| cycle.iter().map(|w| w.to_string()).collect()),
+            );
+        }
+    }
+
+    report.cycles.sort();
+
+    let unresolved: BTreeSet<&str> = blockers
+        .keys()
+        .flat_map(|blocker| bfs(*blocker, |wire| outputs(&dependencies, wire)))
+        .collect();
+
+    for wire in unresolved {
+        let chain = bfs_path(
+            wire,
+            |wire| inputs(&dependencies, wire),
+            |wire| blockers.contains_key(wire),
+        )
+        .expect("Every unresolved wire reads from a blocking wire");
+        let blocker = blockers[chain.last().expect("Paths are never empty")];
+
+        report.unresolved.push(Unresolved {
+            wire: wire.to_string(),
+            chain: chain.iter().map(|w| w.to_string()).collect(),
+            blocker,
+        });
+    }
+
+    if report.is_empty() {
+        Ok(())
+    } else {
+        Err(report)
+    }
+}
+
+/// Every elementary cycle within a strongly connected component, found with Johnson's algorithm. Each cycle starts
+/// at its first wire, and the search from each wire only visits later wires so no cycle is found twice.
+fn cycles<'a>(dependencies: &Graph<&'a str>, component: &[&'a str]) -> Vec<Vec<&'a str>> {
+    let mut wires = component.to_vec();
+    wires.sort_unstable();
+    let mut cycles = vec![];
+
+    for (n, &start) in wires.iter().enumerate() {
+        let later: HashSet<&str> = wires[n..].iter().copied().collect();
+        let outputs = |wire: &&'a str| -> Vec<&'a str> {
+            let mut outputs: Vec<&str> = outputs(dependencies, wire)
+                .into_iter()
+                .filter(|output| later.contains(output))
+                .collect();
+            outputs.sort_unstable();
+            outputs
+        };
+
+        let mut blocked = HashSet::from([start]);
+        let mut blocking = HashMap::<&str, HashSet<&str>>::new();
+        let mut path = vec![start];
+        // Each wire on the path, the wires it feeds, how many of them have been tried and whether any led back
+        let mut frames = vec![(start, outputs(&start), 0, false)];
+
+        while let Some((wire, next, tried, found)) = frames.last_mut() {
+            if let Some(&output) = next.get(*tried) {
+                *tried += 1;
+
+                if output == start {
+                    *found = true;
+                    cycles.push(path.clone());
+                } else if blocked.insert(output) {
+                    path.push(output);
+                    frames.push((output, outputs(&output), 0, false));
+                }
+                continue;
+            }
+
+            let (wire, next, found) = (*wire, std::mem::take(next), *found);
+            frames.pop();
+            path.pop();
+
+            if found {
+                // Unblock the wire along with every wire waiting on it
+                let mut unblock = vec![wire];
+                while let Some(wire) = unblock.pop() {
+                    if blocked.remove(wire) {
+                        unblock.extend(blocking.remove(wire).into_iter().flatten());
+                    }
+                }
+            } else {
+                for output in next {
+                    blocking.entry(output).or_default().insert(wire);
+                }
+            }
+
+            if let Some((_, _, _, parent_found)) = frames.last_mut() {
+                *parent_found |= found;
+            }
+        }
+    }
+
+    cycles
+}
+
+/// The wires `wire` feeds
+fn outputs<'a>(dependencies: &Graph<&'a str>, wire: &&'a str) -> Vec<&'a str> {
+    dependencies
+        .successors(wire)
+        .map(|(output, _)| *output)
+        .collect()
+}
+
+/// The wires `wire` reads from
+fn inputs<'a>(dependencies: &Graph<&'a str>, wire: &&'a str) -> Vec<&'a str> {
+    dependencies
+        .predecessors(wire)
+        .map(|(input, _)| *input)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    mod validate {
+        use super::super::*;
+
+        fn schematic(raw: &[&str]) -> Vec<Signal> {
+            raw.iter().map(|raw| Signal::parse(raw).unwrap()).collect()
+        }
+
+        fn chain(wires: &[&str]) -> Vec<String> {
+            wires.iter().map(|w| w.to_string()).collect()
+        }
+
+        #[test]
+        fn test_valid() {
+            let s = schematic(&["b AND 3 -> a", "NOT c -> b", "7 -> c"]);
+            assert_eq!(validate(&s), Ok(()));
+        }
+
+        #[test]
+        fn test_undriven() {
+            let report = validate(&schematic(&["b OR c -> a", "d -> b", "1 -> c"])).unwrap_err();
+
+            assert!(report.cycles.is_empty());
+            assert!(report.conflicts.is_empty());
+            assert_eq!(
+                report.unresolved,
+                vec![
+                    Unresolved {
+                        wire: "a".to_string(),
+                        chain: chain(&["a", "b", "d"]),
+                        blocker: Blocker::Undriven,
+                    },
+                    Unresolved {
+                        wire: "b".to_string(),
+                        chain: chain(&["b", "d"]),
+                        blocker: Blocker::Undriven,
+                    },
+                    Unresolved {
+                        wire: "d".to_string(),
+                        chain: chain(&["d"]),
+                        blocker: Blocker::Undriven,
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn test_cycles() {
+            let report = validate(&schematic(&[
+                "c -> a",
+                "a AND 1 -> b",
+                "b -> c",
+                "c OR e -> d",
+                "e LSHIFT 1 -> e",
+                "5 -> f",
+            ]))
+            .unwrap_err();
+
+            assert_eq!(report.cycles, vec![chain(&["a", "b", "c"]), chain(&["e"])]);
+
+            let branches = validate(&schematic(&[
+                "a -> b",
+                "a -> c",
+                "b OR c -> a",
+                "c AND d -> d",
+            ]))
+            .unwrap_err();
+            assert_eq!(
+                branches.cycles,
+                vec![chain(&["a", "b"]), chain(&["a", "c"]), chain(&["d"])]
+            );
+
+            let complete =
+                validate(&schematic(&["b OR c -> a", "a OR c -> b", "a OR b -> c"])).unwrap_err();
+            assert_eq!(
+                complete.cycles,
+                vec![
+                    chain(&["a", "b"]),
+                    chain(&["a", "b", "c"]),
+                    chain(&["a", "c"]),
+                    chain(&["a", "c", "b"]),
+                    chain(&["b", "c"]),
+                ]
+            );
+
+            let wires: Vec<&str> = report.unresolved.iter().map(|u| u.wire.as_str()).collect();
+            assert_eq!(wires, vec!["a", "b", "c", "d", "e"]);
+            assert_eq!(report.unresolved[3].chain, chain(&["d", "c"]));
+            assert!(report
+                .unresolved
+                .iter()
+                .all(|u| u.blocker == Blocker::Cycle));
+        }
+
+        #[test]
+        fn test_conflicts() {
+            let report = validate(&schematic(&["1 -> a", "2 -> b", "b -> a"])).unwrap_err();
+
+            assert!(report.unresolved.is_empty());
+            assert_eq!(
+                report.conflicts,
+                vec![Conflict {
+                    wire: "a".to_string(),
+                    drivers: schematic(&["1 -> a", "b -> a"]),
+                }]
+            );
+        }
+
+        #[test]
+        fn test_display() {
+            let report = validate(&schematic(&[
+                "x -> a", "a -> b", "c -> c", "1 -> d", "2 -> d",
+            ]))
+            .unwrap_err();
+
+            assert_eq!(
+                report.to_string(),
+                "wire d is driven by 2 signals\n\
+                 cycle c -> c\n\
+                 wire a is unresolved, it reads x where x is never driven\n\
+                 wire b is unresolved, it reads a <- x where x is never driven\n\
+                 wire c is unresolved, it is part of a cycle\n\
+                 wire x is unresolved, it is never driven\n"
+            );
+        }
+    }
+}
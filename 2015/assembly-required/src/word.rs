@@ -0,0 +1,158 @@
+use std::fmt;
+use std::hash::Hash;
+
+/// The value carried by a wire, a fixed number of bits wide. Every gate works on the bits as an unsigned number no
+/// wider than 64 bits, dropping any bit which does not fit.
+pub trait Word: Copy + Eq + Hash + fmt::Debug + fmt::Display {
+    /// Width of the word in bits
+    const BITS: u32;
+
+    /// The value, provided it fits in the word
+    fn from_u64(value: u64) -> Option<Self>;
+
+    /// The value with any bit beyond the width of the word dropped
+    fn truncate(value: u64) -> Self;
+
+    fn to_u64(self) -> u64;
+
+    /// The word with every bit set
+    fn ones() -> Self {
+        Self::truncate(u64::MAX)
+    }
+
+    fn not(self) -> Self {
+        Self::truncate(!self.to_u64())
+    }
+
+    fn and(self, other: Self) -> Self {
+        Self::truncate(self.to_u64() & other.to_u64())
+    }
+
+    fn or(self, other: Self) -> Self {
+        Self::truncate(self.to_u64() | other.to_u64())
+    }
+
+    fn xor(self, other: Self) -> Self {
+        Self::truncate(self.to_u64() ^ other.to_u64())
+    }
+
+    /// Shifts the bits towards the most significant end, shifting by the width of the word or more leaves nothing
+    fn shl(self, places: Self) -> Self {
+        match places.to_u64() {
+            places if places < Self::BITS as u64 => Self::truncate(self.to_u64() << places),
+            _ => Self::truncate(0),
+        }
+    }
+
+    /// Shifts the bits towards the least significant end, shifting by the width of the word or more leaves nothing
+    fn shr(self, places: Self) -> Self {
+        match places.to_u64() {
+            places if places < Self::BITS as u64 => Self::truncate(self.to_u64() >> places),
+            _ => Self::truncate(0),
+        }
+    }
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(
+            impl Word for $t {
+                const BITS: u32 = <$t>::BITS;
+
+                fn from_u64(value: u64) -> Option<Self> {
+                    <$t>::try_from(value).ok()
+                }
+
+                fn truncate(value: u64) -> Self {
+                    value as $t
+                }
+
+                fn to_u64(self) -> u64 {
+                    self as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u8, u16, u32, u64);
+
+/// A word of any width from 1 to 64 bits
+///
+/// # Examples
+///
+/// ```rust
+/// # use assembly_required::word::{Masked, Word};
+/// let a = Masked::<12>::from_u64(0xf0f).unwrap();
+/// assert_eq!(a.not(), Masked::<12>::truncate(0x0f0));
+/// assert_eq!(Masked::<12>::from_u64(4096), None);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Masked<const BITS: u32>(u64);
+
+impl<const BITS: u32> Masked<BITS> {
+    const MASK: u64 = {
+        assert!(
+            BITS >= 1 && BITS <= 64,
+            "Words must be between 1 and 64 bits wide"
+        );
+        u64::MAX >> (64 - BITS)
+    };
+}
+
+impl<const BITS: u32> Word for Masked<BITS> {
+    const BITS: u32 = BITS;
+
+    fn from_u64(value: u64) -> Option<Self> {
+        (value & !Self::MASK == 0).then_some(Masked(value))
+    }
+
+    fn truncate(value: u64) -> Self {
+        Masked(value & Self::MASK)
+    }
+
+    fn to_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl<const BITS: u32> fmt::Display for Masked<BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod word {
+        use super::super::*;
+
+        #[test]
+        fn test_from_u64() {
+            assert_eq!(u8::from_u64(255), Some(255));
+            assert_eq!(u8::from_u64(256), None);
+            assert_eq!(Masked::<3>::from_u64(7), Some(Masked(7)));
+            assert_eq!(Masked::<3>::from_u64(8), None);
+            assert_eq!(Masked::<64>::from_u64(u64::MAX), Some(Masked(u64::MAX)));
+        }
+
+        #[test]
+        fn test_gates() {
+            assert_eq!(0x0fu8.not(), 0xf0);
+            assert_eq!(0x0fu16.not(), 0xfff0);
+            assert_eq!(Masked::<5>(0b00110).not(), Masked(0b11001));
+            assert_eq!(Masked::<5>(0b00110).xor(Masked(0b10100)), Masked(0b10010));
+            assert_eq!(u32::ones(), u32::MAX);
+        }
+
+        #[test]
+        fn test_shifts_truncate() {
+            assert_eq!(0x81u8.shl(1), 0x02);
+            assert_eq!(0x81u8.shl(8), 0);
+            assert_eq!(0x81u8.shr(7), 1);
+            assert_eq!(Masked::<4>(0b1011).shl(Masked(2)), Masked(0b1100));
+            assert_eq!(Masked::<4>(0b1011).shr(Masked(4)), Masked(0));
+            assert_eq!(1u64.shl(63), 1 << 63);
+        }
+    }
+}
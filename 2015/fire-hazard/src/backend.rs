@@ -0,0 +1,331 @@
+use adventofcode::grid::Grid;
+use std::ops::Index;
+
+/// How a display stores its lights
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// One cell per light, every instruction touches each light it covers
+    #[default]
+    Dense,
+    /// One cell per block of lights which every instruction so far has treated alike, so the work depends on the
+    /// number of instructions rather than the size of the display
+    Compressed,
+}
+
+/// Lights stored by either backend, addressed by `(x, y)` position
+#[derive(Clone, Debug, PartialEq)]
+pub enum Lights<T> {
+    Dense(Grid<T>),
+    Compressed(CompressedGrid<T>),
+}
+
+impl<T: Clone> Lights<T> {
+    pub fn new(width: usize, height: usize, value: T, backend: Backend) -> Lights<T> {
+        match backend {
+            Backend::Dense => Lights::Dense(Grid::new(width, height, value)),
+            Backend::Compressed => Lights::Compressed(CompressedGrid::new(width, height, value)),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        match self {
+            Lights::Dense(grid) => grid.width(),
+            Lights::Compressed(grid) => grid.width(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            Lights::Dense(grid) => grid.height(),
+            Lights::Compressed(grid) => grid.height(),
+        }
+    }
+
+    /// Number of lights
+    pub fn len(&self) -> usize {
+        match self {
+            Lights::Dense(grid) => grid.len(),
+            Lights::Compressed(grid) => grid.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        match self {
+            Lights::Dense(grid) => grid.get(x, y),
+            Lights::Compressed(grid) => grid.get(x, y),
+        }
+    }
+
+    /// Calls `f` on the lights within the rectangle with inclusive corners `tl` and `br`, ignoring any part of the
+    /// rectangle outside the display
+    pub fn update(&mut self, tl: (usize, usize), br: (usize, usize), f: impl FnMut(&mut T)) {
+        match self {
+            Lights::Dense(grid) => grid.rect_mut(tl, br).for_each(f),
+            Lights::Compressed(grid) => grid.update(tl, br, f),
+        }
+    }
+
+    /// The lights as blocks of equal lights, each mapped with `f`. A dense display has one block per light.
+    pub fn blocks<U>(&self, f: impl FnMut(&T) -> U) -> CompressedGrid<U> {
+        match self {
+            Lights::Dense(grid) => CompressedGrid {
+                width: grid.width(),
+                height: grid.height(),
+                columns: (0..grid.width()).collect(),
+                rows: (0..grid.height()).collect(),
+                blocks: grid.map(f),
+            },
+            Lights::Compressed(grid) => CompressedGrid {
+                width: grid.width,
+                height: grid.height,
+                columns: grid.columns.clone(),
+                rows: grid.rows.clone(),
+                blocks: grid.blocks.map(f),
+            },
+        }
+    }
+
+    /// Sum of `f` over every light
+    pub fn total(&self, f: impl Fn(&T) -> usize) -> usize {
+        match self {
+            Lights::Dense(grid) => grid.iter().map(f).sum(),
+            Lights::Compressed(grid) => grid.blocks().map(|(light, size)| f(light) * size).sum(),
+        }
+    }
+}
+
+impl<T: Clone> Index<(usize, usize)> for Lights<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).expect("Light outside the display")
+    }
+}
+
+/// A grid split into rectangular blocks of equal cells, blocks are split further whenever an update covers only
+/// part of them
+///
+/// # Examples
+///
+/// ```rust
+/// # use fire_hazard::backend::CompressedGrid;
+/// let mut grid = CompressedGrid::new(1_000_000, 1_000_000, 0);
+/// grid.update((10, 10), (999_999, 19), |cell| *cell += 1);
+///
+/// assert_eq!(grid.get(10, 19), Some(&1));
+/// assert_eq!(grid.get(10, 20), Some(&0));
+/// assert_eq!(grid.blocks().map(|(cell, size)| cell * size).sum::<usize>(), 9_999_900);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompressedGrid<T> {
+    width: usize,
+    height: usize,
+    columns: Vec<usize>,
+    rows: Vec<usize>,
+    blocks: Grid<T>,
+}
+
+impl<T: Clone> CompressedGrid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> CompressedGrid<T> {
+        let start = |size: usize| if size == 0 { vec![] } else { vec![0] };
+        let (columns, rows) = (start(width), start(height));
+
+        CompressedGrid {
+            blocks: Grid::new(columns.len(), rows.len(), value),
+            width,
+            height,
+            columns,
+            rows,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.blocks
+            .get(block(&self.columns, x), block(&self.rows, y))
+    }
+
+    /// Calls `f` once on each block within the rectangle with inclusive corners `tl` and `br`, splitting the
+    /// blocks on its edges first. Any part of the rectangle outside the grid is ignored.
+    pub fn update(&mut self, tl: (usize, usize), br: (usize, usize), f: impl FnMut(&mut T)) {
+        let right = br.0.saturating_add(1).min(self.width);
+        let bottom = br.1.saturating_add(1).min(self.height);
+        let (left, top) = (tl.0.min(right), tl.1.min(bottom));
+
+        if left == right || top == bottom {
+            return;
+        }
+
+        self.split(&[left, right], &[top, bottom]);
+
+        let (first_column, last_column) =
+            (block(&self.columns, left), block(&self.columns, right - 1));
+        let (first_row, last_row) = (block(&self.rows, top), block(&self.rows, bottom - 1));
+        self.blocks
+            .rect_mut((first_column, first_row), (last_column, last_row))
+            .for_each(f);
+    }
+
+    /// Position each column of blocks starts at
+    pub fn columns(&self) -> &[usize] {
+        &self.columns
+    }
+
+    /// Position each row of blocks starts at
+    pub fn rows(&self) -> &[usize] {
+        &self.rows
+    }
+
+    /// The value of the block in the given column and row of blocks
+    pub fn block(&self, column: usize, row: usize) -> &T {
+        &self.blocks[(column, row)]
+    }
+
+    pub(crate) fn block_mut(&mut self, column: usize, row: usize) -> &mut T {
+        &mut self.blocks[(column, row)]
+    }
+
+    /// Each block with the number of cells it covers
+    pub fn blocks(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.blocks.positions().map(|((column, row), cell)| {
+            let width = extent(&self.columns, column, self.width);
+            let height = extent(&self.rows, row, self.height);
+            (cell, width * height)
+        })
+    }
+
+    /// Starts new blocks at each of the given columns and rows, keeping the value of the block they were cut from
+    pub(crate) fn split(&mut self, columns: &[usize], rows: &[usize]) {
+        let new_columns = cut(&self.columns, columns, self.width);
+        let new_rows = cut(&self.rows, rows, self.height);
+
+        if new_columns.len() == self.columns.len() && new_rows.len() == self.rows.len() {
+            return;
+        }
+
+        let from_column: Vec<usize> = new_columns
+            .iter()
+            .map(|&x| block(&self.columns, x))
+            .collect();
+        let from_row: Vec<usize> = new_rows.iter().map(|&y| block(&self.rows, y)).collect();
+
+        self.blocks = Grid::from_fn(new_columns.len(), new_rows.len(), |column, row| {
+            self.blocks[(from_column[column], from_row[row])].clone()
+        });
+        self.columns = new_columns;
+        self.rows = new_rows;
+    }
+}
+
+impl<T: Clone> Index<(usize, usize)> for CompressedGrid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).expect("Cell outside the grid")
+    }
+}
+
+/// Index of the block holding `position`, given the position each block starts at
+pub(crate) fn block(starts: &[usize], position: usize) -> usize {
+    starts.partition_point(|&start| start <= position) - 1
+}
+
+/// Number of positions covered by a block
+pub(crate) fn extent(starts: &[usize], block: usize, size: usize) -> usize {
+    starts.get(block + 1).copied().unwrap_or(size) - starts[block]
+}
+
+/// Block starts with `cuts` added, leaving out any cut at or beyond the end
+fn cut(starts: &[usize], cuts: &[usize], size: usize) -> Vec<usize> {
+    let mut starts: Vec<usize> = starts
+        .iter()
+        .chain(cuts.iter().filter(|&&cut| cut < size))
+        .copied()
+        .collect();
+    starts.sort_unstable();
+    starts.dedup();
+    starts
+}
+
+#[cfg(test)]
+mod test {
+    mod compressed_grid {
+        use super::super::*;
+
+        #[test]
+        fn test_split() {
+            let mut grid = CompressedGrid::new(10, 10, 0);
+
+            grid.update((2, 3), (4, 5), |cell| *cell += 1);
+            grid.update((4, 0), (9, 3), |cell| *cell += 2);
+
+            assert_eq!(grid.columns, vec![0, 2, 4, 5]);
+            assert_eq!(grid.rows, vec![0, 3, 4, 6]);
+            assert_eq!(grid[(2, 3)], 1);
+            assert_eq!(grid[(4, 3)], 3);
+            assert_eq!(grid[(9, 0)], 2);
+            assert_eq!(grid[(5, 5)], 0);
+        }
+
+        #[test]
+        fn test_matches_dense() {
+            let mut compressed = CompressedGrid::new(20, 15, 0usize);
+            let mut dense = Grid::new(20, 15, 0usize);
+            let rects = [
+                ((0, 0), (19, 14)),
+                ((3, 4), (8, 30)),
+                ((7, 7), (7, 7)),
+                ((12, 1), (40, 9)),
+                ((5, 5), (2, 9)),
+            ];
+
+            for (n, (tl, br)) in rects.into_iter().enumerate() {
+                compressed.update(tl, br, |cell| *cell = *cell * 3 + n);
+                dense
+                    .rect_mut(tl, br)
+                    .for_each(|cell| *cell = *cell * 3 + n);
+            }
+
+            for ((x, y), cell) in dense.positions() {
+                assert_eq!(compressed.get(x, y), Some(cell), "({}, {})", x, y);
+            }
+            assert_eq!(compressed.get(20, 0), None);
+            assert_eq!(
+                compressed.blocks().map(|(_, size)| size).sum::<usize>(),
+                300
+            );
+        }
+
+        #[test]
+        fn test_empty() {
+            let mut grid = CompressedGrid::new(0, 5, true);
+            grid.update((0, 0), (3, 3), |cell| *cell = false);
+
+            assert!(grid.is_empty());
+            assert_eq!(grid.blocks().count(), 0);
+        }
+    }
+}
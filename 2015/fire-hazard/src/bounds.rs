@@ -0,0 +1,233 @@
+use crate::Instruction;
+use std::{error, fmt};
+
+/// What to do with an instruction reaching past the edge of the display
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutOfBounds {
+    /// Refuse the instruction
+    Error,
+    /// Drop the part of the rectangle outside the display
+    #[default]
+    Clip,
+    /// Carry the rectangle over onto the opposite edge, as if the display were the surface of a torus
+    Wrap,
+}
+
+/// An instruction reaching past the edge of a display which does not allow it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoundsError {
+    pub instruction: Instruction,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl fmt::Display for BoundsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' reaches outside the {}x{} display",
+            self.instruction, self.width, self.height
+        )
+    }
+}
+
+impl error::Error for BoundsError {}
+
+impl Instruction {
+    /// The top left and bottom right corners of the rectangle, both inclusive
+    pub fn corners(&self) -> ((usize, usize), (usize, usize)) {
+        match *self {
+            Instruction::TurnOn(tl, br)
+            | Instruction::TurnOff(tl, br)
+            | Instruction::Toggle(tl, br) => (tl, br),
+        }
+    }
+
+    /// The same action over another rectangle
+    pub fn with_corners(&self, tl: (usize, usize), br: (usize, usize)) -> Instruction {
+        match self {
+            Instruction::TurnOn(_, _) => Instruction::TurnOn(tl, br),
+            Instruction::TurnOff(_, _) => Instruction::TurnOff(tl, br),
+            Instruction::Toggle(_, _) => Instruction::Toggle(tl, br),
+        }
+    }
+
+    /// The instruction with its corners swapped along any axis they were given in reverse, so the first is always
+    /// the top left
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use fire_hazard::Instruction;
+    /// let instruction = Instruction::Toggle((5, 1), (2, 8));
+    /// assert_eq!(instruction.normalized(), Instruction::Toggle((2, 1), (5, 8)));
+    /// ```
+    pub fn normalized(&self) -> Instruction {
+        let (a, b) = self.corners();
+        self.with_corners((a.0.min(b.0), a.1.min(b.1)), (a.0.max(b.0), a.1.max(b.1)))
+    }
+
+    /// Normalizes the instruction and fits it onto a display of the given size, returning the instructions which
+    /// cover the rectangle with no corner outside the display. Clipping drops any instruction left with nothing
+    /// to cover, while wrapping can split one instruction into up to four.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use fire_hazard::Instruction;
+    /// # use fire_hazard::bounds::OutOfBounds;
+    /// let instruction = Instruction::TurnOn((8, 2), (11, 3));
+    ///
+    /// assert!(instruction.fit(10, 10, OutOfBounds::Error).is_err());
+    /// assert_eq!(
+    ///     instruction.fit(10, 10, OutOfBounds::Clip),
+    ///     Ok(vec![Instruction::TurnOn((8, 2), (9, 3))])
+    /// );
+    /// assert_eq!(
+    ///     instruction.fit(10, 10, OutOfBounds::Wrap),
+    ///     Ok(vec![Instruction::TurnOn((8, 2), (9, 3)), Instruction::TurnOn((0, 2), (1, 3))])
+    /// );
+    /// ```
+    pub fn fit(
+        &self,
+        width: usize,
+        height: usize,
+        bounds: OutOfBounds,
+    ) -> Result<Vec<Instruction>, BoundsError> {
+        let normalized = self.normalized();
+        let (tl, br) = normalized.corners();
+
+        if br.0 < width && br.1 < height {
+            return Ok(vec![normalized]);
+        }
+
+        let columns = match bounds {
+            OutOfBounds::Error => {
+                return Err(BoundsError {
+                    instruction: *self,
+                    width,
+                    height,
+                })
+            }
+            OutOfBounds::Clip => clip(tl.0, br.0, width),
+            OutOfBounds::Wrap => wrap(tl.0, br.0, width),
+        };
+        let rows = match bounds {
+            OutOfBounds::Clip => clip(tl.1, br.1, height),
+            _ => wrap(tl.1, br.1, height),
+        };
+
+        Ok(rows
+            .iter()
+            .flat_map(|&(top, bottom)| {
+                columns.iter().map(move |&(left, right)| {
+                    normalized.with_corners((left, top), (right, bottom))
+                })
+            })
+            .collect())
+    }
+}
+
+/// The part of the inclusive span `from..=to` within `0..size`
+fn clip(from: usize, to: usize, size: usize) -> Vec<(usize, usize)> {
+    if from >= size {
+        return vec![];
+    }
+
+    vec![(from, to.min(size - 1))]
+}
+
+/// The inclusive span `from..=to` wrapped onto `0..size`, in at most two pieces
+fn wrap(from: usize, to: usize, size: usize) -> Vec<(usize, usize)> {
+    if size == 0 {
+        return vec![];
+    }
+    if to - from >= size - 1 {
+        return vec![(0, size - 1)];
+    }
+
+    let start = from % size;
+    let end = start + (to - from);
+
+    if end < size {
+        vec![(start, end)]
+    } else {
+        vec![(start, size - 1), (0, end - size)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod instruction {
+        use super::super::*;
+
+        #[test]
+        fn test_normalized() {
+            assert_eq!(
+                Instruction::TurnOff((9, 9), (0, 0)).normalized(),
+                Instruction::TurnOff((0, 0), (9, 9))
+            );
+            assert_eq!(
+                Instruction::TurnOn((0, 7), (3, 2)).normalized(),
+                Instruction::TurnOn((0, 2), (3, 7))
+            );
+        }
+
+        #[test]
+        fn test_fit_inside() {
+            let instruction = Instruction::Toggle((9, 9), (0, 0));
+
+            for bounds in [OutOfBounds::Error, OutOfBounds::Clip, OutOfBounds::Wrap] {
+                assert_eq!(
+                    instruction.fit(10, 10, bounds),
+                    Ok(vec![Instruction::Toggle((0, 0), (9, 9))])
+                );
+            }
+        }
+
+        #[test]
+        fn test_fit_error() {
+            let error = Instruction::TurnOn((0, 0), (3, 10))
+                .fit(10, 10, OutOfBounds::Error)
+                .unwrap_err();
+
+            assert_eq!(
+                error.to_string(),
+                "'turn on 0,0 through 3,10' reaches outside the 10x10 display"
+            );
+        }
+
+        #[test]
+        fn test_fit_clip() {
+            assert_eq!(
+                Instruction::TurnOn((12, 0), (15, 3)).fit(10, 10, OutOfBounds::Clip),
+                Ok(vec![])
+            );
+            assert_eq!(
+                Instruction::TurnOn((2, 25), (15, 3)).fit(10, 10, OutOfBounds::Clip),
+                Ok(vec![Instruction::TurnOn((2, 3), (9, 9))])
+            );
+        }
+
+        #[test]
+        fn test_fit_wrap() {
+            assert_eq!(
+                Instruction::Toggle((8, 9), (10, 10)).fit(10, 10, OutOfBounds::Wrap),
+                Ok(vec![
+                    Instruction::Toggle((8, 9), (9, 9)),
+                    Instruction::Toggle((0, 9), (0, 9)),
+                    Instruction::Toggle((8, 0), (9, 0)),
+                    Instruction::Toggle((0, 0), (0, 0)),
+                ])
+            );
+            assert_eq!(
+                Instruction::Toggle((13, 25), (14, 40)).fit(10, 10, OutOfBounds::Wrap),
+                Ok(vec![Instruction::Toggle((3, 0), (4, 9))])
+            );
+            assert_eq!(
+                Instruction::Toggle((13, 0), (14, 0)).fit(0, 10, OutOfBounds::Wrap),
+                Ok(vec![])
+            );
+        }
+    }
+}
@@ -1,8 +1,22 @@
 #[macro_use]
 extern crate lazy_static;
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
+use backend::{Backend, Lights};
+use bounds::{BoundsError, OutOfBounds};
+use model::{Dimmer, LightModel, Switch};
+use query::SummedArea;
 use regex::Regex;
+use std::cell::RefCell;
+use std::fmt;
 
-#[derive(Debug, PartialEq)]
+pub mod backend;
+pub mod bounds;
+pub mod model;
+pub mod query;
+pub mod render;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Instruction {
     TurnOn((usize, usize), (usize, usize)),
     TurnOff((usize, usize), (usize, usize)),
@@ -10,203 +24,196 @@ pub enum Instruction {
 }
 
 impl Instruction {
-    pub fn parse(instruction: &str) -> Instruction {
+    pub fn parse(instruction: &str) -> Result<Instruction, ParseError> {
         lazy_static! {
-            static ref INSTRUCTION_RE: Regex = Regex::new(r"(toggle|turn on|turn off) (\d+),(\d+) through (\d+),(\d+)").expect("Could not compile instruction regular expression");
+            static ref INSTRUCTION_RE: Regex =
+                Regex::new(r"^(toggle|turn on|turn off) (\d+),(\d+) through (\d+),(\d+)$")
+                    .expect("Could not compile instruction regular expression");
         }
 
-        let cap = INSTRUCTION_RE
-                .captures(instruction)
-                .expect("Could not find any captures");
-        let tl = (
-            cap.get(2)
-                .expect("TL X coordinate not found")
-                .as_str()
-                .parse::<usize>()
-                .expect("Could not parse coordinate"),
-            cap.get(3)
-                .expect("TL Y coordinate not found")
-                .as_str()
-                .parse::<usize>()
-                .expect("Could not parse coordinate"),
-        );
-        let br = (
-            cap.get(4)
-                .expect("BR X coordinate not found")
-                .as_str()
-                .parse::<usize>()
-                .expect("Could not parse coordinate"),
-            cap.get(5)
-                .expect("BR Y coordinate not found")
-                .as_str()
+        let cap = match INSTRUCTION_RE.captures(instruction) {
+            Some(cap) => cap,
+            None if !["toggle ", "turn on ", "turn off "]
+                .iter()
+                .any(|action| instruction.starts_with(action)) =>
+            {
+                let action = instruction.split(' ').take(2).collect::<Vec<_>>().join(" ");
+                return Err(ParseError::invalid(
+                    action,
+                    "'turn on', 'turn off' or 'toggle'",
+                ));
+            }
+            None => {
+                return Err(ParseError::invalid(
+                    instruction,
+                    "an instruction in the form '<action> X,Y through X,Y'",
+                ))
+            }
+        };
+        let coordinate = |i: usize| {
+            let m = cap.get(i).expect("Coordinate not found");
+            m.as_str()
                 .parse::<usize>()
-                .expect("Could not parse coordinate"),
-        );
+                .map_err(|_| ParseError::new(m.start() + 1, m.as_str(), "a coordinate"))
+        };
+
+        let tl = (coordinate(2)?, coordinate(3)?);
+        let br = (coordinate(4)?, coordinate(5)?);
 
         match cap.get(1).expect("Could not match instruction").as_str() {
-            "turn on" => Instruction::TurnOn(tl, br),
-            "turn off" => Instruction::TurnOff(tl, br),
-            "toggle" => Instruction::Toggle(tl, br),
-            inst => panic!("Unexpected instruction encountered {}", inst),
+            "turn on" => Ok(Instruction::TurnOn(tl, br)),
+            "turn off" => Ok(Instruction::TurnOff(tl, br)),
+            _ => Ok(Instruction::Toggle(tl, br)),
         }
     }
 }
 
-fn x_y_to_index(width: &usize, x: &usize, y: &usize) -> usize {
-    (y * width) + x
-}
-
-fn rect_to_ranges(width: &usize, tl: &(usize, usize), br: &(usize, usize)) -> Vec<(usize, usize)> {
-    let mut res = Vec::with_capacity(br.1 - tl.1);
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self {
+            Instruction::TurnOn(_, _) => "turn on",
+            Instruction::TurnOff(_, _) => "turn off",
+            Instruction::Toggle(_, _) => "toggle",
+        };
+        let (tl, br) = self.corners();
 
-    for i in tl.1..=br.1 {
-        let range = (
-            x_y_to_index(width, &tl.0, &i),
-            x_y_to_index(width, &br.0, &i),
-        );
-        res.push(range);
+        write!(f, "{} {},{} through {},{}", action, tl.0, tl.1, br.0, br.1)
     }
-
-    res
 }
 
-pub struct LightDisplay {
-    width: usize,
-    lights: Vec<bool>,
+/// A grid of lights following the instructions according to a [`LightModel`]
+pub struct Display<M: LightModel> {
+    model: M,
+    lights: Lights<M::State>,
+    bounds: OutOfBounds,
+    summed: RefCell<Option<SummedArea>>,
 }
 
-impl LightDisplay {
-    pub fn new(width: usize, height: usize) -> LightDisplay {
-        let lights = vec![false; width * height];
+/// Lights which are either on or off
+pub type LightDisplay = Display<Switch>;
+
+/// Lights with a brightness, see [`Dimmer`]
+pub type DimmableLightDisplay = Display<Dimmer>;
 
-        LightDisplay { width, lights }
+impl<M: LightModel + Default> Display<M> {
+    pub fn new(width: usize, height: usize) -> Display<M> {
+        Display::with_backend(width, height, Backend::Dense)
     }
 
-    pub fn process_instructions(&mut self, instructions: &Vec<Instruction>) {
-        for instruction in instructions {
-            self.process_instruction(instruction);
-        }
+    pub fn with_backend(width: usize, height: usize, backend: Backend) -> Display<M> {
+        Display::with_model(width, height, M::default(), backend)
     }
+}
 
-    fn process_instruction(&mut self, instruction: &Instruction) {
-        match instruction {
-            Instruction::TurnOn(tl, br) => {
-                let ranges = rect_to_ranges(&self.width, tl, br);
-                for range in ranges {
-                    for i in range.0..=range.1 {
-                        if let Some(light) = self.lights.get_mut(i) {
-                            *light = true
-                        }
-                    }
-                }
-            }
-            Instruction::TurnOff(tl, br) => {
-                let ranges = rect_to_ranges(&self.width, tl, br);
-                for range in ranges {
-                    for i in range.0..=range.1 {
-                        if let Some(light) = self.lights.get_mut(i) {
-                            *light = false
-                        }
-                    }
-                }
-            }
-            Instruction::Toggle(tl, br) => {
-                let ranges = rect_to_ranges(&self.width, tl, br);
-                for range in ranges {
-                    for i in range.0..=range.1 {
-                        if let Some(light) = self.lights.get_mut(i) {
-                            *light = !*light
-                        }
-                    }
-                }
-            }
+impl<M: LightModel> Display<M> {
+    pub fn with_model(width: usize, height: usize, model: M, backend: Backend) -> Display<M> {
+        Display {
+            lights: Lights::new(width, height, model.initial(), backend),
+            model,
+            bounds: OutOfBounds::default(),
+            summed: RefCell::default(),
         }
     }
 
-    pub fn total_lit(&self) -> usize {
-        self.lights.iter().filter(|e| **e).count()
+    /// Handles instructions reaching past the edge of the display with `bounds` rather than clipping them
+    pub fn with_bounds(mut self, bounds: OutOfBounds) -> Display<M> {
+        self.bounds = bounds;
+        self
     }
-}
 
-pub struct DimmableLightDisplay {
-    width: usize,
-    lights: Vec<usize>,
-}
-
-impl DimmableLightDisplay {
-    pub fn new(width: usize, height: usize) -> DimmableLightDisplay {
-        let lights = vec![0; width * height];
+    /// Follows the instructions in order, or none of them if any reaches outside a display which does not allow it
+    pub fn process_instructions(
+        &mut self,
+        instructions: &[Instruction],
+    ) -> Result<(), BoundsError> {
+        let fitted = instructions
+            .iter()
+            .map(|instruction| self.fit(instruction))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        fitted
+            .iter()
+            .flatten()
+            .for_each(|instruction| self.apply(instruction));
+        Ok(())
+    }
 
-        DimmableLightDisplay { width, lights }
+    fn process_instruction(&mut self, instruction: &Instruction) -> Result<(), BoundsError> {
+        self.fit(instruction)?
+            .iter()
+            .for_each(|instruction| self.apply(instruction));
+        Ok(())
     }
 
-    pub fn process_instructions(&mut self, instructions: &Vec<Instruction>) {
-        for instruction in instructions {
-            self.process_instruction(&instruction);
-        }
+    fn fit(&self, instruction: &Instruction) -> Result<Vec<Instruction>, BoundsError> {
+        instruction.fit(self.lights.width(), self.lights.height(), self.bounds)
     }
 
-    fn process_instruction(&mut self, instruction: &Instruction) {
+    /// Follows an instruction which lies within the display
+    fn apply(&mut self, instruction: &Instruction) {
+        let model = &self.model;
+
         match instruction {
             Instruction::TurnOn(tl, br) => {
-                let ranges = rect_to_ranges(&self.width, tl, br);
-                for range in ranges {
-                    for i in range.0..=range.1 {
-                        if let Some(light) = self.lights.get_mut(i) {
-                            *light = *light + 1
-                        }
-                    }
-                }
+                self.lights.update(*tl, *br, |light| model.turn_on(light))
             }
             Instruction::TurnOff(tl, br) => {
-                let ranges = rect_to_ranges(&self.width, tl, br);
-                for range in ranges {
-                    for i in range.0..=range.1 {
-                        if let Some(light) = self.lights.get_mut(i) {
-                            if *light != 0 {
-                                *light = *light - 1
-                            }
-                        }
-                    }
-                }
+                self.lights.update(*tl, *br, |light| model.turn_off(light))
             }
             Instruction::Toggle(tl, br) => {
-                let ranges = rect_to_ranges(&self.width, tl, br);
-                for range in ranges {
-                    for i in range.0..=range.1 {
-                        if let Some(light) = self.lights.get_mut(i) {
-                            *light = *light + 2
-                        }
-                    }
-                }
+                self.lights.update(*tl, *br, |light| model.toggle(light))
             }
         }
+
+        if let Some(summed) = self.summed.get_mut() {
+            let (tl, br) = instruction.corners();
+            summed.update(tl, br, |position| model.brightness(&self.lights[position]));
+        }
+    }
+
+    /// Number of lights with any brightness
+    pub fn total_lit(&self) -> usize {
+        self.lights
+            .total(|light| (self.model.brightness(light) > 0) as usize)
     }
 
     pub fn total_brightness(&self) -> usize {
-        self.lights.iter().fold(0, |acc, ele| acc + ele)
+        self.lights.total(|light| self.model.brightness(light))
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// Day 6: Probably a Fire Hazard
+pub struct FireHazard;
+
+impl Solution for FireHazard {
+    const YEAR: u16 = 2015;
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Probably a Fire Hazard";
+
+    type Input = Vec<Instruction>;
+    type Answer = usize;
 
-    #[test]
-    fn test_x_y_to_index() {
-        assert_eq!(x_y_to_index(&10, &0, &0), 0);
-        assert_eq!(x_y_to_index(&10, &1, &1), 11)
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_lines(input.trim_end(), Instruction::parse)
     }
 
-    #[test]
-    fn test_rect_to_ranges() {
-        let ranges = rect_to_ranges(&1000, &(0, 0), &(1, 1));
-        let expected = vec![(0, 1), (1000, 1001)];
+    fn part1(instructions: &Self::Input) -> Self::Answer {
+        let mut ld = LightDisplay::with_backend(1000, 1000, Backend::Compressed);
+        ld.process_instructions(instructions)
+            .expect("Clipped instructions always fit");
+        ld.total_lit()
+    }
 
-        assert_eq!(ranges.len(), expected.len());
-        assert_eq!(ranges, expected);
+    fn part2(instructions: &Self::Input) -> Self::Answer {
+        let mut rd = DimmableLightDisplay::with_backend(1000, 1000, Backend::Compressed);
+        rd.process_instructions(instructions)
+            .expect("Clipped instructions always fit");
+        rd.total_brightness()
     }
+}
 
+#[cfg(test)]
+mod test {
     mod instruction {
         use super::super::*;
 
@@ -214,12 +221,12 @@ mod test {
         fn test_parse_turn_on() {
             let raw = "turn on 0,0 through 0,0";
             let expected = Instruction::TurnOn((0, 0), (0, 0));
-            let result = Instruction::parse(raw);
+            let result = Instruction::parse(raw).unwrap();
             assert_eq!(expected, result);
 
             let raw = "turn on 0,0 through 1,1";
             let expected = Instruction::TurnOn((0, 0), (1, 1));
-            let result = Instruction::parse(raw);
+            let result = Instruction::parse(raw).unwrap();
             assert_eq!(expected, result);
         }
 
@@ -227,12 +234,12 @@ mod test {
         fn test_parse_turn_off() {
             let raw = "turn off 0,0 through 0,0";
             let expected = Instruction::TurnOff((0, 0), (0, 0));
-            let result = Instruction::parse(raw);
+            let result = Instruction::parse(raw).unwrap();
             assert_eq!(expected, result);
 
             let raw = "turn off 0,0 through 1,1";
             let expected = Instruction::TurnOff((0, 0), (1, 1));
-            let result = Instruction::parse(raw);
+            let result = Instruction::parse(raw).unwrap();
             assert_eq!(expected, result);
         }
 
@@ -240,14 +247,30 @@ mod test {
         fn test_parse_toggle() {
             let raw = "toggle 0,0 through 0,0";
             let expected = Instruction::Toggle((0, 0), (0, 0));
-            let result = Instruction::parse(raw);
+            let result = Instruction::parse(raw).unwrap();
             assert_eq!(expected, result);
 
             let raw = "toggle 0,0 through 1,1";
             let expected = Instruction::Toggle((0, 0), (1, 1));
-            let result = Instruction::parse(raw);
+            let result = Instruction::parse(raw).unwrap();
             assert_eq!(expected, result);
         }
+
+        #[test]
+        fn test_parse_invalid() {
+            let error = Instruction::parse("turn up 0,0 through 1,1").unwrap_err();
+            assert_eq!((error.column, error.text.as_str()), (1, "turn up"));
+
+            let error = Instruction::parse("toggle 0,0 to 1,1").unwrap_err();
+            assert_eq!(error.text, "toggle 0,0 to 1,1");
+
+            let error =
+                Instruction::parse("turn off 0,0 through 1,99999999999999999999999").unwrap_err();
+            assert_eq!(
+                (error.column, error.text.as_str()),
+                (24, "99999999999999999999999")
+            );
+        }
     }
 
     mod light_display {
@@ -258,45 +281,78 @@ mod test {
             let ld = LightDisplay::new(1000, 1000);
             let expected = false;
 
-            assert_eq!(ld.lights.capacity(), 1_000_000);
-            assert_eq!(ld.lights.get(0), Some(&expected));
+            assert_eq!(ld.lights.len(), 1_000_000);
+            assert_eq!(ld.lights.get(0, 0), Some(&expected));
         }
 
         #[test]
         fn test_process_instruction_turn_on() {
             let mut ld = LightDisplay::new(10, 10);
-            ld.process_instruction(&Instruction::TurnOn((0, 0), (0, 0)));
+            ld.process_instruction(&Instruction::TurnOn((0, 0), (0, 0)))
+                .unwrap();
 
-            assert_eq!(ld.lights[0], true)
+            assert!(ld.lights[(0, 0)])
         }
 
         #[test]
         fn test_process_instruction_turn_off() {
             let mut ld = LightDisplay::new(10, 10);
-            ld.process_instruction(&Instruction::TurnOff((0, 0), (0, 0)));
+            ld.process_instruction(&Instruction::TurnOff((0, 0), (0, 0)))
+                .unwrap();
 
-            assert_eq!(ld.lights[0], false)
+            assert!(!ld.lights[(0, 0)])
         }
 
         #[test]
         fn test_process_instruction_toggle() {
             let mut ld = LightDisplay::new(10, 10);
 
-            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)));
-            assert_eq!(ld.lights[0], true);
+            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)))
+                .unwrap();
+            assert!(ld.lights[(0, 0)]);
 
-            ld.process_instruction(&Instruction::TurnOn((0, 1), (1, 1)));
-            assert_eq!(ld.lights[10], true);
-            assert_eq!(ld.lights[11], true);
+            ld.process_instruction(&Instruction::TurnOn((0, 1), (1, 1)))
+                .unwrap();
+            assert!(ld.lights[(0, 1)]);
+            assert!(ld.lights[(1, 1)]);
         }
 
         #[test]
         fn test_total_lit() {
             let mut ld = LightDisplay::new(10, 10);
 
-            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)));
+            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)))
+                .unwrap();
             assert_eq!(ld.total_lit(), 1);
         }
+
+        #[test]
+        fn test_backends_agree() {
+            let instructions = parse_lines(
+                "turn on 0,0 through 19,19\ntoggle 5,3 through 30,8\nturn off 2,2 through 6,17\ntoggle 0,7 through 9,7",
+                Instruction::parse,
+            )
+            .unwrap();
+            let mut dense = LightDisplay::new(20, 20);
+            let mut compressed = LightDisplay::with_backend(20, 20, Backend::Compressed);
+
+            dense.process_instructions(&instructions).unwrap();
+            compressed.process_instructions(&instructions).unwrap();
+
+            assert_eq!(compressed.total_lit(), dense.total_lit());
+            assert_eq!(compressed.lights[(5, 7)], dense.lights[(5, 7)]);
+        }
+
+        #[test]
+        fn test_large_display() {
+            let mut ld = LightDisplay::with_backend(1_000_000, 1_000_000, Backend::Compressed);
+
+            ld.process_instruction(&Instruction::TurnOn((0, 0), (999_999, 999_999)))
+                .unwrap();
+            ld.process_instruction(&Instruction::Toggle((1, 1), (999_998, 999_998)))
+                .unwrap();
+            assert_eq!(ld.total_lit(), 3_999_996);
+        }
     }
 
     mod dimmable_light_display {
@@ -307,45 +363,181 @@ mod test {
             let ld = DimmableLightDisplay::new(1000, 1000);
             let expected = 0;
 
-            assert_eq!(ld.lights.capacity(), 1_000_000);
-            assert_eq!(ld.lights.get(0), Some(&expected));
+            assert_eq!(ld.lights.len(), 1_000_000);
+            assert_eq!(ld.lights.get(0, 0), Some(&expected));
         }
 
         #[test]
         fn test_process_instruction_turn_on() {
             let mut ld = DimmableLightDisplay::new(10, 10);
-            ld.process_instruction(&Instruction::TurnOn((0, 0), (0, 0)));
+            ld.process_instruction(&Instruction::TurnOn((0, 0), (0, 0)))
+                .unwrap();
 
-            assert_eq!(ld.lights[0], 1)
+            assert_eq!(ld.lights[(0, 0)], 1)
         }
 
         #[test]
         fn test_process_instruction_turn_off() {
             let mut ld = DimmableLightDisplay::new(10, 10);
-            ld.process_instruction(&Instruction::TurnOff((0, 0), (0, 0)));
+            ld.process_instruction(&Instruction::TurnOff((0, 0), (0, 0)))
+                .unwrap();
 
-            assert_eq!(ld.lights[0], 0);
+            assert_eq!(ld.lights[(0, 0)], 0);
 
-            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)));
-            ld.process_instruction(&Instruction::TurnOff((0, 0), (0, 0)));
+            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)))
+                .unwrap();
+            ld.process_instruction(&Instruction::TurnOff((0, 0), (0, 0)))
+                .unwrap();
 
-            assert_eq!(ld.lights[0], 1);
+            assert_eq!(ld.lights[(0, 0)], 1);
         }
 
         #[test]
         fn test_process_instruction_toggle() {
             let mut ld = DimmableLightDisplay::new(10, 10);
 
-            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)));
-            assert_eq!(ld.lights[0], 2);
+            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)))
+                .unwrap();
+            assert_eq!(ld.lights[(0, 0)], 2);
         }
 
         #[test]
         fn test_total_brightness() {
             let mut ld = DimmableLightDisplay::new(10, 10);
 
-            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)));
+            ld.process_instruction(&Instruction::Toggle((0, 0), (0, 0)))
+                .unwrap();
             assert_eq!(ld.total_brightness(), 2);
         }
+
+        #[test]
+        fn test_backends_agree() {
+            let instructions = parse_lines(
+                "turn on 0,0 through 19,19\ntoggle 5,3 through 30,8\nturn off 2,2 through 6,17\nturn off 0,7 through 9,7",
+                Instruction::parse,
+            )
+            .unwrap();
+            let mut dense = DimmableLightDisplay::new(20, 20);
+            let mut compressed = DimmableLightDisplay::with_backend(20, 20, Backend::Compressed);
+
+            dense.process_instructions(&instructions).unwrap();
+            compressed.process_instructions(&instructions).unwrap();
+
+            assert_eq!(compressed.total_brightness(), dense.total_brightness());
+            assert_eq!(compressed.lights[(5, 7)], dense.lights[(5, 7)]);
+        }
+
+        #[test]
+        fn test_large_display() {
+            let mut ld =
+                DimmableLightDisplay::with_backend(1_000_000, 1_000_000, Backend::Compressed);
+
+            ld.process_instruction(&Instruction::TurnOn((0, 0), (999_999, 999_999)))
+                .unwrap();
+            ld.process_instruction(&Instruction::Toggle((1, 1), (999_998, 999_998)))
+                .unwrap();
+            assert_eq!(
+                ld.total_brightness(),
+                1_000_000_000_000 + 2 * 999_998 * 999_998
+            );
+        }
+    }
+
+    mod display {
+        use super::super::*;
+        use crate::model::{Capped, Cycling};
+
+        /// Turning on adds red, toggling rotates the channels and turning off clears them
+        struct Rgb;
+
+        impl LightModel for Rgb {
+            type State = [u8; 3];
+
+            fn initial(&self) -> [u8; 3] {
+                [0; 3]
+            }
+
+            fn turn_on(&self, light: &mut [u8; 3]) {
+                light[0] = light[0].saturating_add(100);
+            }
+
+            fn turn_off(&self, light: &mut [u8; 3]) {
+                *light = [0; 3];
+            }
+
+            fn toggle(&self, light: &mut [u8; 3]) {
+                light.rotate_right(1);
+            }
+
+            fn brightness(&self, light: &[u8; 3]) -> usize {
+                light.iter().map(|&channel| channel as usize).sum()
+            }
+        }
+
+        fn instructions() -> Vec<Instruction> {
+            parse_lines(
+                "toggle 0,0 through 9,9\ntoggle 0,0 through 4,9\nturn on 3,3 through 6,6\nturn off 0,0 through 0,9",
+                Instruction::parse,
+            )
+            .unwrap()
+        }
+
+        #[test]
+        fn test_custom_model() {
+            for backend in [Backend::Dense, Backend::Compressed] {
+                let mut display = Display::with_model(10, 10, Rgb, backend);
+                display.process_instructions(&instructions()).unwrap();
+
+                assert_eq!(display.lights[(3, 3)], [100, 0, 0]);
+                assert_eq!(display.total_lit(), 16);
+                assert_eq!(display.total_brightness(), 1600);
+            }
+        }
+
+        #[test]
+        fn test_provided_models() {
+            let mut capped = Display::with_model(10, 10, Capped { max: 3 }, Backend::Dense);
+            capped.process_instructions(&instructions()).unwrap();
+            assert_eq!(capped.lights[(3, 3)], 3);
+            assert_eq!(capped.total_brightness(), 248);
+
+            let mut cycling = Display::with_model(10, 10, Cycling { modulus: 4 }, Backend::Dense);
+            cycling.process_instructions(&instructions()).unwrap();
+            assert_eq!(cycling.lights[(3, 3)], 1);
+            assert_eq!(cycling.lights[(0, 0)], 3);
+        }
+
+        #[test]
+        fn test_bounds() {
+            let instructions = [
+                Instruction::TurnOn((4, 4), (1, 1)),
+                Instruction::Toggle((3, 0), (6, 0)),
+            ];
+
+            let mut clipped = LightDisplay::new(5, 5);
+            clipped.process_instructions(&instructions).unwrap();
+            assert_eq!(clipped.total_lit(), 18);
+
+            let mut wrapped = LightDisplay::new(5, 5).with_bounds(OutOfBounds::Wrap);
+            wrapped.process_instructions(&instructions).unwrap();
+            assert_eq!(wrapped.total_lit(), 20);
+            assert!(wrapped.lights[(1, 0)]);
+
+            let mut strict = LightDisplay::new(5, 5).with_bounds(OutOfBounds::Error);
+            let error = strict.process_instructions(&instructions).unwrap_err();
+            assert_eq!(error.instruction, instructions[1]);
+            assert_eq!(strict.total_lit(), 0);
+        }
+
+        #[test]
+        fn test_display_round_trip() {
+            for raw in [
+                "turn on 0,0 through 9,9",
+                "turn off 5,1 through 5,1",
+                "toggle 3,4 through 10,12",
+            ] {
+                assert_eq!(Instruction::parse(raw).unwrap().to_string(), raw);
+            }
+        }
     }
 }
@@ -0,0 +1,220 @@
+/// What the instructions do to a single light
+///
+/// # Examples
+///
+/// ```rust
+/// # use fire_hazard::{Display, Instruction};
+/// # use fire_hazard::backend::Backend;
+/// # use fire_hazard::model::LightModel;
+/// /// Lights which only ever get brighter
+/// struct Ratchet;
+///
+/// impl LightModel for Ratchet {
+///     type State = usize;
+///
+///     fn initial(&self) -> usize {
+///         0
+///     }
+///
+///     fn turn_on(&self, light: &mut usize) {
+///         *light += 1;
+///     }
+///
+///     fn turn_off(&self, _: &mut usize) {}
+///
+///     fn toggle(&self, light: &mut usize) {
+///         *light *= 2;
+///     }
+///
+///     fn brightness(&self, light: &usize) -> usize {
+///         *light
+///     }
+/// }
+///
+/// let mut display = Display::with_model(3, 3, Ratchet, Backend::Dense);
+/// display.process_instructions(&[
+///     Instruction::TurnOn((0, 0), (2, 2)),
+///     Instruction::TurnOff((0, 0), (2, 2)),
+///     Instruction::Toggle((1, 1), (2, 2)),
+/// ]).unwrap();
+/// assert_eq!(display.total_brightness(), 13);
+/// ```
+pub trait LightModel {
+    type State: Clone;
+
+    /// The state every light starts in
+    fn initial(&self) -> Self::State;
+
+    fn turn_on(&self, light: &mut Self::State);
+
+    fn turn_off(&self, light: &mut Self::State);
+
+    fn toggle(&self, light: &mut Self::State);
+
+    /// How bright a light is, zero when it is off
+    fn brightness(&self, light: &Self::State) -> usize;
+}
+
+/// Lights which are either on or off
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Switch;
+
+impl LightModel for Switch {
+    type State = bool;
+
+    fn initial(&self) -> bool {
+        false
+    }
+
+    fn turn_on(&self, light: &mut bool) {
+        *light = true;
+    }
+
+    fn turn_off(&self, light: &mut bool) {
+        *light = false;
+    }
+
+    fn toggle(&self, light: &mut bool) {
+        *light = !*light;
+    }
+
+    fn brightness(&self, light: &bool) -> usize {
+        *light as usize
+    }
+}
+
+/// Lights whose brightness goes up by one when turned on, up by two when toggled and down by one, to no less than
+/// zero, when turned off
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Dimmer;
+
+impl LightModel for Dimmer {
+    type State = usize;
+
+    fn initial(&self) -> usize {
+        0
+    }
+
+    fn turn_on(&self, light: &mut usize) {
+        *light += 1;
+    }
+
+    fn turn_off(&self, light: &mut usize) {
+        *light = light.saturating_sub(1);
+    }
+
+    fn toggle(&self, light: &mut usize) {
+        *light += 2;
+    }
+
+    fn brightness(&self, light: &usize) -> usize {
+        *light
+    }
+}
+
+/// A [`Dimmer`] which never gets brighter than `max`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Capped {
+    pub max: usize,
+}
+
+impl LightModel for Capped {
+    type State = usize;
+
+    fn initial(&self) -> usize {
+        0
+    }
+
+    fn turn_on(&self, light: &mut usize) {
+        *light = (*light + 1).min(self.max);
+    }
+
+    fn turn_off(&self, light: &mut usize) {
+        *light = light.saturating_sub(1);
+    }
+
+    fn toggle(&self, light: &mut usize) {
+        *light = (*light + 2).min(self.max);
+    }
+
+    fn brightness(&self, light: &usize) -> usize {
+        *light
+    }
+}
+
+/// A [`Dimmer`] whose brightness wraps around to zero on reaching `modulus`, and back round from zero when turned
+/// off
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycling {
+    pub modulus: usize,
+}
+
+impl LightModel for Cycling {
+    type State = usize;
+
+    fn initial(&self) -> usize {
+        0
+    }
+
+    fn turn_on(&self, light: &mut usize) {
+        *light = (*light + 1) % self.modulus;
+    }
+
+    fn turn_off(&self, light: &mut usize) {
+        *light = (*light + self.modulus - 1) % self.modulus;
+    }
+
+    fn toggle(&self, light: &mut usize) {
+        *light = (*light + 2) % self.modulus;
+    }
+
+    fn brightness(&self, light: &usize) -> usize {
+        *light
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod models {
+        use super::super::*;
+
+        fn apply<M: LightModel>(model: &M, actions: &str) -> M::State {
+            let mut light = model.initial();
+
+            for action in actions.chars() {
+                match action {
+                    '+' => model.turn_on(&mut light),
+                    '-' => model.turn_off(&mut light),
+                    _ => model.toggle(&mut light),
+                }
+            }
+
+            light
+        }
+
+        #[test]
+        fn test_switch() {
+            assert!(apply(&Switch, "+~~"));
+            assert!(!apply(&Switch, "+-"));
+            assert!(apply(&Switch, "-~"));
+        }
+
+        #[test]
+        fn test_dimmer() {
+            assert_eq!(apply(&Dimmer, "+~"), 3);
+            assert_eq!(apply(&Dimmer, "--+"), 1);
+        }
+
+        #[test]
+        fn test_capped() {
+            assert_eq!(apply(&Capped { max: 4 }, "~~~"), 4);
+            assert_eq!(apply(&Capped { max: 4 }, "~~~-"), 3);
+        }
+
+        #[test]
+        fn test_cycling() {
+            assert_eq!(apply(&Cycling { modulus: 4 }, "~+~"), 1);
+            assert_eq!(apply(&Cycling { modulus: 4 }, "-"), 3);
+        }
+    }
+}
@@ -0,0 +1,514 @@
+use crate::backend::{block, extent, CompressedGrid};
+use crate::model::LightModel;
+use crate::Display;
+use adventofcode::graph::bfs;
+use adventofcode::grid::Grid;
+use std::cell::Ref;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::RangeInclusive;
+
+/// A group of lit lights, each reachable from the others through lit lights above, below, left or right of them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// Number of lights in the region
+    pub size: usize,
+    /// Top left corner of the smallest rectangle holding the region
+    pub tl: (usize, usize),
+    /// Bottom right corner of the smallest rectangle holding the region
+    pub br: (usize, usize),
+}
+
+/// Summed-area tables over the brightness of blocks of equal lights, giving the number of lit lights and their
+/// total brightness within any rectangle in constant time
+#[derive(Clone, Debug, PartialEq)]
+pub struct SummedArea {
+    levels: CompressedGrid<usize>,
+    lit: Grid<usize>,
+    brightness: Grid<usize>,
+}
+
+/// A rectangle as the half open ranges of columns and rows it covers
+type Span = (usize, usize, usize, usize);
+
+impl SummedArea {
+    pub fn new(levels: CompressedGrid<usize>) -> SummedArea {
+        SummedArea {
+            lit: table(&levels, |level| (level > 0) as usize),
+            brightness: table(&levels, |level| level),
+            levels,
+        }
+    }
+
+    /// Brings the tables up to date after the lights in the rectangle with inclusive corners `tl` and `br` changed,
+    /// with `brightness` giving the brightness of the light at a position now. The blocks the rectangle covers are
+    /// split off like the lights are, and only the table rows and columns from its top left block onward are
+    /// worked out again.
+    pub(crate) fn update(
+        &mut self,
+        tl: (usize, usize),
+        br: (usize, usize),
+        brightness: impl Fn((usize, usize)) -> usize,
+    ) {
+        let span = match self.span(tl, br) {
+            Some(span) => span,
+            None => return,
+        };
+        let (left, right, top, bottom) = span;
+        self.levels.split(&[left, right], &[top, bottom]);
+
+        let (columns, rows) = self.blocks(span);
+        for row in rows.clone() {
+            for column in columns.clone() {
+                let light = (self.levels.columns()[column], self.levels.rows()[row]);
+                *self.levels.block_mut(column, row) = brightness(light);
+            }
+        }
+
+        // Splitting only adds blocks from the rectangle onward, so the entries above and left of it keep their
+        // place as well as their value
+        let from = (*columns.start(), *rows.start());
+        let size = (
+            self.levels.columns().len() + 1,
+            self.levels.rows().len() + 1,
+        );
+        let kept = |table: &Grid<usize>| {
+            Grid::from_fn(size.0, size.1, |column, row| {
+                match column < from.0 && row < from.1 {
+                    true => table[(column, row)],
+                    false => 0,
+                }
+            })
+        };
+
+        self.lit = kept(&self.lit);
+        fill(&mut self.lit, &self.levels, from, |level| {
+            (level > 0) as usize
+        });
+        self.brightness = kept(&self.brightness);
+        fill(&mut self.brightness, &self.levels, from, |level| level);
+    }
+
+    /// Number of lit lights in the rectangle with inclusive corners `tl` and `br`
+    pub fn lit(&self, tl: (usize, usize), br: (usize, usize)) -> usize {
+        self.span(tl, br).map_or(0, |span| {
+            self.sum(&self.lit, span, |level| (level > 0) as usize)
+        })
+    }
+
+    /// Total brightness of the lights in the rectangle with inclusive corners `tl` and `br`
+    pub fn brightness(&self, tl: (usize, usize), br: (usize, usize)) -> usize {
+        self.span(tl, br)
+            .map_or(0, |span| self.sum(&self.brightness, span, |level| level))
+    }
+
+    /// Brightness of the dimmest light in the rectangle, if it holds any
+    pub fn min(&self, tl: (usize, usize), br: (usize, usize)) -> Option<usize> {
+        self.overlaps(tl, br).map(|(level, _)| level).min()
+    }
+
+    /// Brightness of the brightest light in the rectangle, if it holds any
+    pub fn max(&self, tl: (usize, usize), br: (usize, usize)) -> Option<usize> {
+        self.overlaps(tl, br).map(|(level, _)| level).max()
+    }
+
+    /// Number of lights in the rectangle at each brightness
+    pub fn histogram(&self, tl: (usize, usize), br: (usize, usize)) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+
+        for (level, (left, right, top, bottom)) in self.overlaps(tl, br) {
+            *histogram.entry(level).or_insert(0) += (right - left) * (bottom - top);
+        }
+
+        histogram
+    }
+
+    /// The lit regions within the rectangle, ignoring any connection outside it, largest first
+    pub fn regions(&self, tl: (usize, usize), br: (usize, usize)) -> Vec<Region> {
+        let span = match self.span(tl, br) {
+            Some(span) => span,
+            None => return vec![],
+        };
+        let (columns, rows) = self.blocks(span);
+        let lit = |&(column, row): &(usize, usize)| {
+            columns.contains(&column) && rows.contains(&row) && *self.levels.block(column, row) > 0
+        };
+        let mut seen = HashSet::new();
+        let mut regions = vec![];
+
+        for start in rows
+            .clone()
+            .flat_map(|row| columns.clone().map(move |column| (column, row)))
+        {
+            if !lit(&start) || seen.contains(&start) {
+                continue;
+            }
+
+            let blocks = bfs(start, |&(column, row)| {
+                [
+                    (column, row.wrapping_sub(1)),
+                    (column + 1, row),
+                    (column, row + 1),
+                    (column.wrapping_sub(1), row),
+                ]
+                .into_iter()
+                .filter(lit)
+                .collect::<Vec<_>>()
+            });
+            let mut region = Region {
+                size: 0,
+                tl: (usize::MAX, usize::MAX),
+                br: (0, 0),
+            };
+
+            for &(column, row) in &blocks {
+                let (left, right, top, bottom) = self.overlap(column, row, span);
+                region.size += (right - left) * (bottom - top);
+                region.tl = (region.tl.0.min(left), region.tl.1.min(top));
+                region.br = (region.br.0.max(right - 1), region.br.1.max(bottom - 1));
+            }
+
+            seen.extend(blocks);
+            regions.push(region);
+        }
+
+        regions.sort_by(|a, b| b.size.cmp(&a.size).then(a.tl.cmp(&b.tl)));
+        regions
+    }
+
+    /// The rectangle as half open ranges, with reversed corners swapped and any part outside the display dropped
+    fn span(&self, tl: (usize, usize), br: (usize, usize)) -> Option<Span> {
+        let (width, height) = (self.levels.width(), self.levels.height());
+        let left = tl.0.min(br.0);
+        let top = tl.1.min(br.1);
+        let right = tl.0.max(br.0).saturating_add(1).min(width);
+        let bottom = tl.1.max(br.1).saturating_add(1).min(height);
+
+        (left < right && top < bottom).then_some((left, right, top, bottom))
+    }
+
+    /// Columns and rows of the blocks holding any part of the span
+    fn blocks(
+        &self,
+        (left, right, top, bottom): Span,
+    ) -> (RangeInclusive<usize>, RangeInclusive<usize>) {
+        (
+            block(self.levels.columns(), left)..=block(self.levels.columns(), right - 1),
+            block(self.levels.rows(), top)..=block(self.levels.rows(), bottom - 1),
+        )
+    }
+
+    /// The part of a block within the span
+    fn overlap(&self, column: usize, row: usize, (left, right, top, bottom): Span) -> Span {
+        let (columns, rows) = (self.levels.columns(), self.levels.rows());
+        let x = columns[column];
+        let y = rows[row];
+
+        (
+            x.max(left),
+            (x + extent(columns, column, self.levels.width())).min(right),
+            y.max(top),
+            (y + extent(rows, row, self.levels.height())).min(bottom),
+        )
+    }
+
+    /// The brightness of each block holding any part of the rectangle, along with the part of it within the
+    /// rectangle
+    fn overlaps(
+        &self,
+        tl: (usize, usize),
+        br: (usize, usize),
+    ) -> impl Iterator<Item = (usize, Span)> + '_ {
+        self.span(tl, br).into_iter().flat_map(move |span| {
+            let (columns, rows) = self.blocks(span);
+
+            rows.flat_map(move |row| columns.clone().map(move |column| (column, row)))
+                .map(move |(column, row)| {
+                    (
+                        *self.levels.block(column, row),
+                        self.overlap(column, row, span),
+                    )
+                })
+        })
+    }
+
+    /// Sum of `f` over the lights in the span
+    fn sum(
+        &self,
+        table: &Grid<usize>,
+        (left, right, top, bottom): Span,
+        f: impl Fn(usize) -> usize,
+    ) -> usize {
+        let at = |x, y| self.before(table, x, y, &f);
+        at(right, bottom) + at(left, top) - at(left, bottom) - at(right, top)
+    }
+
+    /// Sum of `f` over the lights above and left of `(x, y)`. Within a block the sum grows in proportion to how
+    /// far into the block `(x, y)` lies, so the table only needs an entry at each block corner.
+    fn before(&self, table: &Grid<usize>, x: usize, y: usize, f: impl Fn(usize) -> usize) -> usize {
+        let (columns, rows) = (self.levels.columns(), self.levels.rows());
+        // The far edge of the display lies past the last block, on the last corner in the table
+        let corner = |starts: &[usize], position: usize, size: usize| match position == size {
+            true => (starts.len(), 0),
+            false => {
+                let index = block(starts, position);
+                (index, position - starts[index])
+            }
+        };
+        let (column, dx) = corner(columns, x, self.levels.width());
+        let (row, dy) = corner(rows, y, self.levels.height());
+
+        let mut sum = table[(column, row)];
+        if dx > 0 {
+            sum += dx * (table[(column + 1, row)] - table[(column, row)])
+                / extent(columns, column, self.levels.width());
+        }
+        if dy > 0 {
+            sum += dy * (table[(column, row + 1)] - table[(column, row)])
+                / extent(rows, row, self.levels.height());
+        }
+        if dx > 0 && dy > 0 {
+            sum += dx * dy * f(*self.levels.block(column, row));
+        }
+
+        sum
+    }
+}
+
+/// Summed-area table of `f` over the blocks, with an entry at each block corner holding the sum over every light
+/// above and left of it
+fn table(levels: &CompressedGrid<usize>, f: impl Fn(usize) -> usize) -> Grid<usize> {
+    let mut table = Grid::new(levels.columns().len() + 1, levels.rows().len() + 1, 0);
+    fill(&mut table, levels, (0, 0), f);
+    table
+}
+
+/// Works out the entries of a summed-area table in the rows and columns of block corners from `from` onward,
+/// keeping the entries above and left of it
+fn fill(
+    table: &mut Grid<usize>,
+    levels: &CompressedGrid<usize>,
+    from: (usize, usize),
+    f: impl Fn(usize) -> usize,
+) {
+    let (columns, rows) = (levels.columns(), levels.rows());
+
+    for row in 0..rows.len() {
+        let height = extent(rows, row, levels.height());
+        let first = match row + 1 < from.1 {
+            true => from.0.saturating_sub(1),
+            false => 0,
+        };
+
+        for column in first..columns.len() {
+            let width = extent(columns, column, levels.width());
+
+            table[(column + 1, row + 1)] = table[(column, row + 1)] + table[(column + 1, row)]
+                - table[(column, row)]
+                + f(*levels.block(column, row)) * width * height;
+        }
+    }
+}
+
+impl<M: LightModel> Display<M> {
+    /// Number of lit lights in the rectangle with inclusive corners `tl` and `br`, ignoring any part outside the
+    /// display
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use fire_hazard::{Instruction, LightDisplay};
+    /// let mut display = LightDisplay::new(10, 10);
+    /// display.process_instructions(&[Instruction::TurnOn((2, 2), (7, 7))]).unwrap();
+    ///
+    /// assert_eq!(display.lit_in((0, 0), (4, 4)), 9);
+    /// assert_eq!(display.lit_in((5, 0), (20, 20)), 18);
+    /// ```
+    pub fn lit_in(&self, tl: (usize, usize), br: (usize, usize)) -> usize {
+        self.summed().lit(tl, br)
+    }
+
+    /// Total brightness of the lights in the rectangle
+    pub fn brightness_in(&self, tl: (usize, usize), br: (usize, usize)) -> usize {
+        self.summed().brightness(tl, br)
+    }
+
+    /// Brightness of the dimmest light in the rectangle, if it holds any
+    pub fn min_in(&self, tl: (usize, usize), br: (usize, usize)) -> Option<usize> {
+        self.summed().min(tl, br)
+    }
+
+    /// Brightness of the brightest light in the rectangle, if it holds any
+    pub fn max_in(&self, tl: (usize, usize), br: (usize, usize)) -> Option<usize> {
+        self.summed().max(tl, br)
+    }
+
+    /// Number of lights in the rectangle at each brightness
+    pub fn histogram(&self, tl: (usize, usize), br: (usize, usize)) -> BTreeMap<usize, usize> {
+        self.summed().histogram(tl, br)
+    }
+
+    /// The lit regions within the rectangle, largest first
+    pub fn regions(&self, tl: (usize, usize), br: (usize, usize)) -> Vec<Region> {
+        self.summed().regions(tl, br)
+    }
+
+    /// The summed-area tables for the lights as they are now, built the first time they are needed and kept up to
+    /// date by every instruction followed after that
+    fn summed(&self) -> Ref<'_, SummedArea> {
+        if self.summed.borrow().is_none() {
+            let levels = self.lights.blocks(|light| self.model.brightness(light));
+            *self.summed.borrow_mut() = Some(SummedArea::new(levels));
+        }
+
+        Ref::map(self.summed.borrow(), |summed| {
+            summed.as_ref().expect("Tables were just built")
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod summed_area {
+        use super::super::*;
+        use crate::backend::Backend;
+        use crate::{DimmableLightDisplay, Instruction, LightDisplay};
+
+        fn instructions() -> Vec<Instruction> {
+            vec![
+                Instruction::TurnOn((0, 0), (5, 5)),
+                Instruction::Toggle((3, 2), (9, 3)),
+                Instruction::TurnOff((1, 1), (1, 8)),
+                Instruction::Toggle((8, 8), (9, 9)),
+            ]
+        }
+
+        fn displays() -> Vec<DimmableLightDisplay> {
+            [Backend::Dense, Backend::Compressed]
+                .into_iter()
+                .map(|backend| {
+                    let mut display = DimmableLightDisplay::with_backend(10, 10, backend);
+                    display.process_instructions(&instructions()).unwrap();
+                    display
+                })
+                .collect()
+        }
+
+        /// Adds up the lights one by one
+        fn naive(
+            display: &DimmableLightDisplay,
+            tl: (usize, usize),
+            br: (usize, usize),
+        ) -> Vec<usize> {
+            let mut levels = vec![];
+
+            for y in tl.1..=br.1.min(9) {
+                for x in tl.0..=br.0.min(9) {
+                    levels.push(display.lights[(x, y)]);
+                }
+            }
+
+            levels
+        }
+
+        #[test]
+        fn test_sums() {
+            for display in displays() {
+                for (tl, br) in [
+                    ((0, 0), (9, 9)),
+                    ((2, 1), (4, 7)),
+                    ((3, 3), (3, 3)),
+                    ((7, 0), (30, 2)),
+                ] {
+                    let levels = naive(&display, tl, br);
+
+                    assert_eq!(display.brightness_in(tl, br), levels.iter().sum::<usize>());
+                    assert_eq!(
+                        display.lit_in(tl, br),
+                        levels.iter().filter(|&&level| level > 0).count()
+                    );
+                    assert_eq!(display.min_in(tl, br), levels.iter().copied().min());
+                    assert_eq!(display.max_in(tl, br), levels.iter().copied().max());
+                }
+
+                assert_eq!(
+                    display.brightness_in((9, 9), (0, 0)),
+                    display.total_brightness()
+                );
+                assert_eq!(display.lit_in((10, 0), (12, 9)), 0);
+                assert_eq!(display.max_in((10, 0), (12, 9)), None);
+            }
+        }
+
+        #[test]
+        fn test_histogram() {
+            for display in displays() {
+                let histogram = display.histogram((0, 0), (9, 3));
+
+                assert_eq!(
+                    histogram,
+                    BTreeMap::from([(0, 11), (1, 15), (2, 8), (3, 6)])
+                );
+            }
+        }
+
+        #[test]
+        fn test_regions() {
+            for display in displays() {
+                assert_eq!(
+                    display.regions((0, 0), (9, 9)),
+                    vec![
+                        Region {
+                            size: 39,
+                            tl: (0, 0),
+                            br: (9, 5)
+                        },
+                        Region {
+                            size: 4,
+                            tl: (8, 8),
+                            br: (9, 9)
+                        },
+                    ]
+                );
+                assert_eq!(display.regions((1, 0), (1, 9)).len(), 1);
+                assert_eq!(display.regions((0, 0), (1, 9))[0].size, 7);
+            }
+        }
+
+        #[test]
+        fn test_updated_in_place() {
+            for backend in [Backend::Dense, Backend::Compressed] {
+                let mut display = DimmableLightDisplay::with_backend(10, 10, backend);
+                assert_eq!(display.brightness_in((0, 0), (9, 9)), 0);
+
+                for instruction in instructions() {
+                    display.process_instructions(&[instruction]).unwrap();
+
+                    let levels = display
+                        .lights
+                        .blocks(|light| display.model.brightness(light));
+                    assert_eq!(
+                        *display.summed(),
+                        SummedArea::new(levels),
+                        "{}",
+                        instruction
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn test_kept_up_to_date() {
+            let mut display = LightDisplay::with_backend(1_000_000, 1_000_000, Backend::Compressed);
+            display
+                .process_instructions(&[Instruction::TurnOn((0, 0), (999_999, 999_999))])
+                .unwrap();
+            assert_eq!(display.lit_in((10, 10), (19, 19)), 100);
+
+            display
+                .process_instructions(&[Instruction::TurnOff((15, 0), (15, 999_999))])
+                .unwrap();
+            assert_eq!(display.lit_in((10, 10), (19, 19)), 90);
+            assert_eq!(display.regions((10, 10), (19, 19)).len(), 2);
+        }
+    }
+}
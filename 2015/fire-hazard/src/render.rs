@@ -0,0 +1,256 @@
+use crate::backend::extent;
+use crate::model::LightModel;
+use crate::{Display, Instruction};
+use adventofcode::grid::Grid;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+
+/// Characters for increasing brightness in ASCII art
+const RAMP: &[u8] = b" .:-=+*#%@";
+
+/// Plain text Netpbm image formats
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Black and white, with lit lights white and unlit lights black
+    Pbm,
+    /// Grayscale, with the brightest light white
+    Pgm,
+    /// The grayscale image as colour
+    Ppm,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Pbm => "pbm",
+            Format::Pgm => "pgm",
+            Format::Ppm => "ppm",
+        }
+    }
+}
+
+impl<M: LightModel> Display<M> {
+    /// Writes the lights as an image, one pixel per light
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use fire_hazard::{DimmableLightDisplay, Instruction};
+    /// # use fire_hazard::render::Format;
+    /// let mut display = DimmableLightDisplay::new(3, 2);
+    /// display.process_instructions(&[Instruction::Toggle((0, 0), (1, 0)), Instruction::TurnOn((1, 0), (1, 1))]).unwrap();
+    ///
+    /// let mut image = vec![];
+    /// display.write_image(Format::Pgm, &mut image).unwrap();
+    /// assert_eq!(String::from_utf8(image).unwrap(), "P2\n3 2\n255\n170 255 0\n0 85 0\n");
+    /// ```
+    pub fn write_image(&self, format: Format, out: &mut impl Write) -> io::Result<()> {
+        let levels = self.lights.blocks(|light| self.model.brightness(light));
+        let max = levels
+            .blocks()
+            .map(|(&level, _)| level)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let gray = |level: usize| level * 255 / max;
+        let (width, height) = (levels.width(), levels.height());
+
+        match format {
+            Format::Pbm => writeln!(out, "P1\n{} {}", width, height)?,
+            Format::Pgm => writeln!(out, "P2\n{} {}\n255", width, height)?,
+            Format::Ppm => writeln!(out, "P3\n{} {}\n255", width, height)?,
+        }
+
+        // Every row of lights within a row of blocks looks the same, so it is only put together once
+        for row in 0..levels.rows().len() {
+            let pixels: Vec<String> = (0..levels.columns().len())
+                .flat_map(|column| {
+                    let level = *levels.block(column, row);
+                    let pixel = match format {
+                        Format::Pbm => ((level == 0) as u8).to_string(),
+                        Format::Pgm => gray(level).to_string(),
+                        Format::Ppm => format!("{0} {0} {0}", gray(level)),
+                    };
+                    iter::repeat_n(pixel, extent(levels.columns(), column, width))
+                })
+                .collect();
+            let line = pixels.join(" ");
+
+            for _ in 0..extent(levels.rows(), row, height) {
+                writeln!(out, "{}", line)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws the lights as text at most `columns` characters wide, each character showing the average brightness
+    /// of a square block of lights
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use fire_hazard::{Instruction, LightDisplay};
+    /// let mut display = LightDisplay::new(8, 4);
+    /// display.process_instructions(&[Instruction::TurnOn((0, 0), (3, 1)), Instruction::TurnOn((4, 2), (4, 3))]).unwrap();
+    ///
+    /// assert_eq!(display.ascii(4), "@@  \n  = \n");
+    /// ```
+    pub fn ascii(&self, columns: usize) -> String {
+        let (width, height) = (self.lights.width(), self.lights.height());
+        let max = self
+            .max_in((0, 0), (width.saturating_sub(1), height.saturating_sub(1)))
+            .unwrap_or(0)
+            .max(1);
+        let scale = width.div_ceil(columns.max(1)).max(1);
+        let blocks = Grid::from_fn(
+            width.div_ceil(scale),
+            height.div_ceil(scale),
+            |column, row| {
+                let tl = (column * scale, row * scale);
+                let br = (
+                    (tl.0 + scale).min(width) - 1,
+                    (tl.1 + scale).min(height) - 1,
+                );
+                let area = (br.0 + 1 - tl.0) * (br.1 + 1 - tl.1);
+
+                self.brightness_in(tl, br) * (RAMP.len() - 1) / (area * max)
+            },
+        );
+
+        blocks.render(|&shade| RAMP[shade] as char)
+    }
+
+    /// Follows the instructions, writing an image of the lights to `directory` after each one. Returns the paths
+    /// of the images written, in order, or stops with an [`io::ErrorKind::InvalidInput`] error at the first
+    /// instruction reaching outside a display which does not allow it.
+    pub fn record(
+        &mut self,
+        instructions: &[Instruction],
+        format: Format,
+        directory: &Path,
+    ) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(directory)?;
+
+        instructions
+            .iter()
+            .enumerate()
+            .map(|(n, instruction)| {
+                self.process_instruction(instruction)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+                let path = directory.join(format!("frame-{:04}.{}", n + 1, format.extension()));
+                let mut out = BufWriter::new(File::create(&path)?);
+                self.write_image(format, &mut out)?;
+                out.flush()?;
+
+                Ok(path)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod render {
+        use super::super::*;
+        use crate::backend::Backend;
+        use crate::LightDisplay;
+        use std::env;
+
+        fn display() -> LightDisplay {
+            let mut display = LightDisplay::with_backend(4, 3, Backend::Compressed);
+            display
+                .process_instructions(&[
+                    Instruction::TurnOn((0, 0), (1, 2)),
+                    Instruction::Toggle((1, 1), (3, 1)),
+                ])
+                .unwrap();
+            display
+        }
+
+        fn image(display: &LightDisplay, format: Format) -> String {
+            let mut image = vec![];
+            display.write_image(format, &mut image).unwrap();
+            String::from_utf8(image).unwrap()
+        }
+
+        #[test]
+        fn test_write_image() {
+            let display = display();
+
+            assert_eq!(
+                image(&display, Format::Pbm),
+                "P1\n4 3\n0 0 1 1\n0 1 0 0\n0 0 1 1\n"
+            );
+            assert_eq!(
+                image(&display, Format::Ppm).lines().nth(4),
+                Some("255 255 255 0 0 0 255 255 255 255 255 255")
+            );
+        }
+
+        #[test]
+        fn test_write_image_unlit() {
+            assert_eq!(
+                image(&LightDisplay::new(2, 1), Format::Pgm),
+                "P2\n2 1\n255\n0 0\n"
+            );
+        }
+
+        #[test]
+        fn test_ascii() {
+            let display = display();
+
+            assert_eq!(display.ascii(4), "@@  \n@ @@\n@@  \n");
+            assert_eq!(display.ascii(2), "*=\n@ \n");
+        }
+
+        #[test]
+        fn test_ascii_large() {
+            let mut display = LightDisplay::with_backend(1_000_000, 1_000_000, Backend::Compressed);
+            display
+                .process_instructions(&[
+                    Instruction::TurnOn((0, 0), (499_999, 999_999)),
+                    Instruction::Toggle((0, 0), (0, 0)),
+                ])
+                .unwrap();
+
+            let half = format!("{}{}", "@".repeat(40), " ".repeat(40));
+            let mut expected = vec![half; 80];
+            // The unlit light leaves the first character just short of full brightness
+            expected[0].replace_range(0..1, "%");
+
+            assert_eq!(display.ascii(80), expected.join("\n") + "\n");
+        }
+
+        #[test]
+        fn test_record() {
+            let dir = env::temp_dir().join(format!("fire-hazard-record-{}", std::process::id()));
+            let mut display = LightDisplay::new(2, 2);
+            let instructions = [
+                Instruction::TurnOn((0, 0), (0, 1)),
+                Instruction::Toggle((0, 0), (1, 0)),
+            ];
+
+            let frames = display.record(&instructions, Format::Pbm, &dir).unwrap();
+
+            assert_eq!(
+                frames,
+                vec![dir.join("frame-0001.pbm"), dir.join("frame-0002.pbm")]
+            );
+            assert_eq!(
+                fs::read_to_string(&frames[0]).unwrap(),
+                "P1\n2 2\n0 1\n0 1\n"
+            );
+            assert_eq!(
+                fs::read_to_string(&frames[1]).unwrap(),
+                "P1\n2 2\n1 0\n0 1\n"
+            );
+            assert_eq!(display.total_lit(), 2);
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
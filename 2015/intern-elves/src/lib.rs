@@ -1,3 +1,5 @@
+use adventofcode::error::ParseError;
+use adventofcode::solution::Solution;
 use std::collections::BTreeSet;
 
 const VOWELS: &str = "aeiou";
@@ -56,11 +58,8 @@ fn has_repeating_characters(subject: &str, window_size: usize) -> bool {
 
 /// Returns true if the subject contains the string "ab", "cd", "pq", or "xy".
 fn has_forbidden_strings(subject: &str) -> bool {
-    let forbidden = vec!["ab", "cd", "pq", "xy"];
-    forbidden
-        .into_iter()
-        .map(|pat| subject.contains(pat))
-        .fold(false, |acc, cond| acc || cond)
+    let forbidden = ["ab", "cd", "pq", "xy"];
+    forbidden.into_iter().any(|pat| subject.contains(pat))
 }
 
 /// Returns true if the string has any substring of length 2 repeated throughout its string
@@ -119,6 +118,36 @@ pub fn is_nice(protocol: NiceProtocol, subject: &str) -> bool {
     }
 }
 
+/// Day 5: Doesn't He Have Intern-Elves For This?
+pub struct InternElves;
+
+impl Solution for InternElves {
+    const YEAR: u16 = 2015;
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Doesn't He Have Intern-Elves For This?";
+
+    type Input = Vec<String>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.trim().lines().map(|line| line.to_string()).collect())
+    }
+
+    fn part1(strings: &Self::Input) -> Self::Answer {
+        strings
+            .iter()
+            .filter(|subject| is_nice(NiceProtocol::V1, subject))
+            .count()
+    }
+
+    fn part2(strings: &Self::Input) -> Self::Answer {
+        strings
+            .iter()
+            .filter(|subject| is_nice(NiceProtocol::V2, subject))
+            .count()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,50 +1,66 @@
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
+
 pub fn code_count(input: &str) -> usize {
     input.chars().count()
 }
 
-pub fn char_count(input: &str) -> usize {
-    const ESCAPE_PREFIX: &str = "\\";
-    const ESCAPE_HEX_PREFIX: &str = "\\x";
-
-    input
-        .chars()
-        .into_iter()
-        .fold((0, None), |acc: (usize, Option<String>), ele| match acc {
-            (sum, None) => match ele {
-                '\\' => (sum, Some(format!("{}", ele))),
-                _ => (sum + 1, None),
-            },
-            (sum, Some(prefix)) => {
-                if prefix.starts_with(ESCAPE_HEX_PREFIX) {
-                    if prefix.len() == 3 {
-                        (sum + 1, None)
-                    } else {
-                        (sum, Some(format!("{}{}", prefix, ele)))
-                    }
-                } else if prefix.starts_with(ESCAPE_PREFIX) {
-                    match ele {
-                        '\\' => (sum + 1, None),
-                        '"' => (sum + 1, None),
-                        'x' => (sum, Some(format!("{}{}", prefix, ele))),
-                        _ => panic!("Invalid escape sequence. {}{}", prefix, ele),
-                    }
-                } else {
-                    panic!("Invalid escape sequence. {}{}", prefix, ele)
-                }
+/// Counts the characters a double quoted string literal decodes to, failing on malformed literals
+pub fn char_count(input: &str) -> Result<usize, ParseError> {
+    const EXPECTED_ESCAPE: &str = "an escape sequence of \\\\, \\\" or \\x and two hex digits";
+
+    let body = input
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(|| ParseError::invalid(input, "a double quoted string"))?;
+    let chars: Vec<char> = body.chars().collect();
+
+    let mut count = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        // Columns are 1 based and the body starts after the opening quote
+        let column = i + 2;
+
+        i += match (chars[i], chars.get(i + 1)) {
+            ('"', _) => return Err(ParseError::new(column, "\"", "an escaped quote")),
+            ('\\', Some('\\')) | ('\\', Some('"')) => 2,
+            ('\\', Some('x'))
+                if chars.len() >= i + 4 && chars[i + 2..i + 4].iter().all(char::is_ascii_hexdigit) =>
+            {
+                4
+            }
+            ('\\', _) => {
+                let sequence: String = chars[i..chars.len().min(i + 4)].iter().collect();
+                return Err(ParseError::new(column, sequence, EXPECTED_ESCAPE));
             }
+            _ => 1,
+        };
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// A line of the puzzle input along with the number of characters it decodes to
+pub struct Literal {
+    pub code: String,
+    pub chars: usize,
+}
+
+impl Literal {
+    pub fn parse(line: &str) -> Result<Literal, ParseError> {
+        Ok(Literal {
+            code: line.to_string(),
+            chars: char_count(line)?,
         })
-        .0
-        - 2
+    }
 }
 
 pub fn encode(input: &str) -> String {
   let body: String = input
     .chars()
-    .into_iter()
     .map(|ele| {
-      if ele == '"' {
-        format!("\\{}", ele)
-      } else if ele == '\\' {
+      if ele == '"' || ele == '\\' {
         format!("\\{}", ele)
       } else {
         format!("{}", ele)
@@ -55,6 +71,36 @@ pub fn encode(input: &str) -> String {
     format!("\"{}\"", body)
 }
 
+/// Day 8: Matchsticks
+pub struct Matchsticks;
+
+impl Solution for Matchsticks {
+    const YEAR: u16 = 2015;
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Matchsticks";
+
+    type Input = Vec<Literal>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_lines(input.trim_end(), Literal::parse)
+    }
+
+    fn part1(literals: &Self::Input) -> Self::Answer {
+        literals
+            .iter()
+            .map(|literal| code_count(&literal.code) - literal.chars)
+            .sum()
+    }
+
+    fn part2(literals: &Self::Input) -> Self::Answer {
+        literals
+            .iter()
+            .map(|literal| code_count(encode(&literal.code).as_str()) - code_count(&literal.code))
+            .sum()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -64,7 +110,7 @@ mod test {
         let input = "\"\"";
 
         assert_eq!(code_count(input), 2);
-        assert_eq!(char_count(input), 0)
+        assert_eq!(char_count(input), Ok(0))
     }
 
     #[test]
@@ -72,7 +118,7 @@ mod test {
         let input = "\"abc\"";
 
         assert_eq!(code_count(input), 5);
-        assert_eq!(char_count(input), 3);
+        assert_eq!(char_count(input), Ok(3));
     }
 
     #[test]
@@ -80,7 +126,7 @@ mod test {
         let input = "\"aaa\\\"aaa\"";
 
         assert_eq!(code_count(input), 10);
-        assert_eq!(char_count(input), 7)
+        assert_eq!(char_count(input), Ok(7))
     }
 
     #[test]
@@ -88,7 +134,22 @@ mod test {
         let input = "\"\\x27\"";
 
         assert_eq!(code_count(input), 6);
-        assert_eq!(char_count(input), 1);
+        assert_eq!(char_count(input), Ok(1));
+    }
+
+    #[test]
+    fn test_invalid_literals() {
+        let error = char_count("abc").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "abc"));
+
+        let error = char_count("\"a\\qbc\"").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (3, "\\qbc"));
+
+        let error = char_count("\"a\\x2g\"").unwrap_err();
+        assert_eq!(error.column, 3);
+
+        let error = char_count("\"a\"b\"").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (3, "\""));
     }
 
     #[test]
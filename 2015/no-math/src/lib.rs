@@ -1,3 +1,6 @@
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
+
 #[derive(Clone)]
 pub struct PresentBox {
     pub l: u64,
@@ -6,6 +9,30 @@ pub struct PresentBox {
 }
 
 impl PresentBox {
+    /// Parses a box's dimensions given in the form `LxWxH`
+    pub fn parse(entry: &str) -> Result<PresentBox, ParseError> {
+        let dimensions: Vec<&str> = entry.split('x').collect();
+
+        if dimensions.len() != 3 {
+            return Err(ParseError::invalid(entry, "dimensions in the form LxWxH"));
+        }
+
+        let mut column = 1;
+        let mut values = dimensions.iter().map(|dimension| {
+            let value = dimension
+                .parse::<u64>()
+                .map_err(|_| ParseError::new(column, *dimension, "a whole number dimension"));
+            column += dimension.len() + 1;
+            value
+        });
+
+        Ok(PresentBox {
+            l: values.next().unwrap()?,
+            w: values.next().unwrap()?,
+            h: values.next().unwrap()?,
+        })
+    }
+
     fn sides_areas(&self) -> Vec<u64> {
         vec![self.l * self.w, self.l * self.h, self.w * self.h]
     }
@@ -48,10 +75,52 @@ impl PresentBox {
     }
 }
 
+/// Parses a list of present boxes, one per line
+pub fn parse(input: &str) -> Result<Vec<PresentBox>, ParseError> {
+    parse_lines(input.trim_end(), PresentBox::parse)
+}
+
+/// Day 2: I Was Told There Would Be No Math
+pub struct NoMath;
+
+impl Solution for NoMath {
+    const YEAR: u16 = 2015;
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "I Was Told There Would Be No Math";
+
+    type Input = Vec<PresentBox>;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(presents: &Self::Input) -> Self::Answer {
+        presents.iter().map(|present| present.required_paper()).sum()
+    }
+
+    fn part2(presents: &Self::Input) -> Self::Answer {
+        presents.iter().map(|present| present.required_ribbon()).sum()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_parse() {
+        let present = PresentBox::parse("2x3x4").unwrap();
+
+        assert_eq!((present.l, present.w, present.h), (2, 3, 4));
+
+        let error = parse("2x3x4\n2xyx4").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.text, "y");
+
+        assert!(PresentBox::parse("2x3").is_err());
+    }
+
     #[test]
     fn test_sides_areas() {
         let present = PresentBox { l: 2, w: 3, h: 4 };
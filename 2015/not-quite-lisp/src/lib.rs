@@ -1,3 +1,6 @@
+use adventofcode::error::ParseError;
+use adventofcode::solution::Solution;
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum Instruction {
     GoUp,
@@ -5,9 +8,9 @@ pub enum Instruction {
 }
 
 /// Follows the instructions supplied, returning the resulting floor number
-pub fn climb_stairs(instructions: &Vec<Instruction>) -> i64 {
+pub fn climb_stairs(instructions: &[Instruction]) -> i64 {
     instructions
-        .into_iter()
+        .iter()
         .fold(0, |floor, instruction| match instruction {
             Instruction::GoUp => floor + 1,
             Instruction::GoDown => floor - 1,
@@ -15,8 +18,8 @@ pub fn climb_stairs(instructions: &Vec<Instruction>) -> i64 {
 }
 
 /// Follows the instructions supplied, returning the position where we enter the basement
-pub fn steps_to_basement(instructions: &Vec<Instruction>) -> i64 {
-    let s = instructions.into_iter().fold(
+pub fn steps_to_basement(instructions: &[Instruction]) -> i64 {
+    let s = instructions.iter().fold(
         (0, 1, None),
         |(floor, steps, first_time_to_basement_step), instruction| match first_time_to_basement_step
         {
@@ -40,43 +43,75 @@ pub fn steps_to_basement(instructions: &Vec<Instruction>) -> i64 {
 }
 
 /// Parses a string into a collection of instructions
-pub fn parse(instructions: &str) -> Vec<Instruction> {
+pub fn parse(instructions: &str) -> Result<Vec<Instruction>, ParseError> {
     instructions
-        .trim()
+        .trim_end()
         .chars()
-        .into_iter()
-        .map(|e| match e {
-            '(' => Instruction::GoUp,
-            ')' => Instruction::GoDown,
-            _ => panic!("Unexpected element {e:?}"),
+        .enumerate()
+        .map(|(i, e)| match e {
+            '(' => Ok(Instruction::GoUp),
+            ')' => Ok(Instruction::GoDown),
+            _ => Err(ParseError::new(i + 1, e.to_string(), "'(' or ')'")),
         })
         .collect()
 }
 
+/// Day 1: Not Quite Lisp
+pub struct NotQuiteLisp;
+
+impl Solution for NotQuiteLisp {
+    const YEAR: u16 = 2015;
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Not Quite Lisp";
+
+    type Input = Vec<Instruction>;
+    type Answer = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(instructions: &Self::Input) -> Self::Answer {
+        climb_stairs(instructions)
+    }
+
+    fn part2(instructions: &Self::Input) -> Self::Answer {
+        steps_to_basement(instructions)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
     fn test_parse() {
-        let left = parse("(");
+        let left = parse("(").unwrap();
         let right = vec![Instruction::GoUp];
 
         assert_eq!(left, right);
 
-        let left = parse(")");
+        let left = parse(")").unwrap();
         let right = vec![Instruction::GoDown];
 
         assert_eq!(left, right);
 
-        let left = parse("()");
+        let left = parse("()").unwrap();
         let right = vec![Instruction::GoUp, Instruction::GoDown];
 
         assert_eq!(left, right);
     }
 
+    #[test]
+    fn test_parse_unexpected() {
+        let error = parse("(()x)").unwrap_err();
+
+        assert_eq!((error.line, error.column), (1, 4));
+        assert_eq!(error.text, "x");
+    }
+
     fn validate_climb_stairs(input: &str, expected: i64) {
-        let instructions = parse(input);
+        let instructions = parse(input).unwrap();
         let pos = climb_stairs(&instructions);
 
         assert_eq!(expected, pos);
@@ -113,7 +148,7 @@ mod test {
 
     #[test]
     fn case_six() {
-        let instructions = parse(")");
+        let instructions = parse(")").unwrap();
         let real = steps_to_basement(&instructions);
 
         assert_eq!(1, real);
@@ -121,7 +156,7 @@ mod test {
 
     #[test]
     fn case_seven() {
-        let instructions = parse("()())");
+        let instructions = parse("()())").unwrap();
         let real = steps_to_basement(&instructions);
 
         assert_eq!(5, real);
@@ -1,44 +1,88 @@
-use std::collections::BTreeSet;
+use adventofcode::error::ParseError;
+use adventofcode::grid::SparseGrid;
+use adventofcode::point::{Direction, Point2};
+use adventofcode::solution::Solution;
 
 pub struct Santa {
-    pub visited: Vec<(i64, i64)>,
-}
-
-#[derive(Clone)]
-pub enum Direction {
-    North,
-    South,
-    East,
-    West,
+    pub visited: Vec<Point2>,
 }
 
 impl Default for Santa {
     fn default() -> Self {
         Santa {
-            visited: vec![(0, 0)],
+            visited: vec![Point2::ORIGIN],
         }
     }
 }
 
 impl Santa {
     pub fn traverse(&mut self, direction: Direction) {
-        let last = self.visited.last().expect("Error fetching last location");
-        let last = last.clone();
-
-        match direction {
-            Direction::North => self.visited.push((last.0, last.1 + 1)),
-            Direction::South => self.visited.push((last.0, last.1 - 1)),
-            Direction::East => self.visited.push((last.0 + 1, last.1)),
-            Direction::West => self.visited.push((last.0 - 1, last.1)),
-        }
+        let last = *self.visited.last().expect("Error fetching last location");
+
+        self.visited.push(last + direction);
     }
 
+    /// Counts the presents delivered to each house, returning how many houses received at least one
     pub fn houses_visited(&self) -> usize {
-        self.visited
-            .clone()
-            .into_iter()
-            .collect::<BTreeSet<(i64, i64)>>()
-            .len()
+        let mut houses = SparseGrid::new();
+        for house in &self.visited {
+            *houses.entry(*house).or_insert(0) += 1;
+        }
+
+        houses.len()
+    }
+}
+
+/// Parses a string of `^`, `>`, `v` and `<` characters into directions
+pub fn parse(input: &str) -> Result<Vec<Direction>, ParseError> {
+    input
+        .trim_end()
+        .chars()
+        .enumerate()
+        .map(|(i, arrow)| {
+            Direction::from_arrow(arrow)
+                .ok_or_else(|| ParseError::new(i + 1, arrow, "a direction of '^', '>', 'v' or '<'"))
+        })
+        .collect()
+}
+
+/// Delivers presents with a number of Santas taking turns following the directions, returning how many houses
+/// received at least one present
+pub fn deliver(directions: &[Direction], santas: usize) -> usize {
+    let mut workers: Vec<Santa> = (0..santas).map(|_| Santa::default()).collect();
+
+    for (turn, direction) in directions.iter().enumerate() {
+        workers[turn % santas].traverse(*direction);
+    }
+
+    let visited = workers
+        .into_iter()
+        .flat_map(|santa| santa.visited)
+        .collect();
+    Santa { visited }.houses_visited()
+}
+
+/// Day 3: Perfectly Spherical Houses in a Vacuum
+pub struct SphericalHouses;
+
+impl Solution for SphericalHouses {
+    const YEAR: u16 = 2015;
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Perfectly Spherical Houses in a Vacuum";
+
+    type Input = Vec<Direction>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(directions: &Self::Input) -> Self::Answer {
+        deliver(directions, 1)
+    }
+
+    fn part2(directions: &Self::Input) -> Self::Answer {
+        deliver(directions, 2)
     }
 }
 
@@ -50,19 +94,19 @@ mod test {
     fn test_traverse() {
         let mut santa = Santa::default();
         santa.traverse(Direction::North);
-        assert_eq!(santa.visited, vec![(0, 0), (0, 1)]);
+        assert_eq!(santa.visited, vec![Point2::ORIGIN, Point2::new(0, -1)]);
 
         let mut santa = Santa::default();
         santa.traverse(Direction::South);
-        assert_eq!(santa.visited, vec![(0, 0), (0, -1)]);
+        assert_eq!(santa.visited, vec![Point2::ORIGIN, Point2::new(0, 1)]);
 
         let mut santa = Santa::default();
         santa.traverse(Direction::East);
-        assert_eq!(santa.visited, vec![(0, 0), (1, 0)]);
+        assert_eq!(santa.visited, vec![Point2::ORIGIN, Point2::new(1, 0)]);
 
         let mut santa = Santa::default();
         santa.traverse(Direction::West);
-        assert_eq!(santa.visited, vec![(0, 0), (-1, 0)]);
+        assert_eq!(santa.visited, vec![Point2::ORIGIN, Point2::new(-1, 0)]);
     }
 
     #[test]
@@ -91,4 +135,21 @@ mod test {
         santa.traverse(Direction::South);
         assert_eq!(santa.houses_visited(), 2);
     }
+
+    #[test]
+    fn test_deliver() {
+        assert_eq!(deliver(&parse(">").unwrap(), 1), 2);
+        assert_eq!(deliver(&parse("^>v<").unwrap(), 1), 4);
+        assert_eq!(deliver(&parse("^v^v^v^v^v").unwrap(), 1), 2);
+
+        assert_eq!(deliver(&parse("^v").unwrap(), 2), 3);
+        assert_eq!(deliver(&parse("^>v<").unwrap(), 2), 3);
+        assert_eq!(deliver(&parse("^v^v^v^v^v").unwrap(), 2), 11);
+    }
+
+    #[test]
+    fn test_parse_unexpected() {
+        let error = parse("^>x<").err().unwrap();
+        assert_eq!((error.column, error.text.as_str()), (3, "x"));
+    }
 }
@@ -1,4 +1,5 @@
-use md5;
+use adventofcode::error::ParseError;
+use adventofcode::solution::Solution;
 
 pub struct Miner {
     secret: String,
@@ -23,12 +24,42 @@ impl Miner {
             if hex.starts_with(prefix) {
                 return iter;
             } else {
-                iter = iter + 1;
+                iter += 1;
             }
         }
     }
 }
 
+/// Day 4: The Ideal Stocking Stuffer
+pub struct StockingStuffer;
+
+impl Solution for StockingStuffer {
+    const YEAR: u16 = 2015;
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "The Ideal Stocking Stuffer";
+
+    type Input = Miner;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let secret = input.trim();
+
+        if secret.is_empty() || secret.contains(char::is_whitespace) {
+            return Err(ParseError::invalid(secret, "a secret key on a single line"));
+        }
+
+        Ok(Miner::new(secret))
+    }
+
+    fn part1(miner: &Self::Input) -> Self::Answer {
+        miner.mine("00000")
+    }
+
+    fn part2(miner: &Self::Input) -> Self::Answer {
+        miner.mine("000000")
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,58 +1,46 @@
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
+
 const ROW_COUNT: u16 = 128;
 const COL_COUNT: u16 = 8;
 
-#[allow(dead_code)]
-fn seat_id(seat_spec: &str) -> u16 {
+fn seat_id(seat_spec: &str) -> Result<u16, ParseError> {
+    if seat_spec.len() != 10 || !seat_spec.is_ascii() {
+        return Err(ParseError::invalid(seat_spec, "ten characters of F, B, L and R"));
+    }
+
     let (row_spec, col_spec) = seat_spec.split_at(7);
-    println!("Seat Spec: {}, Row Spec: {}, Col Spec: {}", seat_spec, row_spec, col_spec);
 
-    let row = row(row_spec);
-    let col = col(col_spec);
+    let row = row(row_spec)?;
+    let col = col(col_spec).map_err(|e| e.offset(7))?;
 
-    (row * COL_COUNT) + col
+    Ok((row * COL_COUNT) + col)
 }
 
-fn row(spec: &str) -> u16 {
-    println!("Row: {}", spec);
-    spec.chars().fold((0, ROW_COUNT - 1), |acc, ele| {
-        println!("acc: {:?}, ele: {:?}", acc, ele);
-        match ele {
-            'F' => {
-                (acc.0, (acc.0 + (acc.1 - acc.0) / 2))
-            },
-            'B' => {
-                (acc.0 + ((acc.1 - acc.0) / 2) + 1, acc.1)
-            },
-            other => {
-                panic!("Encountered unexpected character: {}", other);
-            }
-        }
-    }).0
+fn row(spec: &str) -> Result<u16, ParseError> {
+    partition(spec, ROW_COUNT, 'F', 'B')
 }
 
-fn col(spec: &str) -> u16 {
-    println!("Col: {}", spec);
-    spec.chars().fold((0, COL_COUNT - 1), |acc, ele| {
-        println!("acc: {:?}, ele: {:?}", acc, ele);
-        match ele {
-            'L' => {
-                (acc.0, (acc.0 + (acc.1 - acc.0) / 2))
-            },
-            'R' => {
-                (acc.0 + ((acc.1 - acc.0) / 2) + 1, acc.1)
-            },
-            other => {
-                panic!("Encountered unexpected character: {}", other);
-            }
+fn col(spec: &str) -> Result<u16, ParseError> {
+    partition(spec, COL_COUNT, 'L', 'R')
+}
+
+/// Narrows `count` seats down to one, taking the lower half on `lower` and the upper half on `upper`
+fn partition(spec: &str, count: u16, lower: char, upper: char) -> Result<u16, ParseError> {
+    spec.chars().enumerate().try_fold((0, count - 1), |acc, (i, ele)| {
+        if ele == lower {
+            Ok((acc.0, (acc.0 + (acc.1 - acc.0) / 2)))
+        } else if ele == upper {
+            Ok((acc.0 + ((acc.1 - acc.0) / 2) + 1, acc.1))
+        } else {
+            Err(ParseError::new(i + 1, ele, format!("'{}' or '{}'", lower, upper)))
         }
-    }).0
+    }).map(|range| range.0)
 }
 
-#[allow(dead_code)]
 fn missing(seats: Vec<u16>) -> u16 {
     seats.into_iter()
         .fold((0, 0, 0), |acc, ele| {
-            println!("{:?}", acc);
             if acc.0 == 0 {
                 (ele, 0, 0)
             } else if acc.1 == 0 {
@@ -70,6 +58,33 @@ fn missing(seats: Vec<u16>) -> u16 {
         }).2
 }
 
+/// Day 5: Binary Boarding
+pub struct BinaryBoarding;
+
+impl Solution for BinaryBoarding {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Binary Boarding";
+
+    /// Seat IDs of every boarding pass in ascending order
+    type Input = Vec<u16>;
+    type Answer = u16;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut ids: Vec<u16> = parse_lines(input.trim(), seat_id)?;
+        ids.sort();
+        Ok(ids)
+    }
+
+    fn part1(ids: &Self::Input) -> Self::Answer {
+        *ids.last().expect("No boarding passes found")
+    }
+
+    fn part2(ids: &Self::Input) -> Self::Answer {
+        missing(ids.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -79,28 +94,37 @@ mod tests {
     #[test]
     fn test_row() {
         let spec = "FBFBBFF";
-        assert_eq!(row(spec), 44);
+        assert_eq!(row(spec), Ok(44));
     }
 
     #[test]
     fn test_col() {
         let spec = "RLR";
-        assert_eq!(col(spec), 5);
+        assert_eq!(col(spec), Ok(5));
     }
 
     #[test]
     fn test_sample() {
-        assert_eq!(seat_id("FBFBBFFRLR"), 357);
-        assert_eq!(seat_id("BFFFBBFRRR"), 567);
-        assert_eq!(seat_id("FFFBBBFRRR"), 119);
-        assert_eq!(seat_id("BBFFBBFRLL"), 820);
+        assert_eq!(seat_id("FBFBBFFRLR"), Ok(357));
+        assert_eq!(seat_id("BFFFBBFRRR"), Ok(567));
+        assert_eq!(seat_id("FFFBBBFRRR"), Ok(119));
+        assert_eq!(seat_id("BBFFBBFRLL"), Ok(820));
+    }
+
+    #[test]
+    fn test_invalid_seat() {
+        let error = seat_id("FBFBBFFRLF").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (10, "F"));
+
+        let error = seat_id("FBFBBFF").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "FBFBBFF"));
     }
 
     #[test]
     fn test_highest_sample() {
         let biggest_id = fs::read_to_string("test/sample").unwrap()
             .lines()
-            .map(|spec| seat_id(spec))
+            .map(|spec| seat_id(spec).unwrap())
             .max();
         
         assert!(biggest_id.is_some());
@@ -111,7 +135,7 @@ mod tests {
     fn test_highest_input() {
         let biggest_id = fs::read_to_string("test/input").unwrap()
             .lines()
-            .map(|spec| seat_id(spec))
+            .map(|spec| seat_id(spec).unwrap())
             .max();
         
         assert!(biggest_id.is_some());
@@ -122,7 +146,7 @@ mod tests {
     fn test_find_seat_input() {
         let mut ids: Vec<u16> = fs::read_to_string("test/input").unwrap()
             .lines()
-            .map(|spec| seat_id(spec))
+            .map(|spec| seat_id(spec).unwrap())
             .collect();
         ids.sort();
         
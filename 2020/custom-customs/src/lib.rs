@@ -1,27 +1,23 @@
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
 use std::collections::HashSet;
 
-#[allow(dead_code)]
 fn parse_group_any(group: &str) -> HashSet<char> {
     group.lines()
-        .map(|line| parse_response(line))
+        .map(parse_response)
         .fold(HashSet::new(), |acc, ele| {
-            acc.union(&ele).into_iter()
-                .map(|c| c.clone())
-                .collect()
+            acc.union(&ele).copied().collect()
         })
 }
 
-#[allow(dead_code)]
 fn parse_group_every(group: &str) -> HashSet<char> {
     group.lines()
         .map(|line| {
             parse_response(line)
         })
         .fold(None, |acc: Option<HashSet<char>>, ele: HashSet<char>| {
-            if acc.is_some() {
-                Some(acc.unwrap().intersection(&ele).into_iter()
-                    .map(|c| c.clone())
-                    .collect::<HashSet<char>>())
+            if let Some(acc) = acc {
+                Some(acc.intersection(&ele).copied().collect::<HashSet<char>>())
             } else {
                 Some(ele)
             }
@@ -35,6 +31,42 @@ fn parse_response(line: &str) -> HashSet<char> {
     })
 }
 
+/// Checks a passenger only answered yes to questions `a` through `z`
+fn check_response(line: &str) -> Result<(), ParseError> {
+    match line.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+        Some((i, c)) => Err(ParseError::new(i + 1, c, "a question from 'a' to 'z'")),
+        None => Ok(()),
+    }
+}
+
+/// Day 6: Custom Customs
+pub struct CustomCustoms;
+
+impl Solution for CustomCustoms {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Custom Customs";
+
+    /// Raw answers of each group of passengers
+    type Input = Vec<String>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let input = input.trim();
+        parse_lines(input, check_response)?;
+
+        Ok(input.split("\n\n").map(|group| group.to_string()).collect())
+    }
+
+    fn part1(groups: &Self::Input) -> Self::Answer {
+        groups.iter().map(|group| parse_group_any(group).len()).sum()
+    }
+
+    fn part2(groups: &Self::Input) -> Self::Answer {
+        groups.iter().map(|group| parse_group_every(group).len()).sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -69,7 +101,7 @@ mod tests {
     fn test_sum_sample_any() {
         let sum= fs::read_to_string("test/sample").unwrap()
             .split("\n\n")
-            .map(|group| parse_group_any(group))
+            .map(parse_group_any)
             .fold(0, |acc, ele| acc + ele.len());
         assert_eq!(sum, 11);
     }
@@ -78,7 +110,7 @@ mod tests {
     fn test_sum_input_any() {
         let sum= fs::read_to_string("test/input").unwrap()
             .split("\n\n")
-            .map(|group| parse_group_any(group))
+            .map(parse_group_any)
             .fold(0, |acc, ele| acc + ele.len());
         assert_eq!(sum, 6291);
     }
@@ -87,7 +119,7 @@ mod tests {
     fn test_sum_sample_every() {
         let sum= fs::read_to_string("test/sample").unwrap()
             .split("\n\n")
-            .map(|group| parse_group_every(group))
+            .map(parse_group_every)
             .fold(0, |acc, ele| {
                 acc + ele.len()
             });
@@ -98,8 +130,14 @@ mod tests {
     fn test_sum_input_every() {
         let sum= fs::read_to_string("test/input").unwrap()
             .split("\n\n")
-            .map(|group| parse_group_every(group))
+            .map(parse_group_every)
             .fold(0, |acc, ele| acc + ele.len());
         assert_eq!(sum, 3052);
     }
+
+    #[test]
+    fn test_parse_invalid() {
+        let error = CustomCustoms::parse("abc\n\na\nbC").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (4, 2, "C"));
+    }
 }
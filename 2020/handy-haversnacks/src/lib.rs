@@ -1,4 +1,9 @@
-use std::{collections::{HashMap, HashSet}, fs, io};
+use adventofcode::error::{self, parse_lines, ParseError};
+use adventofcode::graph::{bfs, Graph};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
 
 #[macro_use]
 extern crate lazy_static;
@@ -7,8 +12,8 @@ use regex::Regex;
 
 lazy_static! {
     // light red bags contain 1 bright white bag, 2 muted yellow bags.
-    static ref RULE_REGEX: Regex = Regex::new(r"(.*) contain (.+)\.").unwrap();
-    static ref BAG_REGEX: Regex = Regex::new(r"(([0-9]+) )?([a-z ]+) bags?").unwrap();
+    static ref RULE_REGEX: Regex = Regex::new(r"^([a-z ]+) bags contain (.+)\.$").unwrap();
+    static ref BAG_REGEX: Regex = Regex::new(r"^([0-9]+) ([a-z ]+) bags?$").unwrap();
 }
 
 type Bags = HashMap<String, Bag>;
@@ -17,89 +22,88 @@ type Bags = HashMap<String, Bag>;
 struct Bag {
     color: String,
     contains: Vec<(usize, String)>,
-    contained_by: HashSet<String>
+    contained_by: HashSet<String>,
 }
 
 #[allow(dead_code)]
-fn parse_rules(path: &str) -> Result<Bags, io::Error> {
-    let lines = fs::read_to_string(path)?;
-    let bags = extract_bags(lines.as_str());
+fn parse_rules(path: &str) -> Result<Bags, error::Error> {
+    let lines = fs::read_to_string(path).map_err(|e| error::Error::Io(path.to_owned(), e))?;
 
-    Ok(bags)
+    extract_bags(lines.as_str()).map_err(|e| error::Error::Parse(path.to_owned(), e))
 }
 
-fn extract_bags(lines: &str) -> Bags {
-    let bags: Vec<Bag> = lines.lines()
-        .map(|line| {
-            let captures = RULE_REGEX.captures(line);
-            let container = captures.as_ref().unwrap().get(1).unwrap().as_str();
-            let contained = captures.as_ref().unwrap().get(2).unwrap().as_str();
-
-            (container, contained)
-        })
-        .map(|pair| {
-            let contains: Vec<(usize, String)> = pair.1.split(", ")
-                .map(|bags| {
-                    match bags {
-                        "no other bags" => {
-                            None
-                        },
-                        bags => {
-                            BAG_REGEX.captures(bags)
-                                .map(|capture| {
-                                    let color = capture.get(3).unwrap().as_str().to_owned();
-                                    let qty = capture.get(2).unwrap().as_str().parse::<usize>().unwrap();
-                                    
-                                    Some((qty, color))
-                                }).unwrap()
-                        }
-                    }
+/// Parses a single rule into the bag it describes, leaving out which bags contain it
+fn parse_rule(line: &str) -> Result<Bag, ParseError> {
+    let captures = RULE_REGEX.captures(line).ok_or_else(|| {
+        ParseError::invalid(line, "a rule of the form \"<color> bags contain <bags>.\"")
+    })?;
+    let list = captures.get(2).unwrap();
+
+    let contains = match list.as_str() {
+        "no other bags" => vec![],
+        bags => {
+            let mut column = list.start() + 1;
+
+            bags.split(", ")
+                .map(|bag| {
+                    let pair = BAG_REGEX
+                        .captures(bag)
+                        .and_then(|capture| {
+                            let qty = capture[1].parse::<usize>().ok()?;
+                            Some((qty, capture[2].to_owned()))
+                        })
+                        .ok_or_else(|| ParseError::new(column, bag, "a number of bags of a color"));
+                    column += bag.len() + 2;
+
+                    pair
                 })
-                .fold(Vec::new(), |mut acc, ele| {
-                    if let Some(pair) = ele {
-                        acc.push(pair);
-                    }
-                    
-                    acc
-                });
-            
-            let bag = BAG_REGEX.captures(pair.0)
-                .map(|capture| {
-                    let color = capture.get(3).unwrap().as_str().to_owned();
-                    Bag{
-                        color,
-                        contains,
-                        ..Default::default()
-                    }
-                }).unwrap();
-            
-            
-            bag
-        })
-        .collect();
-    
-    let mut graph = Bags::new();
-    
-    // Populate graph with entire dataset
-    bags.into_iter().for_each(|bag| {
-        println!("{:?}", bag);
-        graph.insert(bag.color.clone(), bag);
-    });
+                .collect::<Result<Vec<(usize, String)>, ParseError>>()?
+        }
+    };
+
+    Ok(Bag {
+        color: captures[1].to_owned(),
+        contains,
+        ..Default::default()
+    })
+}
+
+fn extract_bags(lines: &str) -> Result<Bags, ParseError> {
+    let mut graph = Graph::new();
+    let mut bags = Bags::new();
+
+    // Populate graph with entire dataset, each edge leading from a bag to a bag it contains
+    for bag in parse_lines(lines, parse_rule)? {
+        graph.add_node(bag.color.clone());
+
+        for (qty, color) in &bag.contains {
+            graph.add_edge(bag.color.clone(), color.clone(), *qty);
+        }
 
-    // Iterate over all graph entries and update contained_by fields
-    graph.values().clone()
-        .for_each(|bag| {
-            let contains = bag.contains.clone();
-            
-            contains.into_iter()
-                .map(|pair| pair.1)
-                .for_each(|color| {
-                    let contained_bag = graph.get_mut(&color);
+        bags.insert(bag.color.clone(), bag);
+    }
 
-                });
-        });
+    // Bags containing each bag are found by following its edges backwards
+    for (color, bag) in bags.iter_mut() {
+        bag.contained_by = graph
+            .predecessors(color)
+            .map(|(container, _)| container.clone())
+            .collect();
+    }
 
-    graph
+    Ok(bags)
+}
+
+/// Counts the bags which eventually contain at least one bag of the given color
+#[allow(dead_code)]
+fn containers(bags: &Bags, color: &str) -> usize {
+    let outer = bfs(color.to_owned(), |color| {
+        bags.get(color)
+            .map(|bag| bag.contained_by.iter().cloned().collect::<Vec<_>>())
+            .unwrap_or_default()
+    });
+
+    outer.len() - 1
 }
 
 #[cfg(test)]
@@ -121,4 +125,32 @@ mod tests {
         assert!(bags.is_ok());
         assert_eq!(bags.unwrap().len(), 594);
     }
+
+    #[test]
+    fn test_contained_by_sample() {
+        let bags = parse_rules("test/sample").unwrap();
+        let contained_by = &bags["shiny gold"].contained_by;
+
+        assert_eq!(contained_by.len(), 2);
+        assert!(contained_by.contains("bright white"));
+        assert!(contained_by.contains("muted yellow"));
+        assert!(bags["light red"].contained_by.is_empty());
+    }
+
+    #[test]
+    fn test_containers_sample() {
+        let bags = parse_rules("test/sample").unwrap();
+        assert_eq!(containers(&bags, "shiny gold"), 4);
+    }
+
+    #[test]
+    fn test_parse_invalid_rule() {
+        let error =
+            extract_bags("light red bags contain 1 bright white bag, two muted yellow bags.")
+                .unwrap_err();
+        assert_eq!(
+            (error.column, error.text.as_str()),
+            (44, "two muted yellow bags")
+        );
+    }
 }
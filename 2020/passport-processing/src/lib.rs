@@ -1,6 +1,5 @@
-#![feature(test)]
-#![feature(str_split_once)]
-
+use adventofcode::error::ParseError;
+use adventofcode::solution::Solution;
 use std::fs;
 
 #[macro_use]
@@ -26,8 +25,8 @@ lazy_static! {
     ];
 }
 
-#[derive(Default, Debug)]
-struct Document {
+#[derive(Clone, Default, Debug)]
+pub struct Document {
     byr: Option<String>,
     iyr: Option<String>,
     eyr: Option<String>,
@@ -39,11 +38,11 @@ struct Document {
 }
 
 impl Document {
-    fn passport_fields_present(self: &Self) -> bool {
+    fn passport_fields_present(&self) -> bool {
         self.npc_fields_present() && self.cid.is_some()
     }
 
-    fn npc_fields_present(self: &Self) -> bool {
+    fn npc_fields_present(&self) -> bool {
         self.byr.is_some()
             && self.iyr.is_some()
             && self.eyr.is_some()
@@ -53,22 +52,22 @@ impl Document {
             && self.pid.is_some()
     }
 
-    fn validate_passport(self: &Self) -> bool {
-        if let None = &self.cid {
+    fn validate_passport(&self) -> bool {
+        if self.cid.is_none() {
             return false;
         }
 
         self.validate_npc()
     }
 
-    fn validate_npc(self: &Self) -> bool {
+    fn validate_npc(&self) -> bool {
         // Birth year
         if let Some(byr) = &self.byr {
             if byr.len() != 4 {
                 return false;
             } else {
                 if let Ok(val) = byr.parse::<u16>() {
-                    if val < 1920 || val > 2002 {
+                    if !(1920..=2002).contains(&val) {
                         return false;
                     }
                 } else {
@@ -85,7 +84,7 @@ impl Document {
                 return false;
             } else {
                 if let Ok(val) = iyr.parse::<u16>() {
-                    if val < 2010 || val > 2020 {
+                    if !(2010..=2020).contains(&val) {
                         return false;
                     }
                 } else {
@@ -102,7 +101,7 @@ impl Document {
                 return false;
             } else {
                 if let Ok(val) = eyr.parse::<u16>() {
-                    if val < 2020 || val > 2030 {
+                    if !(2020..=2030).contains(&val) {
                         return false;
                     }
                 } else {
@@ -119,28 +118,23 @@ impl Document {
                 match cap[2].as_ref() {
                     "cm" => {
                         if let Ok(val) = cap[1].parse::<u8>() {
-                            if val < 150 || val > 193 {
-                                println!("Captures: {:#?}", cap);
+                            if !(150..=193).contains(&val) {
                                 return false;
                             }
                         } else {
-                            println!("Captures: {:#?}", cap);
                             return false;
                         }
                     }
                     "in" => {
                         if let Ok(val) = cap[1].parse::<u8>() {
-                            if val < 59 || val > 76 {
-                                println!("Captures: {:#?}", cap);
+                            if !(59..=76).contains(&val) {
                                 return false;
                             }
                         } else {
-                            println!("Captures: {:#?}", cap);
                             return false;
                         }
                     }
                     _ => {
-                        println!("Captures: {:#?}", cap);
                         return false;
                     }
                 }
@@ -178,42 +172,59 @@ impl Document {
             return false;
         }
 
-        return true;
+        true
     }
 }
 
 #[allow(dead_code)]
 fn read_documents(path: &str) -> Vec<Document> {
-    fs::read_to_string(path)
-        .unwrap()
-        .split("\n\n")
-        .into_iter()
-        .map(|mld| mld.replace("\n", " "))
-        .map(|rd| {
-            let mut document = Document::default();
-
-            rd.split(" ").into_iter().for_each(|pair| {
-                if let Some((field, value)) = pair.split_once(":") {
-                    match field {
-                        "byr" => document.byr = Some(value.to_owned()),
-                        "iyr" => document.iyr = Some(value.to_owned()),
-                        "eyr" => document.eyr = Some(value.to_owned()),
-                        "hgt" => document.hgt = Some(value.to_owned()),
-                        "hcl" => document.hcl = Some(value.to_owned()),
-                        "ecl" => document.ecl = Some(value.to_owned()),
-                        "pid" => document.pid = Some(value.to_owned()),
-                        "cid" => document.cid = Some(value.to_owned()),
-                        other => println!("Found field: {}", other),
-                    }
+    parse_documents(fs::read_to_string(path).unwrap().as_str()).unwrap()
+}
+
+/// Parses blank line separated documents, each made up of `key:value` fields spread over one or more lines
+fn parse_documents(input: &str) -> Result<Vec<Document>, ParseError> {
+    let mut documents = vec![];
+    let mut document: Option<Document> = None;
+
+    for (n, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            documents.extend(document.take());
+            continue;
+        }
+
+        let document = document.get_or_insert_with(Document::default);
+        let mut column = 1;
+
+        for pair in line.split(' ') {
+            let error = |expected: &str| ParseError::new(column, pair, expected).on_line(n + 1);
+
+            if !pair.is_empty() {
+                let (field, value) = pair
+                    .split_once(':')
+                    .ok_or_else(|| error("a field of the form \"key:value\""))?;
+                let value = Some(value.to_owned());
+
+                match field {
+                    "byr" => document.byr = value,
+                    "iyr" => document.iyr = value,
+                    "eyr" => document.eyr = value,
+                    "hgt" => document.hgt = value,
+                    "hcl" => document.hcl = value,
+                    "ecl" => document.ecl = value,
+                    "pid" => document.pid = value,
+                    "cid" => document.cid = value,
+                    _ => return Err(error("one of byr, iyr, eyr, hgt, hcl, ecl, pid or cid")),
                 }
-            });
+            }
 
-            document
-        })
-        .collect()
+            column += pair.len() + 1;
+        }
+    }
+
+    documents.extend(document);
+    Ok(documents)
 }
 
-#[allow(dead_code)]
 fn check_presence(documents: Vec<Document>) -> Vec<Document> {
     documents
         .into_iter()
@@ -221,7 +232,6 @@ fn check_presence(documents: Vec<Document>) -> Vec<Document> {
         .collect()
 }
 
-#[allow(dead_code)]
 fn validate(documents: Vec<Document>) -> Vec<Document> {
     check_presence(documents)
         .into_iter()
@@ -229,6 +239,30 @@ fn validate(documents: Vec<Document>) -> Vec<Document> {
         .collect()
 }
 
+/// Day 4: Passport Processing
+pub struct PassportProcessing;
+
+impl Solution for PassportProcessing {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Passport Processing";
+
+    type Input = Vec<Document>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_documents(input.trim())
+    }
+
+    fn part1(documents: &Self::Input) -> Self::Answer {
+        check_presence(documents.clone()).len()
+    }
+
+    fn part2(documents: &Self::Input) -> Self::Answer {
+        validate(documents.clone()).len()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,4 +305,13 @@ mod tests {
         let documents = validate(read_documents("test/input"));
         assert_eq!(documents.len(), 121);
     }
+
+    #[test]
+    fn test_parse_invalid_documents() {
+        let error = parse_documents("ecl:gry pid:860033327\n\nhcl:#cfa07d byr1929").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (3, 13, "byr1929"));
+
+        let error = parse_documents("ecl:gry foo:bar").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (1, 9, "foo:bar"));
+    }
 }
@@ -1,6 +1,6 @@
-#![feature(test)]
-
-use std::{fmt, fs, str::FromStr};
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
+use std::{fs, str::FromStr};
 
 #[macro_use]
 extern crate lazy_static;
@@ -9,20 +9,33 @@ extern crate regex;
 use regex::Regex;
 
 lazy_static! {
-    static ref ENTRY_REGEX: Regex = Regex::new(r"([0-9]+)-([0-9]+) ([a-z]): ([a-z]+)").unwrap();
+    static ref ENTRY_REGEX: Regex = Regex::new(r"^([0-9]+)-([0-9]+) ([a-z]): ([a-z]+)$").unwrap();
 }
 
-#[derive(Debug, Clone)]
-struct ParseError;
-
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        "Could not parse entry".fmt(f)
-    }
+/// Splits an entry into its two policy numbers, the policy token and the password
+fn parse_entry(line: &str) -> Result<(usize, usize, char, String), ParseError> {
+    let cap = ENTRY_REGEX
+        .captures(line)
+        .ok_or_else(|| ParseError::invalid(line, "an entry of the form \"1-3 a: abcde\""))?;
+    let number = |i| {
+        let m = cap.get(i).unwrap();
+        m.as_str()
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| ParseError::new(m.start() + 1, m.as_str(), "a positive number"))
+    };
+
+    Ok((
+        number(1)?,
+        number(2)?,
+        cap[3].parse::<char>().unwrap(),
+        cap[4].to_owned(),
+    ))
 }
 
 #[derive(Debug, Clone)]
-struct SledPasswordEntry {
+pub struct SledPasswordEntry {
     minimum: usize,
     maximum: usize,
     token: char,
@@ -33,20 +46,19 @@ impl FromStr for SledPasswordEntry {
     type Err = ParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        match ENTRY_REGEX.captures(line) {
-            Some(cap) => Ok(SledPasswordEntry {
-                minimum: cap[1].parse::<usize>().unwrap(),
-                maximum: cap[2].parse::<usize>().unwrap(),
-                token: cap[3].parse::<char>().unwrap(),
-                password: cap[4].to_owned(),
-            }),
-            None => Err(ParseError {}),
-        }
+        let (minimum, maximum, token, password) = parse_entry(line)?;
+
+        Ok(SledPasswordEntry {
+            minimum,
+            maximum,
+            token,
+            password,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
-struct TobogganPasswordEntry {
+pub struct TobogganPasswordEntry {
     left: usize,
     right: usize,
     token: char,
@@ -57,15 +69,14 @@ impl FromStr for TobogganPasswordEntry {
     type Err = ParseError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        match ENTRY_REGEX.captures(line) {
-            Some(cap) => Ok(TobogganPasswordEntry {
-                left: cap[1].parse::<usize>().unwrap(),
-                right: cap[2].parse::<usize>().unwrap(),
-                token: cap[3].parse::<char>().unwrap(),
-                password: cap[4].to_owned(),
-            }),
-            None => Err(ParseError {}),
-        }
+        let (left, right, token, password) = parse_entry(line)?;
+
+        Ok(TobogganPasswordEntry {
+            left,
+            right,
+            token,
+            password,
+        })
     }
 }
 
@@ -74,7 +85,7 @@ trait PasswordValidator {
 }
 
 impl PasswordValidator for SledPasswordEntry {
-    fn valid(self: &Self) -> bool {
+    fn valid(&self) -> bool {
         let char_count = self
             .password
             .chars()
@@ -87,9 +98,9 @@ impl PasswordValidator for SledPasswordEntry {
 }
 
 impl PasswordValidator for TobogganPasswordEntry {
-    fn valid(self: &Self) -> bool {
-        let left_match = self.password.chars().nth(self.left - 1).unwrap() == self.token;
-        let right_match = self.password.chars().nth(self.right - 1).unwrap() == self.token;
+    fn valid(&self) -> bool {
+        let left_match = self.password.chars().nth(self.left - 1) == Some(self.token);
+        let right_match = self.password.chars().nth(self.right - 1) == Some(self.token);
 
         (left_match || right_match) && !(left_match && right_match)
     }
@@ -98,17 +109,18 @@ impl PasswordValidator for TobogganPasswordEntry {
 #[allow(dead_code)]
 fn read_entries<T>(path: &str) -> Vec<T>
 where
-    T: FromStr,
-    <T as FromStr>::Err: std::fmt::Debug,
+    T: FromStr<Err = ParseError>,
 {
-    fs::read_to_string(path)
-        .unwrap()
-        .lines()
-        .map(|line| line.parse::<T>().unwrap())
-        .collect()
+    parse_entries(fs::read_to_string(path).unwrap().as_str()).unwrap()
+}
+
+fn parse_entries<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr<Err = ParseError>,
+{
+    parse_lines(input, str::parse::<T>)
 }
 
-#[allow(dead_code)]
 fn valid_passwords<T: PasswordValidator>(entries: Vec<T>) -> usize {
     entries
         .into_iter()
@@ -118,12 +130,35 @@ fn valid_passwords<T: PasswordValidator>(entries: Vec<T>) -> usize {
         .len()
 }
 
+/// Day 2: Password Philosophy
+pub struct PasswordPhilosophy;
+
+impl Solution for PasswordPhilosophy {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Password Philosophy";
+
+    /// The password database read under both the sled rental and toboggan corporate policies
+    type Input = (Vec<SledPasswordEntry>, Vec<TobogganPasswordEntry>);
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let input = input.trim();
+        Ok((parse_entries(input)?, parse_entries(input)?))
+    }
+
+    fn part1(entries: &Self::Input) -> Self::Answer {
+        valid_passwords(entries.0.clone())
+    }
+
+    fn part2(entries: &Self::Input) -> Self::Answer {
+        valid_passwords(entries.1.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn test_read_sled_entries() {
@@ -161,43 +196,12 @@ mod tests {
         assert_eq!(valid_passwords(entries), 708);
     }
 
-    #[bench]
-    fn bench_small_sled_password_validation(b: &mut Bencher) {
-        let entries = read_entries::<SledPasswordEntry>("test/sample");
-
-        b.iter(|| {
-            let entries = entries.clone();
-            valid_passwords(entries);
-        })
-    }
-
-    #[bench]
-    fn bench_small_toboggan_password_validation(b: &mut Bencher) {
-        let entries = read_entries::<TobogganPasswordEntry>("test/sample");
-
-        b.iter(|| {
-            let entries = entries.clone();
-            valid_passwords(entries);
-        })
-    }
-
-    #[bench]
-    fn bench_large_sled_password_validation(b: &mut Bencher) {
-        let entries = read_entries::<SledPasswordEntry>("test/input");
-
-        b.iter(|| {
-            let entries = entries.clone();
-            valid_passwords(entries);
-        })
-    }
-
-    #[bench]
-    fn bench_large_toboggan_password_validation(b: &mut Bencher) {
-        let entries = read_entries::<TobogganPasswordEntry>("test/input");
+    #[test]
+    fn test_parse_invalid_entries() {
+        let error = parse_entries::<SledPasswordEntry>("1-3 a: abcde\n1-3 b cdefg").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
 
-        b.iter(|| {
-            let entries = entries.clone();
-            valid_passwords(entries);
-        })
+        let error = parse_entries::<TobogganPasswordEntry>("0-9 c: ccccccccc").unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (1, "0"));
     }
 }
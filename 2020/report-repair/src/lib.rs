@@ -1,11 +1,9 @@
-#![feature(test)]
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
 
 /// Generates the product of two integers which equal the provided target sum
 pub fn pair_product(values: Vec<u32>, target: u32) -> Option<u32> {
-    match find_pair(values, target) {
-        Some((l, r)) => Some(l * r),
-        None => None,
-    }
+    find_pair(values, target).map(|(l, r)| l * r)
 }
 
 /// Finds two values within a vector which sum to the provided input
@@ -25,7 +23,7 @@ fn find_pair(mut values: Vec<u32>, target: u32) -> Option<(u32, u32)> {
             let n2 = values.get(ridx).unwrap();
 
             if n1 + n2 == target {
-                return Some((n1.clone(), n2.clone()));
+                return Some((*n1, *n2));
             }
         }
     }
@@ -35,10 +33,7 @@ fn find_pair(mut values: Vec<u32>, target: u32) -> Option<(u32, u32)> {
 
 /// Calculates the product of three values within a vector which sum to the provided input
 pub fn triple_product(values: Vec<u32>, target: u32) -> Option<u32> {
-    match find_triple(values, target) {
-        Some((l, m, r)) => Some(l * m * r),
-        None => None,
-    }
+    find_triple(values, target).map(|(l, m, r)| l * m * r)
 }
 
 /// Finds three values within a vector which sum to the provided input
@@ -63,7 +58,7 @@ fn find_triple(mut values: Vec<u32>, target: u32) -> Option<(u32, u32, u32)> {
                 let n3 = values.get(ridx).unwrap();
 
                 if n1 + n2 + n3 == target {
-                    return Some((n1.clone(), n2.clone(), n3.clone()));
+                    return Some((*n1, *n2, *n3));
                 }
             }
         }
@@ -72,12 +67,41 @@ fn find_triple(mut values: Vec<u32>, target: u32) -> Option<(u32, u32, u32)> {
     None
 }
 
+/// Parses an expense report, one entry per line
+pub fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+    parse_lines(input.trim(), |line| {
+        line.parse::<u32>()
+            .map_err(|_| ParseError::invalid(line, "an expense entry"))
+    })
+}
+
+/// Day 1: Report Repair
+pub struct ReportRepair;
+
+impl Solution for ReportRepair {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Report Repair";
+
+    type Input = Vec<u32>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(entries: &Self::Input) -> Self::Answer {
+        pair_product(entries.clone(), 2020).expect("No pair of entries sum to 2020")
+    }
+
+    fn part2(entries: &Self::Input) -> Self::Answer {
+        triple_product(entries.clone(), 2020).expect("No triple of entries sum to 2020")
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
 
     fn entries_from_file(path: &str) -> Vec<u32> {
         std::fs::read_to_string(path)
@@ -127,43 +151,9 @@ mod tests {
         assert_eq!(triple, Some((366, 675, 979)))
     }
 
-    #[bench]
-    fn bench_small_pair_product(b: &mut Bencher) {
-        let entries = entries_from_file("test/sample");
-
-        b.iter(|| {
-            let entries = entries.clone();
-            pair_product(entries, 2020).unwrap();
-        })
-    }
-
-    #[bench]
-    fn bench_small_triple_product(b: &mut Bencher) {
-        let entries = entries_from_file("test/sample");
-
-        b.iter(|| {
-            let entries = entries.clone();
-            triple_product(entries, 2020).unwrap();
-        })
-    }
-
-    #[bench]
-    fn bench_large_pair_product(b: &mut Bencher) {
-        let entries = entries_from_file("test/input");
-
-        b.iter(|| {
-            let entries = entries.clone();
-            pair_product(entries, 2020).unwrap();
-        })
-    }
-
-    #[bench]
-    fn bench_large_triple_product(b: &mut Bencher) {
-        let entries = entries_from_file("test/input");
-
-        b.iter(|| {
-            let entries = entries.clone();
-            triple_product(entries, 2020).unwrap();
-        })
+    #[test]
+    fn test_parse_invalid() {
+        let error = parse("1721\n979\n-366\n").unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (3, "-366"));
     }
 }
@@ -1,34 +1,29 @@
-#![feature(test)]
-
+use adventofcode::error::ParseError;
+use adventofcode::grid::Grid;
+use adventofcode::solution::Solution;
+use std::convert::TryFrom;
 use std::fs;
 
 #[derive(Debug, Clone)]
-enum Tile {
+pub enum Tile {
     Open,
     Tree,
 }
 
-impl From<char> for Tile {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Tile {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            '.' => Tile::Open,
-            '#' => Tile::Tree,
-            _ => panic!("Invalid character {}", c),
+            '.' => Ok(Tile::Open),
+            '#' => Ok(Tile::Tree),
+            _ => Err(ParseError::invalid(c, "'.' or '#'")),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-struct Map {
-    width: usize,
-    tiles: Vec<Tile>,
-}
-
-impl Map {
-    fn height(self: &Self) -> usize {
-        self.tiles.len() / self.width
-    }
-}
+/// The area below the toboggan, repeating endlessly to the right
+pub type Map = Grid<Tile>;
 
 #[derive(Debug, Clone)]
 struct Slope {
@@ -38,51 +33,23 @@ struct Slope {
 
 #[allow(dead_code)]
 fn read_map(path: &str) -> Option<Map> {
-    if let Ok(input) = fs::read_to_string(path) {
-        let width = input.find("\n")?;
-        let tiles: Vec<Tile> = input
-            .chars()
-            .filter(|c| c != &'\n')
-            .map(|c| c.into())
-            .collect();
-
-        Some(Map {
-            width: width,
-            tiles: tiles,
-        })
-    } else {
-        None
-    }
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|input| parse_map(input.as_str()).ok())
+}
+
+/// Parses the map one row per line, every row must be as wide as the first
+fn parse_map(input: &str) -> Result<Map, ParseError> {
+    Grid::parse(input, Tile::try_from)
 }
 
-#[allow(dead_code)]
 fn tree_encounters(map: Map, slope: Slope) -> usize {
     (0..(map.height() / slope.down))
-        .map(|level| {
-            let x = {
-                let offset_x = slope.right * level;
-                if offset_x >= map.width {
-                    offset_x % map.width
-                } else {
-                    offset_x
-                }
-            };
-            let y = slope.down * level;
-            let index = x + (y * map.width);
-
-            println!("l: {}, x: {}, y: {}, i: {}", level, x, y, index);
-
-            map.tiles.get(index)
-        })
-        .filter(|tile| match tile {
-            Some(Tile::Tree) => true,
-            Some(Tile::Open) => false,
-            None => false,
-        })
+        .map(|level| map.get_wrapping((slope.right * level) as i64, (slope.down * level) as i64))
+        .filter(|tile| matches!(tile, Tile::Tree))
         .count()
 }
 
-#[allow(dead_code)]
 fn slope_products(map: Map) -> usize {
     let slopes = vec![
         Slope { right: 1, down: 1 },
@@ -94,39 +61,52 @@ fn slope_products(map: Map) -> usize {
 
     slopes
         .into_iter()
-        .map(|slope| {
-            println!("------------------");
-            println!("{:#?}", slope);
-            println!("Map:\n  height: {}, width: {}", map.height(), map.width);
-            tree_encounters(map.clone(), slope)
-        })
-        .fold(1, |acc, ele| {
-            println!("acc: {}, ele: {}", acc, ele);
-            acc * ele
-        })
+        .map(|slope| tree_encounters(map.clone(), slope))
+        .product()
+}
+
+/// Day 3: Toboggan Trajectory
+pub struct TobogganTrajectory;
+
+impl Solution for TobogganTrajectory {
+    const YEAR: u16 = 2020;
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Toboggan Trajectory";
+
+    type Input = Map;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_map(input.trim())
+    }
+
+    fn part1(map: &Self::Input) -> Self::Answer {
+        tree_encounters(map.clone(), Slope { right: 3, down: 1 })
+    }
+
+    fn part2(map: &Self::Input) -> Self::Answer {
+        slope_products(map.clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn test_read_sample_map() {
         let map = read_map("test/sample");
         assert!(map.is_some());
-        assert_eq!(map.clone().unwrap().tiles.len(), 121);
-        assert_eq!(map.clone().unwrap().width, 11);
+        assert_eq!(map.clone().unwrap().len(), 121);
+        assert_eq!(map.clone().unwrap().width(), 11);
     }
 
     #[test]
     fn test_read_input_map() {
         let map = read_map("test/input");
         assert!(map.is_some());
-        assert_eq!(map.clone().unwrap().tiles.len(), 10013);
-        assert_eq!(map.clone().unwrap().width, 31);
+        assert_eq!(map.clone().unwrap().len(), 10013);
+        assert_eq!(map.clone().unwrap().width(), 31);
     }
 
     #[test]
@@ -159,55 +139,12 @@ mod tests {
         assert_eq!(slope_products(map), 3064612320);
     }
 
-    #[bench]
-    fn bench_sample_read_map(b: &mut Bencher) {
-        b.iter(|| {
-            read_map("test/sample").unwrap();
-        })
-    }
-
-    #[bench]
-    fn bench_input_read_map(b: &mut Bencher) {
-        b.iter(|| {
-            read_map("test/input").unwrap();
-        })
-    }
-
-    #[bench]
-    fn bench_sample_tree_encounters(b: &mut Bencher) {
-        let map = read_map("test/sample").unwrap();
-        let slope = Slope { right: 3, down: 1 };
-
-        b.iter(|| {
-            tree_encounters(map.clone(), slope.clone());
-        })
-    }
-
-    #[bench]
-    fn bench_input_tree_encounters(b: &mut Bencher) {
-        let map = read_map("test/input").unwrap();
-        let slope = Slope { right: 3, down: 1 };
-
-        b.iter(|| {
-            tree_encounters(map.clone(), slope.clone());
-        })
-    }
-
-    #[bench]
-    fn bench_sample_slope_products(b: &mut Bencher) {
-        let map = read_map("test/sample").unwrap();
-
-        b.iter(|| {
-            slope_products(map.clone());
-        })
-    }
-
-    #[bench]
-    fn bench_input_slope_products(b: &mut Bencher) {
-        let map = read_map("test/input").unwrap();
+    #[test]
+    fn test_parse_invalid_map() {
+        let error = parse_map("..#\n.x.\n").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "x"));
 
-        b.iter(|| {
-            slope_products(map.clone());
-        })
+        let error = parse_map("..#\n.#").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
     }
 }
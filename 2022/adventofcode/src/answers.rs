@@ -0,0 +1,230 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::registry::Part;
+
+/// Environment variable overriding where recorded answers are kept
+pub const ANSWERS_DIR_VAR: &str = "AOC_ANSWERS_DIR";
+
+/// The confirmed answers to both parts of a single day's puzzle
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Answers {
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+
+    pub fn set(&mut self, part: Part, answer: impl Into<String>) {
+        let answer = Some(answer.into());
+
+        match part {
+            Part::One => self.part1 = answer,
+            Part::Two => self.part2 = answer,
+        }
+    }
+
+    /// Parses an answers file, one `part <n>: <answer>` line per solved part
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::answers::Answers;
+    /// # use adventofcode::registry::Part;
+    /// let answers = Answers::parse("part 1: 280\n").unwrap();
+    /// assert_eq!(answers.get(Part::One), Some("280"));
+    /// assert_eq!(answers.get(Part::Two), None);
+    /// ```
+    pub fn parse(input: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (part, answer) = line
+                .strip_prefix("part ")
+                .and_then(|rest| rest.split_once(':'))
+                .ok_or_else(|| format!("Malformed answer {}", line))?;
+            let part = part
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| format!("Malformed answer {}", line))?;
+
+            answers.set(Part::try_from(part)?, answer.trim());
+        }
+
+        Ok(answers)
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in Part::ALL {
+            if let Some(answer) = self.get(part) {
+                writeln!(f, "part {}: {}", part, answer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Directory of recorded answers laid out as `<year>/day<dd>.txt`, kept under version control so solvers can be
+/// checked against them after every change
+#[derive(Clone, Debug)]
+pub struct AnswerBook {
+    dir: PathBuf,
+}
+
+impl AnswerBook {
+    pub fn new(dir: impl Into<PathBuf>) -> AnswerBook {
+        AnswerBook { dir: dir.into() }
+    }
+
+    /// Uses the `AOC_ANSWERS_DIR` environment variable, defaulting to the `answers` directory at the root of the
+    /// repository
+    pub fn from_env() -> AnswerBook {
+        let dir = env::var_os(ANSWERS_DIR_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../answers"));
+
+        AnswerBook::new(dir)
+    }
+
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    /// Location of the answers file for a given year and day
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{:02}.txt", day))
+    }
+
+    /// Loads the answers recorded for a given year and day, none are recorded when the file does not exist
+    pub fn load(&self, year: u16, day: u8) -> Result<Answers, String> {
+        let path = self.path(year, day);
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                Answers::parse(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("Could not read file {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Writes the answers for a given year and day, replacing any already recorded
+    pub fn save(&self, year: u16, day: u8, answers: &Answers) -> Result<(), String> {
+        let path = self.path(year, day);
+        let write = || -> io::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, answers.to_string())
+        };
+
+        write().map_err(|e| format!("Could not write file {}: {}", path.display(), e))
+    }
+}
+
+/// Outcome of checking a solver's answer against the recorded one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The answer matches the recorded answer
+    Pass,
+    /// No answer could be produced
+    Fail(String),
+    /// The answer differs from the recorded answer
+    Changed { expected: String, actual: String },
+    /// An answer was produced but there is nothing recorded to compare it with
+    Unrecorded(String),
+}
+
+impl Status {
+    /// Compares a solver's outcome with the recorded answer, if any
+    pub fn check(expected: Option<&str>, actual: Result<String, String>) -> Status {
+        match (expected, actual) {
+            (_, Err(message)) => Status::Fail(message),
+            (Some(expected), Ok(actual)) if expected == actual.trim() => Status::Pass,
+            (Some(expected), Ok(actual)) => Status::Changed {
+                expected: expected.to_string(),
+                actual,
+            },
+            (None, Ok(actual)) => Status::Unrecorded(actual),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail(message) => write!(f, "fail ({})", message),
+            Status::Changed { expected, actual } => {
+                write!(f, "changed (expected {}, got {})", expected, actual)
+            }
+            Status::Unrecorded(actual) => write!(f, "unrecorded (got {})", actual),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut answers = Answers::default();
+        answers.set(Part::One, "280");
+        answers.set(Part::Two, "1797");
+
+        assert_eq!(answers.to_string(), "part 1: 280\npart 2: 1797\n");
+        assert_eq!(Answers::parse(&answers.to_string()), Ok(answers));
+        assert!(Answers::parse("part 3: 1\n").is_err());
+        assert!(Answers::parse("280\n").is_err());
+    }
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = env::temp_dir().join(format!("adventofcode-answers-{}", std::process::id()));
+        let book = AnswerBook::new(&dir);
+
+        assert_eq!(book.load(2015, 1), Ok(Answers::default()));
+
+        let answers = Answers::parse("part 2: abc\n").unwrap();
+        book.save(2015, 1, &answers).unwrap();
+        assert_eq!(book.load(2015, 1), Ok(answers));
+        assert!(dir.join("2015").join("day01.txt").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(Status::check(Some("1"), Ok("1".to_string())), Status::Pass);
+        assert_eq!(
+            Status::check(Some("1"), Ok("2".to_string())),
+            Status::Changed {
+                expected: "1".to_string(),
+                actual: "2".to_string()
+            }
+        );
+        assert_eq!(
+            Status::check(Some("1"), Err("boom".to_string())),
+            Status::Fail("boom".to_string())
+        );
+        assert_eq!(
+            Status::check(None, Ok("2".to_string())),
+            Status::Unrecorded("2".to_string())
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use std::fmt;
+use std::hint::black_box;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::error::ParseError;
+use crate::solution::Solution;
+
+/// Header line of the machine readable results
+pub const CSV_HEADER: &str = "year,day,stage,runs,min_ns,median_ns,max_ns";
+
+/// A separately timed step of solving a puzzle
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Part1 => write!(f, "part1"),
+            Stage::Part2 => write!(f, "part2"),
+        }
+    }
+}
+
+impl FromStr for Stage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "parse" => Ok(Stage::Parse),
+            "part1" => Ok(Stage::Part1),
+            "part2" => Ok(Stage::Part2),
+            o => Err(format!("Unknown stage {}", o)),
+        }
+    }
+}
+
+/// Summary of the time taken over repeated runs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarises a set of samples, there must be at least one
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::bench::Stats;
+    /// # use std::time::Duration;
+    /// let samples = [5, 1, 3, 9, 4].map(Duration::from_millis).to_vec();
+    /// let stats = Stats::from_samples(samples);
+    /// assert_eq!(stats.min, Duration::from_millis(1));
+    /// assert_eq!(stats.median, Duration::from_millis(4));
+    /// assert_eq!(stats.max, Duration::from_millis(9));
+    /// ```
+    pub fn from_samples(mut samples: Vec<Duration>) -> Stats {
+        assert!(!samples.is_empty(), "Cannot summarise zero samples");
+        samples.sort();
+
+        let runs = samples.len();
+        let median = if runs.is_multiple_of(2) {
+            (samples[runs / 2 - 1] + samples[runs / 2]) / 2
+        } else {
+            samples[runs / 2]
+        };
+
+        Stats {
+            runs,
+            min: samples[0],
+            median,
+            max: samples[runs - 1],
+        }
+    }
+
+    /// Calls `f` `runs` times, timing each call
+    pub fn sample<T>(runs: usize, mut f: impl FnMut() -> T) -> Stats {
+        let samples = (0..runs.max(1))
+            .map(|_| {
+                let start = Instant::now();
+                black_box(f());
+                start.elapsed()
+            })
+            .collect();
+
+        Stats::from_samples(samples)
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:?} median {:?} max {:?} ({} runs)",
+            self.min, self.median, self.max, self.runs
+        )
+    }
+}
+
+/// Timing of a single stage of a single puzzle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+impl fmt::Display for Record {
+    /// Formats the record as a line of CSV matching [`CSV_HEADER`]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{},{},{},{}",
+            self.year,
+            self.day,
+            self.stage,
+            self.stats.runs,
+            self.stats.min.as_nanos(),
+            self.stats.median.as_nanos(),
+            self.stats.max.as_nanos()
+        )
+    }
+}
+
+impl FromStr for Record {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.trim().split(',').collect();
+
+        if fields.len() != 7 {
+            return Err(format!("Malformed benchmark record {}", s));
+        }
+
+        let malformed = |_| format!("Malformed benchmark record {}", s);
+        let nanos = |field: &str| field.parse().map(Duration::from_nanos).map_err(malformed);
+
+        Ok(Record {
+            year: fields[0].parse().map_err(malformed)?,
+            day: fields[1].parse().map_err(malformed)?,
+            stage: fields[2].parse()?,
+            stats: Stats {
+                runs: fields[3].parse().map_err(malformed)?,
+                min: nanos(fields[4])?,
+                median: nanos(fields[5])?,
+                max: nanos(fields[6])?,
+            },
+        })
+    }
+}
+
+/// Times parsing and both parts of a puzzle separately, running each stage `runs` times
+pub fn measure<S: Solution>(input: &str, runs: usize) -> Result<Vec<Record>, ParseError> {
+    let parsed = S::parse(input)?;
+    let record = |stage, stats| Record {
+        year: S::YEAR,
+        day: S::DAY,
+        stage,
+        stats,
+    };
+
+    Ok(vec![
+        record(Stage::Parse, Stats::sample(runs, || S::parse(input))),
+        record(Stage::Part1, Stats::sample(runs, || S::part1(&parsed))),
+        record(Stage::Part2, Stats::sample(runs, || S::part2(&parsed))),
+    ])
+}
+
+/// Writes records as CSV, header included
+pub fn to_csv(records: &[Record]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+
+    for record in records {
+        csv.push_str(&format!("{}\n", record));
+    }
+
+    csv
+}
+
+/// Reads records written by [`to_csv`]
+pub fn from_csv(csv: &str) -> Result<Vec<Record>, String> {
+    csv.lines()
+        .filter(|line| !line.trim().is_empty() && line.trim() != CSV_HEADER)
+        .map(|line| line.parse())
+        .collect()
+}
+
+/// How the median time of a stage moved between two sets of results
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    pub year: u16,
+    pub day: u8,
+    pub stage: Stage,
+    pub baseline: Option<Duration>,
+    pub current: Option<Duration>,
+}
+
+impl Comparison {
+    /// Relative change of the median in percent, when the stage was timed in both results
+    pub fn change(&self) -> Option<f64> {
+        match (self.baseline, self.current) {
+            (Some(baseline), Some(current)) if !baseline.is_zero() => Some(
+                (current.as_secs_f64() - baseline.as_secs_f64()) / baseline.as_secs_f64() * 100.0,
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {:>2} {}: ", self.year, self.day, self.stage)?;
+
+        match (self.baseline, self.current, self.change()) {
+            (Some(baseline), Some(current), Some(change)) => {
+                write!(f, "{:?} -> {:?} ({:+.1}%)", baseline, current, change)
+            }
+            (Some(baseline), Some(current), None) => write!(f, "{:?} -> {:?}", baseline, current),
+            (Some(baseline), None, _) => write!(f, "{:?} -> missing", baseline),
+            (None, Some(current), _) => write!(f, "missing -> {:?}", current),
+            (None, None, _) => write!(f, "missing"),
+        }
+    }
+}
+
+/// Pairs up the stages of two sets of results by year, day and stage
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::bench::*;
+/// let baseline = from_csv("2015,7,part1,10,1000,2000,3000\n").unwrap();
+/// let current = from_csv("2015,7,part1,10,500,1000,1500\n").unwrap();
+///
+/// let comparisons = compare(&baseline, &current);
+/// assert_eq!(comparisons[0].change(), Some(-50.0));
+/// ```
+pub fn compare(baseline: &[Record], current: &[Record]) -> Vec<Comparison> {
+    let key = |record: &Record| (record.year, record.day, record.stage);
+    let median = |records: &[Record], k| {
+        records
+            .iter()
+            .find(|record| key(record) == k)
+            .map(|record| record.stats.median)
+    };
+
+    let mut keys: Vec<_> = baseline.iter().chain(current).map(key).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .map(|k| Comparison {
+            year: k.0,
+            day: k.1,
+            stage: k.2,
+            baseline: median(baseline, k),
+            current: median(current, k),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(stage: Stage, median: u64) -> Record {
+        Record {
+            year: 2020,
+            day: 1,
+            stage,
+            stats: Stats {
+                runs: 3,
+                min: Duration::from_nanos(median / 2),
+                median: Duration::from_nanos(median),
+                max: Duration::from_nanos(median * 2),
+            },
+        }
+    }
+
+    #[test]
+    fn test_even_median() {
+        let stats = Stats::from_samples([4, 1, 3, 2].map(Duration::from_nanos).to_vec());
+        assert_eq!(stats.median, Duration::from_nanos(2));
+        assert_eq!(stats.runs, 4);
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut calls = 0;
+        let stats = Stats::sample(5, || calls += 1);
+
+        assert_eq!(calls, 5);
+        assert_eq!(stats.runs, 5);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let records = vec![record(Stage::Parse, 10), record(Stage::Part2, 2000)];
+        let csv = to_csv(&records);
+
+        assert_eq!(
+            csv,
+            format!(
+                "{}\n2020,1,parse,3,5,10,20\n2020,1,part2,3,1000,2000,4000\n",
+                CSV_HEADER
+            )
+        );
+        assert_eq!(from_csv(&csv), Ok(records));
+        assert!(from_csv("2020,1,part3,3,5,10,20").is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = vec![record(Stage::Parse, 100), record(Stage::Part1, 100)];
+        let current = vec![record(Stage::Part1, 150), record(Stage::Part2, 10)];
+        let lines: Vec<String> = compare(&baseline, &current)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "2020 day  1 parse: 100ns -> missing",
+                "2020 day  1 part1: 100ns -> 150ns (+50.0%)",
+                "2020 day  1 part2: missing -> 10ns",
+            ]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+/// Describes where and why a puzzle input could not be parsed
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::error::ParseError;
+/// let error = ParseError::new(3, "x", "an instruction").on_line(2);
+/// assert_eq!(
+///     error.to_string(),
+///     "line 2, column 3: expected an instruction, found \"x\""
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the input the error was found on, starting at 1
+    pub line: usize,
+    /// Character column within the line the offending text starts at, starting at 1
+    pub column: usize,
+    /// The text which could not be parsed
+    pub text: String,
+    /// Description of what was expected in its place
+    pub expected: String,
+}
+
+impl ParseError {
+    /// An error on the first line of the input, parsers working line by line report errors this way and leave it to
+    /// [`parse_lines`] to fill in the actual line
+    pub fn new(column: usize, text: impl Into<String>, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            line: 1,
+            column,
+            text: text.into(),
+            expected: expected.into(),
+        }
+    }
+
+    /// An error covering the whole of `text`
+    pub fn invalid(text: impl Into<String>, expected: impl Into<String>) -> ParseError {
+        ParseError::new(1, text, expected)
+    }
+
+    /// Moves the error to the given line
+    pub fn on_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+
+    /// Shifts the error right by `columns` characters, for errors found within part of a line
+    pub fn offset(mut self, columns: usize) -> ParseError {
+        self.column += columns;
+        self
+    }
+
+    /// Renders the error along with the offending line of `input` and a marker under the offending text
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::error::ParseError;
+    /// let error = ParseError::new(3, "x", "'(' or ')'");
+    /// assert_eq!(
+    ///     error.diagnostic("(()x)"),
+    ///     "line 1, column 3: expected '(' or ')', found \"x\"\n  |\n1 | (()x)\n  |   ^\n"
+    /// );
+    /// ```
+    pub fn diagnostic(&self, input: &str) -> String {
+        let source = match input.lines().nth(self.line.saturating_sub(1)) {
+            Some(source) => source,
+            None => return format!("{}\n", self),
+        };
+
+        let gutter = " ".repeat(self.line.to_string().len());
+        let indent = " ".repeat(self.column.saturating_sub(1));
+        let width = self.text.chars().count().max(1);
+
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}{}\n",
+            self,
+            gutter,
+            self.line,
+            source,
+            gutter,
+            indent,
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found {:?}",
+            self.line, self.column, self.expected, self.text
+        )
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Failure to load a puzzle input from disk
+#[derive(Debug)]
+pub enum Error {
+    /// The input file could not be read
+    Io(String, io::Error),
+    /// The input file was read but could not be parsed
+    Parse(String, ParseError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(path, e) => write!(f, "Could not read file {}: {}", path, e),
+            Error::Parse(path, e) => write!(f, "{}: {}", path, e),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(_, e) => Some(e),
+            Error::Parse(_, e) => Some(e),
+        }
+    }
+}
+
+/// Parses every line of the input with `f`, attaching the line number to any error
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::error::{parse_lines, ParseError};
+/// let number = |line: &str| line.parse::<u32>().map_err(|_| ParseError::invalid(line, "a number"));
+///
+/// assert_eq!(parse_lines("1\n2", number), Ok(vec![1, 2]));
+/// assert_eq!(parse_lines("1\nx", number).unwrap_err().line, 2);
+/// ```
+pub fn parse_lines<T>(
+    input: &str,
+    f: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| {
+            f(line).map_err(|e| {
+                let line = n + e.line;
+                e.on_line(line)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines_relative() {
+        let two_lines = |line: &str| -> Result<(), ParseError> {
+            Err(ParseError::new(4, line, "nothing").on_line(2))
+        };
+
+        let error = parse_lines("a\nb", two_lines).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn test_diagnostic_past_end() {
+        let error = ParseError::new(1, "", "more input").on_line(3);
+        assert_eq!(
+            error.diagnostic("a\nb"),
+            "line 3, column 1: expected more input, found \"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_wide_line_number() {
+        let input = "\n".repeat(11) + "1x3";
+        let error = ParseError::new(2, "x", "a digit").on_line(12);
+        assert_eq!(
+            error.diagnostic(&input),
+            "line 12, column 2: expected a digit, found \"x\"\n   |\n12 | 1x3\n   |  ^\n"
+        );
+    }
+}
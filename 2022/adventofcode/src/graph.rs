@@ -0,0 +1,738 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Add;
+use std::slice;
+
+/// Assigns each distinct node a dense index, in the order nodes are first seen
+#[derive(Clone, Debug)]
+struct Indexed<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+}
+
+impl<N: Clone + Eq + Hash> Indexed<N> {
+    fn new() -> Indexed<N> {
+        Indexed {
+            nodes: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+    fn get(&self, node: &N) -> Option<usize> {
+        self.index.get(node).copied()
+    }
+
+    fn get_or_insert(&mut self, node: N) -> usize {
+        if let Some(i) = self.get(&node) {
+            return i;
+        }
+
+        self.index.insert(node.clone(), self.nodes.len());
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+}
+
+/// A directed graph stored as adjacency lists in both directions. Nodes are identified by their values and kept in
+/// the order they were added, so every traversal is deterministic.
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::graph::Graph;
+/// let mut graph = Graph::new();
+/// graph.add_edge("a", "b", 7);
+/// graph.add_edge("b", "c", 2);
+/// graph.add_edge("a", "c", 10);
+///
+/// assert_eq!(graph.bfs(&"a"), vec!["a", "b", "c"]);
+/// assert_eq!(graph.topological_sort().unwrap(), vec!["a", "b", "c"]);
+/// assert_eq!(graph.shortest_path(&"a", &"c"), Some((vec!["a", "b", "c"], 9)));
+/// assert_eq!(graph.predecessors(&"c").count(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Graph<N, W = ()> {
+    nodes: Indexed<N>,
+    outgoing: Vec<Vec<(usize, W)>>,
+    incoming: Vec<Vec<(usize, W)>>,
+}
+
+impl<N: Clone + Eq + Hash, W: Clone> Default for Graph<N, W> {
+    fn default() -> Self {
+        Graph {
+            nodes: Indexed::new(),
+            outgoing: vec![],
+            incoming: vec![],
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash, W: Clone> Graph<N, W> {
+    pub fn new() -> Graph<N, W> {
+        Graph::default()
+    }
+
+    /// Adds a node without any edges, doing nothing if it is already present
+    pub fn add_node(&mut self, node: N) {
+        let i = self.nodes.get_or_insert(node);
+
+        if i == self.outgoing.len() {
+            self.outgoing.push(vec![]);
+            self.incoming.push(vec![]);
+        }
+    }
+
+    /// Adds an edge, and either node if not already present
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) {
+        self.add_node(from.clone());
+        self.add_node(to.clone());
+
+        let from = self.nodes.get_or_insert(from);
+        let to = self.nodes.get_or_insert(to);
+        self.outgoing[from].push((to, weight.clone()));
+        self.incoming[to].push((from, weight));
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.nodes.get(node).is_some()
+    }
+
+    /// Number of nodes in the graph
+    pub fn len(&self) -> usize {
+        self.nodes.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.nodes.is_empty()
+    }
+
+    /// Nodes in the order they were added
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.nodes.iter()
+    }
+
+    /// Every edge as `(from, to, weight)`
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N, &W)> {
+        self.outgoing
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, edges)| {
+                edges
+                    .iter()
+                    .map(move |(to, w)| (&self.nodes.nodes[from], &self.nodes.nodes[*to], w))
+            })
+    }
+
+    fn adjacent<'a>(
+        &'a self,
+        lists: &'a [Vec<(usize, W)>],
+        node: &N,
+    ) -> impl Iterator<Item = (&'a N, &'a W)> {
+        self.nodes
+            .get(node)
+            .into_iter()
+            .flat_map(move |i| lists[i].iter().map(|(j, w)| (&self.nodes.nodes[*j], w)))
+    }
+
+    /// Nodes reached by the edges leaving `node`, along with their weights
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = (&N, &W)> {
+        self.adjacent(&self.outgoing, node)
+    }
+
+    /// Nodes with edges leading to `node`, along with their weights
+    pub fn predecessors(&self, node: &N) -> impl Iterator<Item = (&N, &W)> {
+        self.adjacent(&self.incoming, node)
+    }
+
+    fn successor_fn(&self) -> impl FnMut(&N) -> Vec<N> + '_ {
+        |node| self.successors(node).map(|(n, _)| n.clone()).collect()
+    }
+
+    /// The same graph with every edge pointing the other way
+    pub fn reverse(&self) -> Graph<N, W> {
+        Graph {
+            nodes: self.nodes.clone(),
+            outgoing: self.incoming.clone(),
+            incoming: self.outgoing.clone(),
+        }
+    }
+
+    /// Every node reachable from `start`, `start` included, in breadth first order
+    pub fn bfs(&self, start: &N) -> Vec<N> {
+        bfs(start.clone(), self.successor_fn())
+    }
+
+    /// Every node reachable from `start`, `start` included, in depth first order
+    pub fn dfs(&self, start: &N) -> Vec<N> {
+        dfs(start.clone(), self.successor_fn())
+    }
+
+    /// Orders the nodes so that every edge points forwards, see [`topological_sort`]
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        topological_sort(&self.nodes.nodes, self.successor_fn())
+    }
+
+    /// Groups the nodes into strongly connected components, see [`strongly_connected_components`]
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        strongly_connected_components(&self.nodes.nodes, self.successor_fn())
+    }
+}
+
+impl<N, W> Graph<N, W>
+where
+    N: Clone + Eq + Hash,
+    W: Copy + Ord + Default + Add<Output = W>,
+{
+    /// The cheapest path between two nodes using the edge weights as costs, along with its total cost
+    pub fn shortest_path(&self, start: &N, goal: &N) -> Option<(Vec<N>, W)> {
+        dijkstra(
+            start.clone(),
+            |node| {
+                self.successors(node)
+                    .map(|(n, w)| (n.clone(), *w))
+                    .collect::<Vec<_>>()
+            },
+            |node| node == goal,
+        )
+    }
+}
+
+/// A cycle which prevented a topological sort
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle<N> {
+    /// The nodes which do not depend on any cycle, in topological order
+    pub sorted: Vec<N>,
+    /// The nodes making up one of the cycles, each with an edge to the next and the last with an edge to the first
+    pub cycle: Vec<N>,
+}
+
+impl<N: fmt::Display> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle ")?;
+
+        for node in &self.cycle {
+            write!(f, "{} -> ", node)?;
+        }
+
+        match self.cycle.first() {
+            Some(first) => write!(f, "{}", first),
+            None => write!(f, "(empty)"),
+        }
+    }
+}
+
+/// Every node reachable from `start`, `start` included, in breadth first order
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::graph::bfs;
+/// let reachable = bfs(1, |n| if *n < 20 { vec![n * 2, n * 3] } else { vec![] });
+/// assert_eq!(reachable[..6], [1, 2, 3, 4, 6, 9]);
+/// ```
+pub fn bfs<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+    let mut order = vec![];
+
+    while let Some(node) = queue.pop_front() {
+        for next in successors(&node) {
+            if seen.insert(next.clone()) {
+                queue.push_back(next);
+            }
+        }
+
+        order.push(node);
+    }
+
+    order
+}
+
+/// A path from `start` to the nearest node satisfying `goal` taking the fewest steps
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::graph::bfs_path;
+/// let path = bfs_path(1, |n| [n + 1, n * 2], |n| *n == 10).unwrap();
+/// assert_eq!(path, vec![1, 2, 4, 5, 10]);
+/// ```
+pub fn bfs_path<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut nodes = Indexed::new();
+    let mut parents = vec![None];
+    let mut queue = VecDeque::from([nodes.get_or_insert(start)]);
+
+    while let Some(i) = queue.pop_front() {
+        if goal(&nodes.nodes[i]) {
+            return Some(path(&nodes.nodes, &parents, i));
+        }
+
+        for next in successors(&nodes.nodes[i]) {
+            if nodes.get(&next).is_none() {
+                queue.push_back(nodes.get_or_insert(next));
+                parents.push(Some(i));
+            }
+        }
+    }
+
+    None
+}
+
+/// Every node reachable from `start`, `start` included, in depth first order
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::graph::dfs;
+/// let tree = |n: &u32| if *n < 4 { vec![n * 2, n * 2 + 1] } else { vec![] };
+/// assert_eq!(dfs(1, tree), vec![1, 2, 4, 5, 3, 6, 7]);
+/// ```
+pub fn dfs<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashSet::new();
+    let mut stack = vec![start];
+    let mut order = vec![];
+
+    while let Some(node) = stack.pop() {
+        if !seen.insert(node.clone()) {
+            continue;
+        }
+
+        let next: Vec<N> = successors(&node).into_iter().collect();
+        stack.extend(next.into_iter().rev().filter(|n| !seen.contains(n)));
+        order.push(node);
+    }
+
+    order
+}
+
+/// Follows the parent links back from `end` to the start of a search
+fn path<N: Clone>(nodes: &[N], parents: &[Option<usize>], end: usize) -> Vec<N> {
+    let mut path = vec![nodes[end].clone()];
+    let mut current = end;
+
+    while let Some(parent) = parents[current] {
+        path.push(nodes[parent].clone());
+        current = parent;
+    }
+
+    path.reverse();
+    path
+}
+
+/// The cheapest path from `start` to the nearest node satisfying `goal` along with its cost, where `successors`
+/// gives each neighboring node with the cost of moving to it
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::graph::dijkstra;
+/// // Doubling costs 1 and adding one costs 3
+/// let moves = |n: &u32| vec![(n * 2, 1), (n + 1, 3)];
+/// assert_eq!(dijkstra(1, moves, |n| *n == 6), Some((vec![1, 2, 3, 6], 5)));
+/// ```
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), goal)
+}
+
+/// As [`dijkstra`], guided towards the goal by `heuristic`. The heuristic must never overestimate the remaining
+/// cost for the path found to be the cheapest.
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::graph::astar;
+/// # use adventofcode::point::Point2;
+/// let goal = Point2::new(3, 2);
+/// let (path, cost) = astar(
+///     Point2::ORIGIN,
+///     |p| p.neighbors().map(|n| (n, 1)).collect::<Vec<_>>(),
+///     |p| p.manhattan(goal),
+///     |p| *p == goal,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(cost, 5);
+/// assert_eq!(path.len(), 6);
+/// ```
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut nodes = Indexed::new();
+    let mut parents = vec![None];
+    let mut costs = vec![C::default()];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    nodes.get_or_insert(start);
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > costs[i] {
+            continue;
+        }
+
+        if goal(&nodes.nodes[i]) {
+            return Some((path(&nodes.nodes, &parents, i), cost));
+        }
+
+        for (next, step) in successors(&nodes.nodes[i]) {
+            let next_cost = cost + step;
+            let j = match nodes.get(&next) {
+                Some(j) if next_cost >= costs[j] => continue,
+                Some(j) => {
+                    costs[j] = next_cost;
+                    parents[j] = Some(i);
+                    j
+                }
+                None => {
+                    costs.push(next_cost);
+                    parents.push(Some(i));
+                    nodes.get_or_insert(next)
+                }
+            };
+
+            heap.push(Reverse((
+                next_cost + heuristic(&nodes.nodes[j]),
+                next_cost,
+                j,
+            )));
+        }
+    }
+
+    None
+}
+
+/// Discovers every node reachable from `nodes` along with the successors of each, by index
+fn adjacency<N, I>(
+    nodes: &[N],
+    mut successors: impl FnMut(&N) -> I,
+) -> (Indexed<N>, Vec<Vec<usize>>)
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut indexed = Indexed::new();
+    nodes.iter().for_each(|node| {
+        indexed.get_or_insert(node.clone());
+    });
+
+    let mut edges = vec![];
+    while edges.len() < indexed.nodes.len() {
+        let next = successors(&indexed.nodes[edges.len()]);
+        edges.push(
+            next.into_iter()
+                .map(|node| indexed.get_or_insert(node))
+                .collect(),
+        );
+    }
+
+    (indexed, edges)
+}
+
+/// Orders the nodes so that each comes before all of its successors. Successors not in `nodes` are included too.
+/// Nodes which do not depend on one another keep the order they were given in.
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::graph::topological_sort;
+/// let depends = |n: &char| match n {
+///     'a' => vec!['c'],
+///     'b' => vec!['a', 'c'],
+///     'c' => vec!['d'],
+///     _ => vec![],
+/// };
+///
+/// assert_eq!(topological_sort(&['a', 'b'], depends), Ok(vec!['b', 'a', 'c', 'd']));
+///
+/// let error = topological_sort(&['a', 'b'], |n| if *n == 'a' { vec!['b'] } else { vec!['a'] });
+/// assert_eq!(error.unwrap_err().cycle, vec!['a', 'b']);
+/// ```
+pub fn topological_sort<N, I>(
+    nodes: &[N],
+    successors: impl FnMut(&N) -> I,
+) -> Result<Vec<N>, Cycle<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let (indexed, edges) = adjacency(nodes, successors);
+    let mut incoming = vec![0; edges.len()];
+    edges.iter().flatten().for_each(|&j| incoming[j] += 1);
+
+    let mut ready: VecDeque<usize> = (0..edges.len()).filter(|&i| incoming[i] == 0).collect();
+    let mut sorted = vec![];
+
+    while let Some(i) = ready.pop_front() {
+        sorted.push(i);
+
+        for &j in &edges[i] {
+            incoming[j] -= 1;
+            if incoming[j] == 0 {
+                ready.push_back(j);
+            }
+        }
+    }
+
+    let values = |indices: &[usize]| indices.iter().map(|&i| indexed.nodes[i].clone()).collect();
+
+    if sorted.len() == edges.len() {
+        return Ok(values(&sorted));
+    }
+
+    // Every node left over has a predecessor which is also left over, so walking backwards through those must
+    // eventually revisit a node
+    let mut predecessors = vec![vec![]; edges.len()];
+    for (i, next) in edges.iter().enumerate() {
+        next.iter().for_each(|&j| predecessors[j].push(i));
+    }
+
+    let mut walk = vec![];
+    let mut position = HashMap::new();
+    let mut current = (0..edges.len())
+        .find(|&i| incoming[i] > 0)
+        .expect("Unsorted nodes remain");
+
+    while !position.contains_key(&current) {
+        position.insert(current, walk.len());
+        walk.push(current);
+        current = *predecessors[current]
+            .iter()
+            .find(|&&p| incoming[p] > 0)
+            .expect("Unsorted node without an unsorted predecessor");
+    }
+
+    let mut cycle = walk.split_off(position[&current]);
+    cycle.reverse();
+    cycle.rotate_right(1);
+
+    Err(Cycle {
+        sorted: values(&sorted),
+        cycle: values(&cycle),
+    })
+}
+
+/// Groups nodes, and any nodes reachable from them, into sets within which every node can reach every other.
+/// Components are returned in reverse topological order, so no component has an edge to one returned after it.
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::graph::strongly_connected_components;
+/// let edges = |n: &u8| match n {
+///     1 => vec![2],
+///     2 => vec![3, 4],
+///     3 => vec![1],
+///     _ => vec![],
+/// };
+///
+/// let mut components = strongly_connected_components(&[1], edges);
+/// components.iter_mut().for_each(|c| c.sort());
+/// assert_eq!(components, vec![vec![4], vec![1, 2, 3]]);
+/// ```
+pub fn strongly_connected_components<N, I>(
+    nodes: &[N],
+    successors: impl FnMut(&N) -> I,
+) -> Vec<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let (indexed, edges) = adjacency(nodes, successors);
+    let mut tarjan = Tarjan {
+        edges: &edges,
+        index: vec![None; edges.len()],
+        lowlink: vec![0; edges.len()],
+        on_stack: vec![false; edges.len()],
+        stack: vec![],
+        next: 0,
+        components: vec![],
+    };
+
+    for i in 0..edges.len() {
+        if tarjan.index[i].is_none() {
+            tarjan.visit(i);
+        }
+    }
+
+    tarjan
+        .components
+        .into_iter()
+        .map(|component| {
+            component
+                .into_iter()
+                .map(|i| indexed.nodes[i].clone())
+                .collect()
+        })
+        .collect()
+}
+
+/// State of Tarjan's strongly connected components algorithm
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    lowlink: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl<'a> Tarjan<'a> {
+    /// Visits every node reachable from `root`, keeping an explicit stack of the nodes being visited along with the
+    /// successors each has left to visit so long paths cannot overflow the call stack
+    fn visit(&mut self, root: usize) {
+        let mut frames = vec![self.enter(root)];
+
+        while let Some((i, successors)) = frames.last_mut() {
+            let i = *i;
+
+            match successors.next() {
+                Some(&j) => match self.index[j] {
+                    None => frames.push(self.enter(j)),
+                    Some(index) if self.on_stack[j] => self.lowlink[i] = self.lowlink[i].min(index),
+                    Some(_) => {}
+                },
+                None => {
+                    frames.pop();
+                    self.leave(i);
+
+                    if let Some(&(parent, _)) = frames.last() {
+                        self.lowlink[parent] = self.lowlink[parent].min(self.lowlink[i]);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Numbers a node on first reaching it, returning its frame
+    fn enter(&mut self, i: usize) -> (usize, slice::Iter<'a, usize>) {
+        self.index[i] = Some(self.next);
+        self.lowlink[i] = self.next;
+        self.next += 1;
+        self.stack.push(i);
+        self.on_stack[i] = true;
+
+        (i, self.edges[i].iter())
+    }
+
+    /// Collects the component rooted at a node once every successor has been visited
+    fn leave(&mut self, i: usize) {
+        if Some(self.lowlink[i]) == self.index[i] {
+            let mut component = vec![];
+
+            loop {
+                let j = self.stack.pop().expect("Component root missing from stack");
+                self.on_stack[j] = false;
+                component.push(j);
+
+                if j == i {
+                    break;
+                }
+            }
+
+            component.reverse();
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle_after_sorted_nodes() {
+        let mut graph = Graph::new();
+        graph.add_edge("start", "x", ());
+        graph.add_edge("x", "y", ());
+        graph.add_edge("y", "z", ());
+        graph.add_edge("z", "x", ());
+        graph.add_edge("z", "end", ());
+        graph.add_node("alone");
+
+        let error = graph.topological_sort().unwrap_err();
+        assert_eq!(error.sorted, vec!["start", "alone"]);
+        assert_eq!(error.cycle, vec!["x", "y", "z"]);
+        assert_eq!(error.to_string(), "cycle x -> y -> z -> x");
+    }
+
+    #[test]
+    fn test_self_loop() {
+        let error = topological_sort(&[1], |_| vec![1]).unwrap_err();
+        assert_eq!(error.cycle, vec![1]);
+        assert_eq!(
+            strongly_connected_components(&[1], |_| vec![1]),
+            vec![vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut graph = Graph::new();
+        graph.add_edge(1, 2, 'a');
+        graph.add_edge(1, 3, 'b');
+
+        let reversed = graph.reverse();
+        assert_eq!(reversed.bfs(&3), vec![3, 1]);
+        assert_eq!(
+            reversed.successors(&2).collect::<Vec<_>>(),
+            vec![(&1, &'a')]
+        );
+        assert_eq!(graph.edges().count(), 2);
+        assert_eq!(graph.dfs(&4), vec![4]);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let odd = |n: &u8| if *n < 9 { vec![n + 2] } else { vec![] };
+        assert_eq!(bfs_path(1, odd, |n| *n == 4), None);
+
+        let weighted = |n: &u8| odd(n).into_iter().map(|n| (n, 1)).collect::<Vec<_>>();
+        assert_eq!(dijkstra(1, weighted, |n| *n == 4), None::<(Vec<u8>, u32)>);
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let next = |n: &u32| if *n < 200_000 { vec![n + 1] } else { vec![0] };
+
+        let components = strongly_connected_components(&[0], next);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), 200_001);
+
+        let chain = |n: &u32| if *n < 200_000 { vec![n + 1] } else { vec![] };
+        assert_eq!(strongly_connected_components(&[0], chain).len(), 200_001);
+    }
+}
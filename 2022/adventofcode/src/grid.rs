@@ -0,0 +1,521 @@
+use std::collections::hash_map::{self, HashMap};
+use std::ops::{Index, IndexMut};
+
+use crate::error::{parse_lines, ParseError};
+use crate::point::{Bounds, Point2};
+
+/// Offsets of the four cells sharing an edge with a cell, clockwise from above
+const ORTHOGONAL: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the eight cells sharing an edge or a corner with a cell, clockwise from above
+const SURROUNDING: [(i64, i64); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A fixed size two dimensional grid stored row by row, `(x, y)` positions start at `(0, 0)` in the top left
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::grid::Grid;
+/// let mut grid = Grid::new(3, 2, '.');
+/// grid[(2, 1)] = '#';
+///
+/// assert_eq!(grid.get(2, 1), Some(&'#'));
+/// assert_eq!(grid.get(3, 1), None);
+/// assert_eq!(grid.render(|c| *c), "...\n..#\n");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid with every cell set to `value`
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// A grid with each cell set to the result of calling `f` with its position
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a character map, one row per line, converting each character with `f`. Every row must be as wide as
+    /// the first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::error::ParseError;
+    /// # use adventofcode::grid::Grid;
+    /// let tree = |c| match c {
+    ///     '.' => Ok(false),
+    ///     '#' => Ok(true),
+    ///     c => Err(ParseError::invalid(c, "'.' or '#'")),
+    /// };
+    ///
+    /// let grid = Grid::parse("..#\n#..", tree).unwrap();
+    /// assert_eq!((grid.width(), grid.height()), (3, 2));
+    /// assert_eq!(grid.get(0, 1), Some(&true));
+    ///
+    /// let error = Grid::parse("..#\n#x.", tree).unwrap_err();
+    /// assert_eq!((error.line, error.column), (2, 2));
+    /// ```
+    pub fn parse(
+        input: &str,
+        f: impl Fn(char) -> Result<T, ParseError>,
+    ) -> Result<Grid<T>, ParseError> {
+        let width = input.lines().next().unwrap_or_default().chars().count();
+        if width == 0 {
+            return Err(ParseError::invalid(input, "a row of at least one cell"));
+        }
+
+        let rows = parse_lines(input, |line| {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(i, c)| f(c).map_err(|e| e.offset(i)))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+
+            if row.len() == width {
+                Ok(row)
+            } else {
+                let extra: String = line.chars().skip(width).collect();
+                Err(ParseError::new(
+                    row.len().min(width) + 1,
+                    extra,
+                    format!("a row {} wide", width),
+                ))
+            }
+        })?;
+
+        Ok(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of cells in the grid
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whether a possibly negative position lies within the grid
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index_of(x, y).map(|i| &mut self.cells[i])
+    }
+
+    /// Looks up a position on a grid which repeats endlessly in every direction
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::grid::Grid;
+    /// let grid = Grid::from_fn(3, 2, |x, y| x + y * 3);
+    /// assert_eq!(grid.get_wrapping(4, 0), &1);
+    /// assert_eq!(grid.get_wrapping(-1, -1), &5);
+    /// ```
+    pub fn get_wrapping(&self, x: i64, y: i64) -> &T {
+        &self[self.wrap(x, y)]
+    }
+
+    pub fn get_wrapping_mut(&mut self, x: i64, y: i64) -> &mut T {
+        let position = self.wrap(x, y);
+        &mut self[position]
+    }
+
+    /// Maps a position on the endlessly repeating grid back onto the grid itself
+    pub fn wrap(&self, x: i64, y: i64) -> (usize, usize) {
+        assert!(!self.is_empty(), "Cannot wrap around an empty grid");
+
+        (
+            x.rem_euclid(self.width as i64) as usize,
+            y.rem_euclid(self.height as i64) as usize,
+        )
+    }
+
+    /// Cells row by row
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Cells row by row along with their positions
+    pub fn positions(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Clamps the rectangle with inclusive corners `tl` and `br` to the grid, returning the columns and rows it
+    /// covers
+    fn clamp(&self, tl: (usize, usize), br: (usize, usize)) -> (usize, usize, usize, usize) {
+        let right = br.0.saturating_add(1).min(self.width);
+        let bottom = br.1.saturating_add(1).min(self.height);
+
+        (tl.0.min(right), right, tl.1.min(bottom), bottom)
+    }
+
+    /// Cells within the rectangle with inclusive corners `tl` and `br`, row by row. Any part of the rectangle
+    /// outside the grid is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::grid::Grid;
+    /// let grid = Grid::from_fn(4, 4, |x, y| x + y * 4);
+    /// let view: Vec<usize> = grid.rect((1, 1), (2, 9)).copied().collect();
+    /// assert_eq!(view, vec![5, 6, 9, 10, 13, 14]);
+    /// ```
+    pub fn rect(&self, tl: (usize, usize), br: (usize, usize)) -> impl Iterator<Item = &T> {
+        let (left, right, top, bottom) = self.clamp(tl, br);
+
+        self.cells
+            .chunks(self.width.max(1))
+            .skip(top)
+            .take(bottom - top)
+            .flat_map(move |row| row[left..right].iter())
+    }
+
+    /// Mutable cells within the rectangle with inclusive corners `tl` and `br`, see [`Grid::rect`]
+    pub fn rect_mut(
+        &mut self,
+        tl: (usize, usize),
+        br: (usize, usize),
+    ) -> impl Iterator<Item = &mut T> {
+        let (left, right, top, bottom) = self.clamp(tl, br);
+
+        self.cells
+            .chunks_mut(self.width.max(1))
+            .skip(top)
+            .take(bottom - top)
+            .flat_map(move |row| row[left..right].iter_mut())
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(i64, i64)],
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets
+            .iter()
+            .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+            .filter(|(x, y)| self.contains(*x, *y))
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    /// Positions of the cells above, right of, below and left of a cell which lie within the grid
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::grid::Grid;
+    /// let grid = Grid::new(3, 3, 0);
+    /// assert_eq!(grid.neighbors(0, 0).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+    /// assert_eq!(grid.all_neighbors(1, 1).count(), 8);
+    /// ```
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &ORTHOGONAL)
+    }
+
+    /// Positions of the up to eight cells sharing an edge or a corner with a cell
+    pub fn all_neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offsets(x, y, &SURROUNDING)
+    }
+
+    /// Positions of the four cells sharing an edge with a cell on the endlessly repeating grid
+    pub fn neighbors_wrapping(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ORTHOGONAL
+            .iter()
+            .map(move |(dx, dy)| self.wrap(x as i64 + dx, y as i64 + dy))
+    }
+
+    /// A grid of the same size with every cell converted by `f`
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Renders the grid as text, one line per row, converting each cell to a character with `f`
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+
+        for row in self.rows() {
+            text.extend(row.iter().map(&f));
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y)
+            .unwrap_or_else(|| panic!("Position ({}, {}) is outside the grid", x, y))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("Position ({}, {}) is outside the grid", x, y))
+    }
+}
+
+/// An unbounded grid holding values only at the positions which have been set
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::grid::SparseGrid;
+/// # use adventofcode::point::Point2;
+/// let mut grid = SparseGrid::new();
+/// grid.insert(Point2::new(-1, 0), '#');
+/// grid.insert(Point2::new(1, 1), '#');
+///
+/// assert_eq!(grid.get(Point2::new(-1, 0)), Some(&'#'));
+/// assert_eq!(grid.len(), 2);
+/// assert_eq!(grid.render(|c| *c.unwrap_or(&'.')), "#..\n..#\n");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    /// Number of positions holding a value
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, point: Point2) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    pub fn get(&self, point: Point2) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    pub fn get_mut(&mut self, point: Point2) -> Option<&mut T> {
+        self.cells.get_mut(&point)
+    }
+
+    /// Sets the value at a position, returning the value it replaced
+    pub fn insert(&mut self, point: Point2, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn remove(&mut self, point: Point2) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    pub fn entry(&mut self, point: Point2) -> hash_map::Entry<'_, Point2, T> {
+        self.cells.entry(point)
+    }
+
+    /// Positions holding a value along with their values, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.cells.iter().map(|(point, value)| (*point, value))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point2> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// The smallest rectangle covering every position holding a value
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::covering(self.points())
+    }
+
+    /// Values at the positions sharing an edge with `point`, clockwise from north
+    pub fn neighbors(&self, point: Point2) -> impl Iterator<Item = (Point2, &T)> {
+        point
+            .neighbors()
+            .filter_map(move |p| self.get(p).map(|value| (p, value)))
+    }
+
+    /// Renders the area within [`SparseGrid::bounds`] as text, one line per row, converting each position to a
+    /// character with `f`
+    pub fn render(&self, f: impl Fn(Option<&T>) -> char) -> String {
+        let mut text = String::new();
+
+        if let Some(bounds) = self.bounds() {
+            for point in bounds.points() {
+                text.push(f(self.get(point)));
+
+                if point.x == bounds.max.x {
+                    text.push('\n');
+                }
+            }
+        }
+
+        text
+    }
+}
+
+impl<T> FromIterator<(Point2, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2, T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rect_mut() {
+        let mut grid = Grid::new(3, 3, 0);
+        grid.rect_mut((1, 0), (5, 1)).for_each(|cell| *cell += 1);
+
+        assert_eq!(grid.render(|c| char::from(b'0' + *c)), "011\n011\n000\n");
+        assert_eq!(grid.rect((3, 0), (5, 5)).count(), 0);
+    }
+
+    #[test]
+    fn test_neighbors_at_edges() {
+        let grid = Grid::new(2, 2, ());
+
+        assert_eq!(
+            grid.neighbors(1, 1).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(
+            grid.all_neighbors(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (1, 1), (0, 1)]
+        );
+        assert_eq!(
+            grid.neighbors_wrapping(0, 0).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0), (0, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_parse_ragged() {
+        let error = Grid::parse("ab\nabc", Ok).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "c"));
+
+        let error = Grid::parse("", Ok).unwrap_err();
+        assert_eq!(error.line, 1);
+    }
+
+    #[test]
+    fn test_sparse_neighbors() {
+        let grid: SparseGrid<u8> = [(Point2::new(0, -1), 1), (Point2::new(1, 1), 2)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            grid.neighbors(Point2::ORIGIN).collect::<Vec<_>>(),
+            vec![(Point2::new(0, -1), &1)]
+        );
+        assert_eq!(
+            grid.bounds(),
+            Bounds::covering([Point2::new(0, -1), Point2::new(1, 1)])
+        );
+        assert_eq!(SparseGrid::<u8>::new().render(|_| '#'), "");
+    }
+
+    #[test]
+    fn test_positions() {
+        let grid = Grid::from_fn(2, 2, |x, y| x * 10 + y);
+
+        assert_eq!(
+            grid.positions().collect::<Vec<_>>(),
+            vec![((0, 0), &0), ((1, 0), &10), ((0, 1), &1), ((1, 1), &11)]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Environment variable holding the session cookie of a logged in Advent of Code user
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Environment variable overriding the site puzzle inputs are fetched from
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// Environment variable overriding where fetched inputs are cached
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+pub(crate) const USER_AGENT: &str = concat!(
+    "github.com/bradfordcp/adventofcode ",
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION")
+);
+
+#[derive(Debug)]
+pub enum InputError {
+    /// No session token was available to authenticate with
+    MissingSession,
+    /// The server answered with something other than a success
+    Status(u16, String),
+    /// The server could not be reached
+    Transport(String),
+    /// Reading or writing the cache failed
+    Io(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::MissingSession => write!(
+                f,
+                "No session token found, set {} to the value of your session cookie",
+                SESSION_VAR
+            ),
+            InputError::Status(status, body) => {
+                write!(f, "Server responded with {}: {}", status, body.trim())
+            }
+            InputError::Transport(message) => write!(f, "Could not reach server: {}", message),
+            InputError::Io(e) => write!(f, "Could not access input cache: {}", e),
+        }
+    }
+}
+
+impl From<io::Error> for InputError {
+    fn from(e: io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+
+/// Provides puzzle inputs, reading them from a local cache and fetching any that are missing
+#[derive(Clone, Debug)]
+pub struct InputProvider {
+    base_url: String,
+    session: Option<String>,
+    cache_dir: PathBuf,
+}
+
+impl InputProvider {
+    pub fn new(
+        base_url: impl Into<String>,
+        session: Option<String>,
+        cache_dir: impl Into<PathBuf>,
+    ) -> InputProvider {
+        InputProvider {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session,
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    /// Configures a provider from the `AOC_SESSION`, `AOC_BASE_URL` and `AOC_CACHE_DIR` environment variables. The cache
+    /// defaults to `$XDG_CACHE_HOME/adventofcode`, falling back to `$HOME/.cache/adventofcode`.
+    pub fn from_env() -> InputProvider {
+        let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let session = env::var(SESSION_VAR).ok().filter(|s| !s.trim().is_empty());
+        let cache_dir = env::var_os(CACHE_DIR_VAR)
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("XDG_CACHE_HOME").map(|dir| PathBuf::from(dir).join("adventofcode"))
+            })
+            .or_else(|| {
+                env::var_os("HOME")
+                    .map(|dir| PathBuf::from(dir).join(".cache").join("adventofcode"))
+            })
+            .unwrap_or_else(|| PathBuf::from(".adventofcode"));
+
+        InputProvider::new(base_url, session, cache_dir)
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    pub fn cache_dir(&self) -> &PathBuf {
+        &self.cache_dir
+    }
+
+    /// Location of the cached input for a given year and day
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::input::InputProvider;
+    /// let provider = InputProvider::new("http://localhost", None, "/tmp/aoc");
+    /// assert_eq!(provider.cache_path(2015, 7).to_str(), Some("/tmp/aoc/2015/day07.txt"));
+    /// ```
+    pub fn cache_path(&self, year: u16, day: u8) -> PathBuf {
+        self.cache_dir
+            .join(year.to_string())
+            .join(format!("day{:02}.txt", day))
+    }
+
+    /// Returns the input for a given year and day, fetching and caching it when it has not been seen before
+    pub fn get(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let path = self.cache_path(year, day);
+
+        match fs::read_to_string(&path) {
+            Ok(input) => Ok(input),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => self.refresh(year, day),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Downloads the input for a given year and day, replacing any cached copy
+    pub fn refresh(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let path = self.cache_path(year, day);
+        let input = self.fetch(year, day)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &input)?;
+
+        Ok(input)
+    }
+
+    /// Downloads the input for a given year and day, bypassing the cache
+    pub fn fetch(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let session = self.session.as_ref().ok_or(InputError::MissingSession)?;
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", USER_AGENT)
+            .call();
+
+        read_response(response)
+    }
+}
+
+/// Turns a response into its body text, mapping failures onto an InputError
+pub(crate) fn read_response(
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<String, InputError> {
+    match response {
+        Ok(response) => response.into_string().map_err(InputError::Io),
+        Err(ureq::Error::Status(status, response)) => Err(InputError::Status(
+            status,
+            response.into_string().unwrap_or_default(),
+        )),
+        Err(e) => Err(InputError::Transport(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "adventofcode-input-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetch() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/2015/day/7/input" => (200, "123 -> x\n".to_string()),
+            _ => (404, "Not Found".to_string()),
+        });
+        let provider = InputProvider::new(&server.url, Some("abc".to_string()), cache_dir("fetch"));
+
+        assert_eq!(provider.fetch(2015, 7).unwrap(), "123 -> x\n");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].header("Cookie"), Some("session=abc"));
+        assert!(requests[0].header("User-Agent").is_some());
+    }
+
+    #[test]
+    fn test_fetch_error_status() {
+        let server = MockServer::start(|_| (400, "Please log in".to_string()));
+        let provider =
+            InputProvider::new(&server.url, Some("abc".to_string()), cache_dir("status"));
+
+        match provider.fetch(2015, 7) {
+            Err(InputError::Status(400, body)) => assert_eq!(body, "Please log in"),
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_fetch_without_session() {
+        let provider = InputProvider::new("http://127.0.0.1:1", None, cache_dir("session"));
+
+        assert!(matches!(
+            provider.fetch(2015, 7),
+            Err(InputError::MissingSession)
+        ));
+    }
+
+    #[test]
+    fn test_get_caches() {
+        let server = MockServer::start(|_| (200, "(()\n".to_string()));
+        let dir = cache_dir("cache");
+        let provider = InputProvider::new(&server.url, Some("abc".to_string()), &dir);
+
+        assert_eq!(provider.get(2015, 1).unwrap(), "(()\n");
+        assert_eq!(provider.get(2015, 1).unwrap(), "(()\n");
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(
+            fs::read_to_string(dir.join("2015").join("day01.txt")).unwrap(),
+            "(()\n"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_prefers_cache() {
+        let dir = cache_dir("prefer");
+        let provider = InputProvider::new("http://127.0.0.1:1", None, &dir);
+        fs::create_dir_all(dir.join("2022")).unwrap();
+        fs::write(provider.cache_path(2022, 1), "1000\n").unwrap();
+
+        assert_eq!(provider.get(2022, 1).unwrap(), "1000\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,21 +1,45 @@
 use clap::Parser;
 use std::fs;
 
+use crate::error::{Error, ParseError};
+
+pub mod answers;
+pub mod bench;
+pub mod error;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod point;
+pub mod registry;
+pub mod runner;
+pub mod solution;
+pub mod submit;
+
+#[cfg(test)]
+mod mock;
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
     /// Puzzle input file
-    #[arg(short, long)]
+    #[arg(short, long, default_value = "input.txt")]
     pub input: String,
+
+    /// Time each stage over this many runs instead of printing the answers
+    #[arg(short, long)]
+    pub bench: Option<usize>,
 }
 
 pub fn parse_args() -> Args {
     Args::parse()
 }
 
-pub fn read_input<T>(path: &String, f: fn(std::str::Lines) -> Vec<T>) -> Vec<T> {
-    let input = fs::read_to_string(path).expect(format!("Could not read file {}", path).as_str());
+pub fn read_input<T>(
+    path: &String,
+    f: fn(std::str::Lines) -> Result<Vec<T>, ParseError>,
+) -> Result<Vec<T>, Error> {
+    let input = fs::read_to_string(path).map_err(|e| Error::Io(path.clone(), e))?;
     let input = input.trim().lines();
 
-    f(input)
+    f(input).map_err(|e| Error::Parse(path.clone(), e))
 }
@@ -0,0 +1,433 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or offset on an unbounded plane. As with [`Grid`](crate::grid::Grid) `y` grows downwards, so north
+/// is `(0, -1)`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::point::{Direction, Point2};
+/// let start = Point2::new(1, 2);
+/// let end = start + Direction::North.offset() * 3 + Point2::new(2, 0);
+///
+/// assert_eq!(end, Point2::new(3, -1));
+/// assert_eq!(start.manhattan(end), 5);
+/// assert_eq!(start.chebyshev(end), 3);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point2 {
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Point2 {
+        Point2 { x, y }
+    }
+
+    /// Distance travelling only along the axes
+    pub fn manhattan(self, other: Point2) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Distance when diagonal moves count as a single step
+    pub fn chebyshev(self, other: Point2) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// The point one step away in `direction`
+    pub fn step(self, direction: Direction) -> Point2 {
+        self + direction.offset()
+    }
+
+    /// The four points sharing an edge with this one, clockwise from north
+    pub fn neighbors(self) -> impl Iterator<Item = Point2> {
+        Direction::CARDINAL.into_iter().map(move |d| self.step(d))
+    }
+
+    /// The eight points sharing an edge or a corner with this one, clockwise from north
+    pub fn all_neighbors(self) -> impl Iterator<Item = Point2> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+}
+
+impl From<(i64, i64)> for Point2 {
+    fn from((x, y): (i64, i64)) -> Point2 {
+        Point2 { x, y }
+    }
+}
+
+impl fmt::Display for Point2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add for Point2 {
+    type Output = Point2;
+
+    fn add(self, other: Point2) -> Point2 {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Add<Direction> for Point2 {
+    type Output = Point2;
+
+    fn add(self, direction: Direction) -> Point2 {
+        self.step(direction)
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Point2;
+
+    fn sub(self, other: Point2) -> Point2 {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i64> for Point2 {
+    type Output = Point2;
+
+    fn mul(self, scale: i64) -> Point2 {
+        Point2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Neg for Point2 {
+    type Output = Point2;
+
+    fn neg(self) -> Point2 {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point2 {
+    fn add_assign(&mut self, other: Point2) {
+        *self = *self + other;
+    }
+}
+
+impl AddAssign<Direction> for Point2 {
+    fn add_assign(&mut self, direction: Direction) {
+        *self = self.step(direction);
+    }
+}
+
+impl SubAssign for Point2 {
+    fn sub_assign(&mut self, other: Point2) {
+        *self = *self - other;
+    }
+}
+
+/// A position or offset in unbounded space
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::point::Point3;
+/// let a = Point3::new(1, -2, 3);
+/// assert_eq!(a * 2 - a, a);
+/// assert_eq!(a.manhattan(Point3::ORIGIN), 6);
+/// assert_eq!(a.neighbors().count(), 6);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    /// Distance travelling only along the axes
+    pub fn manhattan(self, other: Point3) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// Distance when diagonal moves count as a single step
+    pub fn chebyshev(self, other: Point3) -> u64 {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    /// The six points sharing a face with this one
+    pub fn neighbors(self) -> impl Iterator<Item = Point3> {
+        [
+            Point3::new(1, 0, 0),
+            Point3::new(-1, 0, 0),
+            Point3::new(0, 1, 0),
+            Point3::new(0, -1, 0),
+            Point3::new(0, 0, 1),
+            Point3::new(0, 0, -1),
+        ]
+        .into_iter()
+        .map(move |offset| self + offset)
+    }
+}
+
+impl From<(i64, i64, i64)> for Point3 {
+    fn from((x, y, z): (i64, i64, i64)) -> Point3 {
+        Point3 { x, y, z }
+    }
+}
+
+impl fmt::Display for Point3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl Add for Point3 {
+    type Output = Point3;
+
+    fn add(self, other: Point3) -> Point3 {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point3 {
+    type Output = Point3;
+
+    fn sub(self, other: Point3) -> Point3 {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<i64> for Point3 {
+    type Output = Point3;
+
+    fn mul(self, scale: i64) -> Point3 {
+        Point3::new(self.x * scale, self.y * scale, self.z * scale)
+    }
+}
+
+impl Neg for Point3 {
+    type Output = Point3;
+
+    fn neg(self) -> Point3 {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl AddAssign for Point3 {
+    fn add_assign(&mut self, other: Point3) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point3 {
+    fn sub_assign(&mut self, other: Point3) {
+        *self = *self - other;
+    }
+}
+
+/// One of the eight compass directions
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The four directions along the axes, clockwise from north
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    /// The direction drawn by an arrow, one of `^`, `>`, `v` and `<`
+    pub fn from_arrow(arrow: char) -> Option<Direction> {
+        match arrow {
+            '^' => Some(Direction::North),
+            '>' => Some(Direction::East),
+            'v' => Some(Direction::South),
+            '<' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    /// The step taken when moving one position in this direction
+    pub fn offset(self) -> Point2 {
+        match self {
+            Direction::North => Point2::new(0, -1),
+            Direction::NorthEast => Point2::new(1, -1),
+            Direction::East => Point2::new(1, 0),
+            Direction::SouthEast => Point2::new(1, 1),
+            Direction::South => Point2::new(0, 1),
+            Direction::SouthWest => Point2::new(-1, 1),
+            Direction::West => Point2::new(-1, 0),
+            Direction::NorthWest => Point2::new(-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        !Direction::CARDINAL.contains(&self)
+    }
+
+    /// Turns clockwise by `eighths` eighths of a full turn, counter-clockwise when negative
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::point::Direction;
+    /// assert_eq!(Direction::North.rotate(1), Direction::NorthEast);
+    /// assert_eq!(Direction::North.rotate(-3), Direction::SouthWest);
+    /// assert_eq!(Direction::West.turn_right(), Direction::North);
+    /// assert_eq!(Direction::East.reverse(), Direction::West);
+    /// ```
+    pub fn rotate(self, eighths: i64) -> Direction {
+        Direction::ALL[(self as i64 + eighths).rem_euclid(8) as usize]
+    }
+
+    /// A quarter turn clockwise
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// A quarter turn counter-clockwise
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    pub fn reverse(self) -> Direction {
+        self.rotate(4)
+    }
+}
+
+/// The smallest rectangle, corners included, covering a set of points
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::point::{Bounds, Point2};
+/// let bounds = Bounds::covering([Point2::new(2, -1), Point2::new(-3, 4)]).unwrap();
+///
+/// assert_eq!((bounds.min, bounds.max), (Point2::new(-3, -1), Point2::new(2, 4)));
+/// assert_eq!((bounds.width(), bounds.height()), (6, 6));
+/// assert!(bounds.contains(Point2::ORIGIN));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point2,
+    pub max: Point2,
+}
+
+impl Bounds {
+    /// Bounds of a single point
+    pub fn at(point: Point2) -> Bounds {
+        Bounds {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// Bounds of all the given points, if there are any
+    pub fn covering(points: impl IntoIterator<Item = Point2>) -> Option<Bounds> {
+        let mut points = points.into_iter();
+        let first = Bounds::at(points.next()?);
+
+        Some(points.fold(first, Bounds::include))
+    }
+
+    /// Grows the bounds to cover `point`
+    pub fn include(self, point: Point2) -> Bounds {
+        Bounds {
+            min: Point2::new(self.min.x.min(point.x), self.min.y.min(point.y)),
+            max: Point2::new(self.max.x.max(point.x), self.max.y.max(point.y)),
+        }
+    }
+
+    pub fn contains(&self, point: Point2) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    /// Every point within the bounds, row by row
+    pub fn points(self) -> impl Iterator<Item = Point2> {
+        (self.min.y..=self.max.y)
+            .flat_map(move |y| (self.min.x..=self.max.x).map(move |x| Point2::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_turning() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.rotate(8), direction);
+            assert_eq!(direction.reverse().offset(), -direction.offset());
+        }
+
+        assert!(Direction::SouthEast.is_diagonal());
+        assert!(!Direction::South.is_diagonal());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let neighbors: Vec<Point2> = Point2::ORIGIN.neighbors().collect();
+        assert_eq!(
+            neighbors,
+            vec![
+                Point2::new(0, -1),
+                Point2::new(1, 0),
+                Point2::new(0, 1),
+                Point2::new(-1, 0)
+            ]
+        );
+
+        assert!(Point2::ORIGIN
+            .all_neighbors()
+            .all(|p| p.chebyshev(Point2::ORIGIN) == 1));
+    }
+
+    #[test]
+    fn test_bounds_points() {
+        let bounds = Bounds::at(Point2::new(1, 1)).include(Point2::new(2, 0));
+        let points: Vec<(i64, i64)> = bounds.points().map(|p| (p.x, p.y)).collect();
+
+        assert_eq!(points, vec![(1, 0), (2, 0), (1, 1), (2, 1)]);
+        assert_eq!(Bounds::covering([]), None);
+    }
+}
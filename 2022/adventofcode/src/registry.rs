@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::bench::{Record, Stage, Stats};
+use crate::error::ParseError;
+
+/// Identifies one of the two halves of a day's puzzle
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    /// Both parts, in the order they are solved
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl TryFrom<u8> for Part {
+    type Error = String;
+
+    /// Converts a part number as given on the command line into a Part
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::registry::Part;
+    /// assert_eq!(Part::try_from(1), Ok(Part::One));
+    /// assert_eq!(Part::try_from(2), Ok(Part::Two));
+    /// assert!(Part::try_from(3).is_err());
+    /// ```
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            o => Err(format!("Unknown puzzle part {}", o)),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A puzzle solver which may be registered with, and run through, the `aoc` runner
+pub trait Solver {
+    /// Year of the event the puzzle belongs to
+    fn year(&self) -> u16;
+
+    /// Day of the event, 1 through 25
+    fn day(&self) -> u8;
+
+    /// Puzzle title as shown on the event calendar
+    fn title(&self) -> &str;
+
+    /// Solves a single part of the puzzle given the raw puzzle input
+    fn solve(&self, part: Part, input: &str) -> Result<String, ParseError>;
+
+    /// Times each stage of solving the puzzle over `runs` runs. Solvers which cannot parse separately from solving
+    /// report only the two parts, each including the time spent parsing.
+    fn bench(&self, input: &str, runs: usize) -> Result<Vec<Record>, ParseError> {
+        [(Stage::Part1, Part::One), (Stage::Part2, Part::Two)]
+            .into_iter()
+            .map(|(stage, part)| {
+                self.solve(part, input)?;
+
+                Ok(Record {
+                    year: self.year(),
+                    day: self.day(),
+                    stage,
+                    stats: Stats::sample(runs, || self.solve(part, input)),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Collection of solvers keyed by year and day
+#[derive(Default)]
+pub struct Registry {
+    solvers: BTreeMap<(u16, u8), Box<dyn Solver>>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// Adds a solver to the registry. Registering two solvers for the same year and day is a programming error and
+    /// will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::error::ParseError;
+    /// # use adventofcode::registry::*;
+    /// struct Echo;
+    ///
+    /// impl Solver for Echo {
+    ///     fn year(&self) -> u16 { 2015 }
+    ///     fn day(&self) -> u8 { 1 }
+    ///     fn title(&self) -> &str { "Echo" }
+    ///     fn solve(&self, _part: Part, input: &str) -> Result<String, ParseError> {
+    ///         Ok(input.to_string())
+    ///     }
+    /// }
+    ///
+    /// let mut registry = Registry::new();
+    /// registry.register(Echo);
+    ///
+    /// let solver = registry.get(2015, 1).unwrap();
+    /// assert_eq!(solver.solve(Part::One, "hello"), Ok("hello".to_string()));
+    /// ```
+    pub fn register<S: Solver + 'static>(&mut self, solver: S) -> &mut Registry {
+        let key = (solver.year(), solver.day());
+
+        if self.solvers.contains_key(&key) {
+            panic!("A solver is already registered for {} day {}", key.0, key.1);
+        }
+
+        self.solvers.insert(key, Box::new(solver));
+        self
+    }
+
+    /// Looks up the solver for a given year and day
+    pub fn get(&self, year: u16, day: u8) -> Option<&dyn Solver> {
+        self.solvers.get(&(year, day)).map(|solver| solver.as_ref())
+    }
+
+    /// Iterates over every registered solver ordered by year then day
+    pub fn solvers(&self) -> impl Iterator<Item = &dyn Solver> {
+        self.solvers.values().map(|solver| solver.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.solvers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solvers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Fixed(u16, u8);
+
+    impl Solver for Fixed {
+        fn year(&self) -> u16 {
+            self.0
+        }
+
+        fn day(&self) -> u8 {
+            self.1
+        }
+
+        fn title(&self) -> &str {
+            "Fixed"
+        }
+
+        fn solve(&self, part: Part, _input: &str) -> Result<String, ParseError> {
+            Ok(format!("{}-{}-{}", self.0, self.1, part))
+        }
+    }
+
+    #[test]
+    fn test_get() {
+        let mut registry = Registry::new();
+        registry.register(Fixed(2015, 7)).register(Fixed(2022, 1));
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(
+            registry.get(2015, 7).unwrap().solve(Part::Two, ""),
+            Ok("2015-7-2".to_string())
+        );
+        assert!(registry.get(2015, 8).is_none());
+    }
+
+    #[test]
+    fn test_solvers_ordered() {
+        let mut registry = Registry::new();
+        registry
+            .register(Fixed(2022, 1))
+            .register(Fixed(2015, 7))
+            .register(Fixed(2015, 1));
+
+        let order: Vec<(u16, u8)> = registry.solvers().map(|s| (s.year(), s.day())).collect();
+        assert_eq!(order, vec![(2015, 1), (2015, 7), (2022, 1)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_register_duplicate() {
+        let mut registry = Registry::new();
+        registry.register(Fixed(2015, 7)).register(Fixed(2015, 7));
+    }
+}
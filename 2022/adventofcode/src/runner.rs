@@ -0,0 +1,646 @@
+use clap::{Parser, Subcommand};
+use std::fs;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::answers::{AnswerBook, Status};
+use crate::bench;
+use crate::error::ParseError;
+use crate::input::InputProvider;
+use crate::registry::{Part, Registry, Solver};
+use crate::submit::Submitter;
+
+/// Runs any registered Advent of Code solver
+#[derive(Parser, Debug)]
+#[command(name = "aoc", author, version, about, long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Solves a single puzzle
+    Run {
+        /// Event year
+        #[arg(short, long)]
+        year: u16,
+
+        /// Day of the event
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        /// Puzzle part, both parts are solved when omitted
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Puzzle input file, the cached input is used when omitted and fetched if not yet cached
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+
+    /// Downloads a puzzle input into the local cache
+    Fetch {
+        /// Event year
+        #[arg(short, long)]
+        year: u16,
+
+        /// Day of the event
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        /// Download the input again even if it is already cached
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Submits an answer, solving the puzzle for it when no answer is given
+    Submit {
+        /// Event year
+        #[arg(short, long)]
+        year: u16,
+
+        /// Day of the event
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        /// Puzzle part
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+
+        /// Answer to submit, the registered solver is run against the input when omitted
+        #[arg(short, long)]
+        answer: Option<String>,
+
+        /// Puzzle input file, the cached input is used when omitted and fetched if not yet cached
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+
+    /// Checks every registered solver against the recorded answers using the cached inputs
+    Verify {
+        /// Only verify puzzles from this year
+        #[arg(short, long)]
+        year: Option<u16>,
+
+        /// Directory of recorded answers, defaults to the answers directory of the repository
+        #[arg(short, long)]
+        answers: Option<String>,
+
+        /// Record answers for parts which do not have one yet
+        #[arg(short, long)]
+        record: bool,
+    },
+
+    /// Times every stage of the registered solvers against their cached inputs
+    Bench {
+        /// Only benchmark puzzles from this year
+        #[arg(short, long)]
+        year: Option<u16>,
+
+        /// Only benchmark this day
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+
+        /// Number of times each stage is run
+        #[arg(short, long, default_value_t = 10)]
+        runs: usize,
+
+        /// Write the results as CSV to this file, for comparison with another revision
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Compares the median timings of two benchmark result files
+    Compare {
+        /// Results of the earlier revision
+        baseline: String,
+
+        /// Results of the later revision
+        current: String,
+    },
+
+    /// Lists every registered puzzle
+    List,
+}
+
+/// Parses the command line and executes it against the registry, exiting with a non-zero status on failure
+pub fn main(registry: &Registry) {
+    let cli = Cli::parse();
+
+    let provider = InputProvider::from_env();
+
+    if let Err(message) = execute(&cli, registry, &provider, &mut std::io::stdout()) {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    }
+}
+
+/// Executes a parsed command against the registry writing results to `out`
+pub fn execute(
+    cli: &Cli,
+    registry: &Registry,
+    provider: &InputProvider,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    match &cli.command {
+        Command::Run {
+            year,
+            day,
+            part,
+            input,
+        } => {
+            let solver = lookup(registry, *year, *day)?;
+            let input = read_input(provider, *year, *day, input)?;
+            let parts = match part {
+                Some(part) => vec![Part::try_from(*part)?],
+                None => Part::ALL.to_vec(),
+            };
+
+            for part in parts {
+                let answer = solver
+                    .solve(part, &input)
+                    .map_err(|e| diagnose(*year, *day, &e, &input))?;
+                writeln!(out, "{} day {} part {}: {}", year, day, part, answer)
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        Command::Fetch { year, day, force } => {
+            let path = provider.cache_path(*year, *day);
+
+            if *force || !path.exists() {
+                provider.refresh(*year, *day).map_err(|e| e.to_string())?;
+            }
+
+            writeln!(out, "{}", path.display()).map_err(|e| e.to_string())?;
+        }
+        Command::Submit {
+            year,
+            day,
+            part,
+            answer,
+            input,
+        } => {
+            let part = Part::try_from(*part)?;
+            let answer = match answer {
+                Some(answer) => answer.clone(),
+                None => {
+                    let solver = lookup(registry, *year, *day)?;
+                    let input = read_input(provider, *year, *day, input)?;
+                    solver
+                        .solve(part, &input)
+                        .map_err(|e| diagnose(*year, *day, &e, &input))?
+                }
+            };
+
+            let mut submitter = Submitter::new(provider).map_err(|e| e.to_string())?;
+            let verdict = submitter
+                .submit(*year, *day, part, &answer)
+                .map_err(|e| e.to_string())?;
+
+            writeln!(
+                out,
+                "{} day {} part {}: {} is {}",
+                year,
+                day,
+                part,
+                answer.trim(),
+                verdict
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        Command::Verify {
+            year,
+            answers,
+            record,
+        } => {
+            let book = match answers {
+                Some(dir) => AnswerBook::new(dir),
+                None => AnswerBook::from_env(),
+            };
+            let solvers = registry
+                .solvers()
+                .filter(|solver| year.is_none_or(|year| solver.year() == year));
+
+            let (mut passed, mut changed, mut failed, mut unrecorded) = (0, 0, 0, 0);
+            for solver in solvers {
+                let (year, day) = (solver.year(), solver.day());
+                let mut recorded = book.load(year, day)?;
+                let input = provider.get(year, day).map_err(|e| e.to_string());
+
+                for part in Part::ALL {
+                    let actual = input.clone().and_then(|input| solve(solver, part, &input));
+                    let status = Status::check(recorded.get(part), actual);
+
+                    match &status {
+                        Status::Pass => passed += 1,
+                        Status::Changed { .. } => changed += 1,
+                        Status::Fail(_) => failed += 1,
+                        Status::Unrecorded(actual) => {
+                            unrecorded += 1;
+                            if *record {
+                                recorded.set(part, actual.trim());
+                                book.save(year, day, &recorded)?;
+                            }
+                        }
+                    }
+
+                    writeln!(out, "{} day {:>2} part {}: {}", year, day, part, status)
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+
+            writeln!(
+                out,
+                "{} passed, {} changed, {} failed, {} unrecorded",
+                passed, changed, failed, unrecorded
+            )
+            .map_err(|e| e.to_string())?;
+
+            if changed + failed > 0 {
+                return Err(format!(
+                    "Verification failed for {} part(s)",
+                    changed + failed
+                ));
+            }
+        }
+        Command::Bench {
+            year,
+            day,
+            runs,
+            output,
+        } => {
+            let solvers = registry.solvers().filter(|solver| {
+                year.is_none_or(|year| solver.year() == year)
+                    && day.is_none_or(|day| solver.day() == day)
+            });
+
+            let mut records = Vec::new();
+            for solver in solvers {
+                let input = provider
+                    .get(solver.year(), solver.day())
+                    .map_err(|e| e.to_string())?;
+
+                let results = solver
+                    .bench(&input, *runs)
+                    .map_err(|e| diagnose(solver.year(), solver.day(), &e, &input))?;
+
+                for record in results {
+                    writeln!(
+                        out,
+                        "{} day {:>2} {}: {}",
+                        record.year, record.day, record.stage, record.stats
+                    )
+                    .map_err(|e| e.to_string())?;
+                    records.push(record);
+                }
+            }
+
+            if let Some(path) = output {
+                fs::write(path, bench::to_csv(&records))
+                    .map_err(|e| format!("Could not write file {}: {}", path, e))?;
+            }
+        }
+        Command::Compare { baseline, current } => {
+            let read = |path: &String| {
+                fs::read_to_string(path)
+                    .map_err(|e| format!("Could not read file {}: {}", path, e))
+                    .and_then(|csv| bench::from_csv(&csv))
+            };
+
+            for comparison in bench::compare(&read(baseline)?, &read(current)?) {
+                writeln!(out, "{}", comparison).map_err(|e| e.to_string())?;
+            }
+        }
+        Command::List => {
+            for solver in registry.solvers() {
+                writeln!(
+                    out,
+                    "{} day {:>2}: {}",
+                    solver.year(),
+                    solver.day(),
+                    solver.title()
+                )
+                .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn lookup(registry: &Registry, year: u16, day: u8) -> Result<&dyn Solver, String> {
+    registry
+        .get(year, day)
+        .ok_or_else(|| format!("No solver registered for {} day {}", year, day))
+}
+
+/// Solves a part, turning malformed input or a panicking solver into an error
+fn solve(solver: &dyn Solver, part: Part, input: &str) -> Result<String, String> {
+    match panic::catch_unwind(AssertUnwindSafe(|| solver.solve(part, input))) {
+        Ok(answer) => answer.map_err(|e| e.to_string()),
+        Err(e) => Err(e
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| e.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "solver panicked".to_string())),
+    }
+}
+
+/// Describes why the input for a puzzle could not be parsed, pointing out the offending text
+fn diagnose(year: u16, day: u8, error: &ParseError, input: &str) -> String {
+    format!(
+        "Could not parse input for {} day {}, {}",
+        year,
+        day,
+        error.diagnostic(input).trim_end()
+    )
+}
+
+/// Reads the input file if one was named, otherwise the cached input
+fn read_input(
+    provider: &InputProvider,
+    year: u16,
+    day: u8,
+    path: &Option<String>,
+) -> Result<String, String> {
+    match path {
+        Some(path) => {
+            fs::read_to_string(path).map_err(|e| format!("Could not read file {}: {}", path, e))
+        }
+        None => provider.get(year, day).map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+
+    struct Length;
+
+    impl Solver for Length {
+        fn year(&self) -> u16 {
+            2015
+        }
+
+        fn day(&self) -> u8 {
+            1
+        }
+
+        fn title(&self) -> &str {
+            "Length"
+        }
+
+        fn solve(&self, part: Part, input: &str) -> Result<String, ParseError> {
+            if let Some(column) = input.find('!') {
+                return Err(ParseError::new(column + 1, "!", "a letter"));
+            }
+
+            Ok(match part {
+                Part::One => input.trim().len().to_string(),
+                Part::Two => input.trim().chars().rev().collect(),
+            })
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(Length);
+        registry
+    }
+
+    fn execute_args(args: &[&str]) -> Result<String, String> {
+        let provider = InputProvider::new("http://127.0.0.1:1", None, std::env::temp_dir());
+        execute_with(args, &provider)
+    }
+
+    fn execute_with(args: &[&str], provider: &InputProvider) -> Result<String, String> {
+        let cli = Cli::try_parse_from(args).map_err(|e| e.to_string())?;
+        let mut out = Vec::new();
+        execute(&cli, &registry(), provider, &mut out)?;
+
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(
+            execute_args(&["aoc", "list"]),
+            Ok("2015 day  1: Length\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run() {
+        let path = std::env::temp_dir().join("adventofcode-runner-test-run");
+        fs::write(&path, "abc\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        assert_eq!(
+            execute_args(&["aoc", "run", "-y", "2015", "-d", "1", "-i", path]),
+            Ok("2015 day 1 part 1: 3\n2015 day 1 part 2: cba\n".to_string())
+        );
+        assert_eq!(
+            execute_args(&["aoc", "run", "-y", "2015", "-d", "1", "-p", "2", "-i", path]),
+            Ok("2015 day 1 part 2: cba\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_malformed() {
+        let path = std::env::temp_dir().join("adventofcode-runner-test-malformed");
+        fs::write(&path, "ab!c\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        assert_eq!(
+            execute_args(&["aoc", "run", "-y", "2015", "-d", "1", "-i", path]),
+            Err(concat!(
+                "Could not parse input for 2015 day 1, ",
+                "line 1, column 3: expected a letter, found \"!\"\n",
+                "  |\n",
+                "1 | ab!c\n",
+                "  |   ^"
+            )
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_unregistered() {
+        let result = execute_args(&["aoc", "run", "-y", "2016", "-d", "1", "-i", "missing"]);
+        assert_eq!(
+            result,
+            Err("No solver registered for 2016 day 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_fetches_input() {
+        let server = MockServer::start(|_| (200, "abcd\n".to_string()));
+        let dir =
+            std::env::temp_dir().join(format!("adventofcode-runner-fetch-{}", std::process::id()));
+        let provider = InputProvider::new(&server.url, Some("abc".to_string()), &dir);
+
+        assert_eq!(
+            execute_with(
+                &["aoc", "run", "-y", "2015", "-d", "1", "-p", "1"],
+                &provider
+            ),
+            Ok("2015 day 1 part 1: 4\n".to_string())
+        );
+        assert_eq!(
+            execute_with(
+                &["aoc", "run", "-y", "2015", "-d", "1", "-p", "2"],
+                &provider
+            ),
+            Ok("2015 day 1 part 2: dcba\n".to_string())
+        );
+        assert_eq!(server.requests().len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch() {
+        let server = MockServer::start(|_| (200, "abcd\n".to_string()));
+        let dir = std::env::temp_dir().join(format!(
+            "adventofcode-runner-refetch-{}",
+            std::process::id()
+        ));
+        let provider = InputProvider::new(&server.url, Some("abc".to_string()), &dir);
+        let path = provider.cache_path(2015, 1);
+
+        let expected = Ok(format!("{}\n", path.display()));
+        assert_eq!(
+            execute_with(&["aoc", "fetch", "-y", "2015", "-d", "1"], &provider),
+            expected
+        );
+        assert_eq!(
+            execute_with(&["aoc", "fetch", "-y", "2015", "-d", "1"], &provider),
+            expected
+        );
+        assert_eq!(server.requests().len(), 1);
+
+        assert_eq!(
+            execute_with(&["aoc", "fetch", "-y", "2015", "-d", "1", "-f"], &provider),
+            expected
+        );
+        assert_eq!(server.requests().len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = MockServer::start(|request| {
+            if request.body == "level=2&answer=dcba" {
+                (200, "<p>That's the right answer!</p>".to_string())
+            } else {
+                (200, "<p>That's not the right answer.</p>".to_string())
+            }
+        });
+        let dir =
+            std::env::temp_dir().join(format!("adventofcode-runner-submit-{}", std::process::id()));
+        let provider = InputProvider::new(&server.url, Some("abc".to_string()), &dir);
+        fs::create_dir_all(dir.join("2015")).unwrap();
+        fs::write(provider.cache_path(2015, 1), "abcd\n").unwrap();
+
+        assert_eq!(
+            execute_with(
+                &["aoc", "submit", "-y", "2015", "-d", "1", "-p", "2", "-a", "abcd"],
+                &provider
+            ),
+            Ok("2015 day 1 part 2: abcd is incorrect\n".to_string())
+        );
+        assert_eq!(
+            execute_with(
+                &["aoc", "submit", "-y", "2015", "-d", "1", "-p", "2", "-a", "abcd"],
+                &provider
+            ),
+            Err("Refusing to submit: abcd was already rejected as incorrect".to_string())
+        );
+        assert_eq!(
+            execute_with(
+                &["aoc", "submit", "-y", "2015", "-d", "1", "-p", "2"],
+                &provider
+            ),
+            Ok("2015 day 1 part 2: dcba is correct\n".to_string())
+        );
+        assert_eq!(server.requests().len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify() {
+        let dir =
+            std::env::temp_dir().join(format!("adventofcode-runner-verify-{}", std::process::id()));
+        let provider = InputProvider::new("http://127.0.0.1:1", None, dir.join("cache"));
+        fs::create_dir_all(dir.join("cache").join("2015")).unwrap();
+        fs::write(provider.cache_path(2015, 1), "abcd\n").unwrap();
+        let answers = dir.join("answers");
+        let answers = answers.to_str().unwrap();
+
+        assert_eq!(
+            execute_with(&["aoc", "verify", "-a", answers, "-r"], &provider),
+            Ok(concat!(
+                "2015 day  1 part 1: unrecorded (got 4)\n",
+                "2015 day  1 part 2: unrecorded (got dcba)\n",
+                "0 passed, 0 changed, 0 failed, 2 unrecorded\n"
+            )
+            .to_string())
+        );
+        assert_eq!(
+            execute_with(&["aoc", "verify", "-a", answers], &provider),
+            Ok(concat!(
+                "2015 day  1 part 1: pass\n",
+                "2015 day  1 part 2: pass\n",
+                "2 passed, 0 changed, 0 failed, 0 unrecorded\n"
+            )
+            .to_string())
+        );
+
+        fs::write(provider.cache_path(2015, 1), "abc\n").unwrap();
+        assert_eq!(
+            execute_with(&["aoc", "verify", "-a", answers], &provider),
+            Err("Verification failed for 2 part(s)".to_string())
+        );
+
+        assert_eq!(
+            execute_with(&["aoc", "verify", "-y", "2022", "-a", answers], &provider),
+            Ok("0 passed, 0 changed, 0 failed, 0 unrecorded\n".to_string())
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_bench_and_compare() {
+        let dir =
+            std::env::temp_dir().join(format!("adventofcode-runner-bench-{}", std::process::id()));
+        let provider = InputProvider::new("http://127.0.0.1:1", None, &dir);
+        fs::create_dir_all(dir.join("2015")).unwrap();
+        fs::write(provider.cache_path(2015, 1), "abcd\n").unwrap();
+        let results = dir.join("results.csv");
+        let results = results.to_str().unwrap();
+
+        let output = execute_with(
+            &["aoc", "bench", "-y", "2015", "-r", "3", "-o", results],
+            &provider,
+        )
+        .unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.starts_with("2015 day  1 part1: min "));
+
+        let records = bench::from_csv(&fs::read_to_string(results).unwrap()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|record| record.stats.runs == 3));
+
+        let output = execute_with(&["aoc", "compare", results, results], &provider).unwrap();
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.lines().all(|line| line.contains("->")));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use crate::bench::{self, Record};
+use crate::error::ParseError;
+use crate::registry::{Part, Solver};
+
+/// A day's puzzle split into its parsing and solving stages. Implementing this trait is all a crate needs to do to be
+/// run from its own binary through [`run`] or registered with the `aoc` runner.
+///
+/// # Examples
+///
+/// ```rust
+/// # use adventofcode::error::{parse_lines, ParseError};
+/// # use adventofcode::solution::Solution;
+/// struct Sum;
+///
+/// impl Solution for Sum {
+///     const YEAR: u16 = 2015;
+///     const DAY: u8 = 1;
+///     const TITLE: &'static str = "Sum";
+///
+///     type Input = Vec<u64>;
+///     type Answer = u64;
+///
+///     fn parse(input: &str) -> Result<Self::Input, ParseError> {
+///         parse_lines(input, |line| {
+///             line.parse().map_err(|_| ParseError::invalid(line, "a number"))
+///         })
+///     }
+///
+///     fn part1(input: &Self::Input) -> Self::Answer {
+///         input.iter().sum()
+///     }
+///
+///     fn part2(input: &Self::Input) -> Self::Answer {
+///         input.iter().product()
+///     }
+/// }
+///
+/// let report = adventofcode::solution::solve::<Sum>("2\n3\n4").unwrap();
+/// assert_eq!(report.part1.value, 9);
+/// assert_eq!(report.part2.value, 24);
+///
+/// let error = adventofcode::solution::solve::<Sum>("2\nx\n4").err().unwrap();
+/// assert_eq!(error.line, 2);
+/// ```
+pub trait Solution {
+    /// Year of the event the puzzle belongs to
+    const YEAR: u16;
+
+    /// Day of the event, 1 through 25
+    const DAY: u8;
+
+    /// Puzzle title as shown on the event calendar
+    const TITLE: &'static str;
+
+    /// Parsed form of the puzzle input shared by both parts
+    type Input;
+
+    /// Answer produced by either part
+    type Answer: fmt::Display;
+
+    /// Parses the raw puzzle input, describing the first problem found when it is malformed
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Solves the first part of the puzzle
+    fn part1(input: &Self::Input) -> Self::Answer;
+
+    /// Solves the second part of the puzzle
+    fn part2(input: &Self::Input) -> Self::Answer;
+}
+
+impl<S: Solution> Solver for S {
+    fn year(&self) -> u16 {
+        S::YEAR
+    }
+
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn title(&self) -> &str {
+        S::TITLE
+    }
+
+    fn solve(&self, part: Part, input: &str) -> Result<String, ParseError> {
+        let input = S::parse(input)?;
+
+        Ok(match part {
+            Part::One => S::part1(&input).to_string(),
+            Part::Two => S::part2(&input).to_string(),
+        })
+    }
+
+    fn bench(&self, input: &str, runs: usize) -> Result<Vec<Record>, ParseError> {
+        bench::measure::<S>(input, runs)
+    }
+}
+
+/// The result of a single stage along with how long it took to produce
+#[derive(Debug)]
+pub struct Timed<T> {
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+impl<T> Timed<T> {
+    /// Runs `f` recording how long it takes
+    pub fn run(f: impl FnOnce() -> T) -> Timed<T> {
+        let start = Instant::now();
+        let value = f();
+
+        Timed {
+            value,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+/// Answers to both parts of a puzzle and the time spent on each stage
+pub struct Report<S: Solution> {
+    pub parse: Duration,
+    pub part1: Timed<S::Answer>,
+    pub part2: Timed<S::Answer>,
+}
+
+impl<S: Solution> fmt::Display for Report<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} day {}: {}", S::YEAR, S::DAY, S::TITLE)?;
+        writeln!(f, "Parse: {:?}", self.parse)?;
+        writeln!(f, "Part 1: {} ({:?})", self.part1.value, self.part1.elapsed)?;
+        writeln!(f, "Part 2: {} ({:?})", self.part2.value, self.part2.elapsed)
+    }
+}
+
+/// Parses the input and solves both parts, timing each stage
+pub fn solve<S: Solution>(input: &str) -> Result<Report<S>, ParseError> {
+    let parsed = Timed::run(|| S::parse(input));
+    let value = parsed.value?;
+
+    Ok(Report {
+        parse: parsed.elapsed,
+        part1: Timed::run(|| S::part1(&value)),
+        part2: Timed::run(|| S::part2(&value)),
+    })
+}
+
+/// Reads the input file named on the command line, then solves and prints both parts of the puzzle. When `--bench` is
+/// given each stage is timed over that many runs instead. Unreadable or malformed input is reported and the process
+/// exits with a non-zero status.
+pub fn run<S: Solution>() {
+    let args = crate::parse_args();
+    let input = match fs::read_to_string(&args.input) {
+        Ok(input) => input,
+        Err(e) => exit(&format!("Could not read file {}: {}", args.input, e)),
+    };
+
+    match args.bench {
+        Some(runs) => match bench::measure::<S>(&input, runs) {
+            Ok(records) => {
+                println!("{} day {}: {}", S::YEAR, S::DAY, S::TITLE);
+                for record in records {
+                    println!("{}: {}", record.stage, record.stats);
+                }
+            }
+            Err(e) => exit(&format!("{}: {}", args.input, e.diagnostic(&input))),
+        },
+        None => match solve::<S>(&input) {
+            Ok(report) => print!("{}", report),
+            Err(e) => exit(&format!("{}: {}", args.input, e.diagnostic(&input))),
+        },
+    }
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("{}", message.trim_end());
+    std::process::exit(1);
+}
@@ -0,0 +1,534 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::input::{read_response, InputError, InputProvider};
+use crate::registry::Part;
+
+/// Name of the ledger file kept alongside the cached inputs
+pub const LEDGER_FILE: &str = "answers.ledger";
+
+/// The server's judgement of a submitted answer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without any hint as to which way
+    Incorrect,
+    /// Submitted before the previous attempt's timeout expired, the answer was not checked
+    Throttled,
+    /// The part has already been solved, or is not yet unlocked
+    WrongLevel,
+    /// The response did not match any known verdict
+    Unknown,
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page returned after submitting an answer
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::submit::Verdict;
+    /// let body = "<p>That's not the right answer; your answer is too high.</p>";
+    /// assert_eq!(Verdict::from_response(body), Verdict::TooHigh);
+    /// assert_eq!(Verdict::from_response("That's the right answer!"), Verdict::Correct);
+    /// ```
+    pub fn from_response(body: &str) -> Verdict {
+        if body.contains("That's the right answer") {
+            Verdict::Correct
+        } else if body.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if body.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if body.contains("That's not the right answer") {
+            Verdict::Incorrect
+        } else if body.contains("You gave an answer too recently") {
+            Verdict::Throttled
+        } else if body.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    /// Whether the server checked the answer and found it wrong
+    pub fn is_rejection(&self) -> bool {
+        matches!(
+            self,
+            Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect
+        )
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Incorrect => "incorrect",
+            Verdict::Throttled => "throttled",
+            Verdict::WrongLevel => "wrong-level",
+            Verdict::Unknown => "unknown",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "too-high" => Ok(Verdict::TooHigh),
+            "too-low" => Ok(Verdict::TooLow),
+            "incorrect" => Ok(Verdict::Incorrect),
+            "throttled" => Ok(Verdict::Throttled),
+            "wrong-level" => Ok(Verdict::WrongLevel),
+            "unknown" => Ok(Verdict::Unknown),
+            o => Err(format!("Unknown verdict {}", o)),
+        }
+    }
+}
+
+/// A single answer submitted to the server
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attempt {
+    /// Seconds since the unix epoch at which the answer was submitted
+    pub timestamp: u64,
+    pub year: u16,
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+impl Attempt {
+    fn is_for(&self, year: u16, day: u8, part: Part) -> bool {
+        self.year == year && self.day == day && self.part == part
+    }
+}
+
+impl fmt::Display for Attempt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.timestamp, self.year, self.day, self.part, self.verdict, self.answer
+        )
+    }
+}
+
+impl FromStr for Attempt {
+    type Err = String;
+
+    /// Parses a ledger line of tab separated timestamp, year, day, part, verdict and answer
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.splitn(6, '\t').collect();
+
+        if fields.len() != 6 {
+            return Err(format!("Malformed ledger entry {}", s));
+        }
+
+        let number = |field: &str| format!("Malformed {} in ledger entry {}", field, s);
+
+        Ok(Attempt {
+            timestamp: fields[0].parse().map_err(|_| number("timestamp"))?,
+            year: fields[1].parse().map_err(|_| number("year"))?,
+            day: fields[2].parse().map_err(|_| number("day"))?,
+            part: Part::try_from(fields[3].parse::<u8>().map_err(|_| number("part"))?)?,
+            verdict: fields[4].parse()?,
+            answer: fields[5].to_string(),
+        })
+    }
+}
+
+/// Why an answer was not submitted, or could not be
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The ledger already shows the answer cannot be right
+    Refused(String),
+    /// Posting the answer failed
+    Input(InputError),
+    /// Reading or writing the ledger failed
+    Ledger(String),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Refused(reason) => write!(f, "Refusing to submit: {}", reason),
+            SubmitError::Input(e) => write!(f, "{}", e),
+            SubmitError::Ledger(message) => write!(f, "Could not access ledger: {}", message),
+        }
+    }
+}
+
+impl From<InputError> for SubmitError {
+    fn from(e: InputError) -> Self {
+        SubmitError::Input(e)
+    }
+}
+
+impl From<io::Error> for SubmitError {
+    fn from(e: io::Error) -> Self {
+        SubmitError::Ledger(e.to_string())
+    }
+}
+
+/// Every answer ever submitted, persisted one attempt per line so the history survives between runs
+#[derive(Debug)]
+pub struct Ledger {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    /// Loads the ledger at `path`, starting an empty one if the file does not exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Ledger, SubmitError> {
+        let path = path.into();
+        let attempts = match fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.parse())
+                .collect::<Result<Vec<Attempt>, String>>()
+                .map_err(SubmitError::Ledger)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Ledger { path, attempts })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every recorded attempt, oldest first
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Attempts made at a single puzzle part, oldest first
+    pub fn history(&self, year: u16, day: u8, part: Part) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |attempt| attempt.is_for(year, day, part))
+    }
+
+    /// Checks an answer against what the server has already said about this part. Answers already rejected, and
+    /// numeric answers at or beyond a reported too high or too low bound, are refused.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use adventofcode::registry::Part;
+    /// # use adventofcode::submit::{Attempt, Ledger, Verdict};
+    /// # let path = std::env::temp_dir().join("adventofcode-ledger-doc");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut ledger = Ledger::open(&path).unwrap();
+    /// ledger.record(Attempt {
+    ///     timestamp: 0,
+    ///     year: 2015,
+    ///     day: 1,
+    ///     part: Part::One,
+    ///     answer: "300".to_string(),
+    ///     verdict: Verdict::TooHigh,
+    /// }).unwrap();
+    ///
+    /// assert!(ledger.check(2015, 1, Part::One, "280").is_ok());
+    /// assert!(ledger.check(2015, 1, Part::One, "300").is_err());
+    /// assert!(ledger.check(2015, 1, Part::One, "301").is_err());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn check(&self, year: u16, day: u8, part: Part, answer: &str) -> Result<(), String> {
+        let value = answer.parse::<i128>().ok();
+
+        for attempt in self.history(year, day, part) {
+            match attempt.verdict {
+                Verdict::Correct => {
+                    return Err(format!(
+                        "{} day {} part {} was already solved with {}",
+                        year, day, part, attempt.answer
+                    ))
+                }
+                v if v.is_rejection() && attempt.answer == answer => {
+                    return Err(format!("{} was already rejected as {}", answer, v))
+                }
+                _ => {}
+            }
+
+            let bound = match attempt.answer.parse::<i128>() {
+                Ok(bound) => bound,
+                Err(_) => continue,
+            };
+
+            match (attempt.verdict, value) {
+                (Verdict::TooHigh, Some(value)) if value >= bound => {
+                    return Err(format!(
+                        "{} is not below {} which was too high",
+                        value, bound
+                    ))
+                }
+                (Verdict::TooLow, Some(value)) if value <= bound => {
+                    return Err(format!(
+                        "{} is not above {} which was too low",
+                        value, bound
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends an attempt to the ledger, writing it through to disk immediately
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), SubmitError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", attempt)?;
+
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+/// Posts answers to the server, consulting and updating a ledger of previous attempts
+pub struct Submitter<'a> {
+    provider: &'a InputProvider,
+    ledger: Ledger,
+}
+
+impl<'a> Submitter<'a> {
+    /// Uses the provider's endpoint and session, keeping the ledger next to its cached inputs
+    pub fn new(provider: &'a InputProvider) -> Result<Submitter<'a>, SubmitError> {
+        let ledger = Ledger::open(provider.cache_dir().join(LEDGER_FILE))?;
+
+        Ok(Submitter { provider, ledger })
+    }
+
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    /// Submits an answer unless the ledger shows it cannot be right, recording whatever the server says
+    pub fn submit(
+        &mut self,
+        year: u16,
+        day: u8,
+        part: Part,
+        answer: &str,
+    ) -> Result<Verdict, SubmitError> {
+        let answer = answer.trim();
+
+        if answer.is_empty() || answer.contains(['\t', '\n']) {
+            return Err(SubmitError::Refused(format!(
+                "{:?} is not an answer",
+                answer
+            )));
+        }
+        self.ledger
+            .check(year, day, part, answer)
+            .map_err(SubmitError::Refused)?;
+
+        let verdict = Verdict::from_response(&self.post(year, day, part, answer)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+
+        self.ledger.record(Attempt {
+            timestamp,
+            year,
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict,
+        })?;
+
+        Ok(verdict)
+    }
+
+    fn post(&self, year: u16, day: u8, part: Part, answer: &str) -> Result<String, InputError> {
+        let session = self.provider.session().ok_or(InputError::MissingSession)?;
+        let url = format!("{}/{}/day/{}/answer", self.provider.base_url(), year, day);
+        let level = part.to_string();
+
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", session))
+            .set("User-Agent", crate::input::USER_AGENT)
+            .send_form(&[("level", &level), ("answer", answer)]);
+
+        read_response(response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::MockServer;
+    use std::env;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "adventofcode-submit-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn attempt(part: Part, answer: &str, verdict: Verdict) -> Attempt {
+        Attempt {
+            timestamp: 1,
+            year: 2015,
+            day: 7,
+            part,
+            answer: answer.to_string(),
+            verdict,
+        }
+    }
+
+    /// Plays the part of the server for a puzzle whose answer is 42
+    fn judge(body: &str) -> String {
+        let answer: i64 = body
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("answer="))
+            .and_then(|answer| answer.parse().ok())
+            .unwrap_or_default();
+
+        match answer {
+            42 => "<p>That's the right answer!</p>",
+            a if a > 42 => "<p>That's not the right answer; your answer is too high.</p>",
+            _ => "<p>That's not the right answer; your answer is too low.</p>",
+        }
+        .to_string()
+    }
+
+    #[test]
+    fn test_verdict_from_response() {
+        assert_eq!(
+            Verdict::from_response("That's not the right answer; your answer is too low."),
+            Verdict::TooLow
+        );
+        assert_eq!(
+            Verdict::from_response("That's not the right answer.  If you're stuck"),
+            Verdict::Incorrect
+        );
+        assert_eq!(
+            Verdict::from_response("You gave an answer too recently"),
+            Verdict::Throttled
+        );
+        assert_eq!(
+            Verdict::from_response(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ),
+            Verdict::WrongLevel
+        );
+        assert_eq!(Verdict::from_response("<html></html>"), Verdict::Unknown);
+    }
+
+    #[test]
+    fn test_attempt_round_trip() {
+        let attempt = attempt(Part::Two, "abc def", Verdict::Incorrect);
+        assert_eq!(attempt.to_string(), "1\t2015\t7\t2\tincorrect\tabc def");
+        assert_eq!(attempt.to_string().parse(), Ok(attempt));
+        assert!("1\t2015\t7\t3\tincorrect\tabc".parse::<Attempt>().is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = cache_dir("check");
+        let mut ledger = Ledger::open(dir.join(LEDGER_FILE)).unwrap();
+        ledger
+            .record(attempt(Part::One, "10", Verdict::TooLow))
+            .unwrap();
+        ledger
+            .record(attempt(Part::One, "50", Verdict::TooHigh))
+            .unwrap();
+        ledger
+            .record(attempt(Part::One, "abc", Verdict::Incorrect))
+            .unwrap();
+        ledger
+            .record(attempt(Part::Two, "7", Verdict::Correct))
+            .unwrap();
+
+        assert!(ledger.check(2015, 7, Part::One, "11").is_ok());
+        assert!(ledger.check(2015, 7, Part::One, "49").is_ok());
+        assert!(ledger.check(2015, 7, Part::One, "abd").is_ok());
+        assert!(ledger.check(2015, 7, Part::One, "10").is_err());
+        assert!(ledger.check(2015, 7, Part::One, "-3").is_err());
+        assert!(ledger.check(2015, 7, Part::One, "51").is_err());
+        assert!(ledger.check(2015, 7, Part::One, "abc").is_err());
+        assert!(ledger.check(2015, 7, Part::Two, "8").is_err());
+        assert!(ledger.check(2015, 8, Part::One, "10").is_ok());
+
+        let reopened = Ledger::open(dir.join(LEDGER_FILE)).unwrap();
+        assert_eq!(reopened.attempts(), ledger.attempts());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            "/2015/day/7/answer" => (200, judge(&request.body)),
+            _ => (404, "Not Found".to_string()),
+        });
+        let dir = cache_dir("submit");
+        let provider = InputProvider::new(&server.url, Some("abc".to_string()), &dir);
+        let mut submitter = Submitter::new(&provider).unwrap();
+
+        assert_eq!(
+            submitter.submit(2015, 7, Part::One, "50").unwrap(),
+            Verdict::TooHigh
+        );
+        assert_eq!(
+            submitter.submit(2015, 7, Part::One, "30").unwrap(),
+            Verdict::TooLow
+        );
+        assert!(matches!(
+            submitter.submit(2015, 7, Part::One, "60"),
+            Err(SubmitError::Refused(_))
+        ));
+        assert!(matches!(
+            submitter.submit(2015, 7, Part::One, "30"),
+            Err(SubmitError::Refused(_))
+        ));
+        assert_eq!(
+            submitter.submit(2015, 7, Part::One, "42").unwrap(),
+            Verdict::Correct
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].body, "level=1&answer=50");
+        assert_eq!(requests[0].header("Cookie"), Some("session=abc"));
+
+        let ledger = Ledger::open(dir.join(LEDGER_FILE)).unwrap();
+        let verdicts: Vec<Verdict> = ledger.attempts().iter().map(|a| a.verdict).collect();
+        assert_eq!(
+            verdicts,
+            vec![Verdict::TooHigh, Verdict::TooLow, Verdict::Correct]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,35 @@
+use adventofcode::registry::Registry;
+
+/// Builds a registry containing every solved puzzle across all event years
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+
+    registry
+        .register(not_quite_lisp::NotQuiteLisp)
+        .register(no_math::NoMath)
+        .register(spherical_houses::SphericalHouses)
+        .register(stocking_stuffer::StockingStuffer)
+        .register(intern_elves::InternElves)
+        .register(fire_hazard::FireHazard)
+        .register(assembly_required::AssemblyRequired)
+        .register(matchsticks::Matchsticks);
+
+    registry
+        .register(report_repair::ReportRepair)
+        .register(password_philosophy::PasswordPhilosophy)
+        .register(toboggan_trajectory::TobogganTrajectory)
+        .register(passport_processing::PassportProcessing)
+        .register(binary_boarding::BinaryBoarding)
+        .register(custom_customs::CustomCustoms);
+
+    registry
+        .register(calorie_counting::CalorieCounting)
+        .register(rock_paper_scissors::RockPaperScissors)
+        .register(rucksack_reorganization::RucksackReorganization);
+
+    registry
+}
+
+fn main() {
+    adventofcode::runner::main(&registry());
+}
@@ -1,3 +1,5 @@
+use adventofcode::error::ParseError;
+use adventofcode::solution::Solution;
 use std::str::Lines;
 
 /// Parses calorie totals for a group of elves given a list of text file lines. Each line is a single snack for a given elf with blank lines separating each elf. Each elf is given an entry in the resulting Vec with their total amount of calories stored at the value.
@@ -22,33 +24,36 @@ use std::str::Lines;
 /// 10000
 /// ";
 ///
-/// let elves = calorie_counting::parse_elves(input.lines());
+/// let elves = calorie_counting::parse_elves(input.lines()).unwrap();
 /// assert_eq!(elves.len(), 5);
 /// assert_eq!(elves, vec![6000, 4000, 11000, 24000, 10000]);
+///
+/// let error = calorie_counting::parse_elves("1000\n\n20x0".lines()).unwrap_err();
+/// assert_eq!((error.line, error.text.as_str()), (3, "20x0"));
 /// ```
-pub fn parse_elves(lines: Lines) -> Vec<u64> {
+pub fn parse_elves(lines: Lines) -> Result<Vec<u64>, ParseError> {
     lines
-        .fold((true, vec![]), |acc, ele| {
-            let (new_record, elves) = acc;
+        .enumerate()
+        .try_fold((true, vec![]), |acc, (n, ele)| {
+            let (new_record, mut elves) = acc;
 
-            if ele.len() == 0 {
-                (true, elves)
-            } else {
-                let value = ele.parse::<u64>().unwrap();
+            if ele.is_empty() {
+                return Ok((true, elves));
+            }
 
-                if new_record {
-                    (false, [elves, vec![value]].concat())
-                } else {
-                    if let Some((last, others)) = elves.split_last() {
-                        let sum = last + value;
-                        (false, [others, &[sum]].concat())
-                    } else {
-                        panic!("Could not split elves :(")
-                    }
-                }
+            let value = ele
+                .parse::<u64>()
+                .map_err(|_| ParseError::invalid(ele, "a calorie count").on_line(n + 1))?;
+
+            if new_record {
+                elves.push(value);
+            } else if let Some(last) = elves.last_mut() {
+                *last += value;
             }
+
+            Ok((false, elves))
         })
-        .1
+        .map(|(_, elves)| elves)
 }
 
 /// Iterates over the provided vector of elves returning the one carrying the most calories.
@@ -60,15 +65,17 @@ pub fn parse_elves(lines: Lines) -> Vec<u64> {
 /// let max = calorie_counting::max_calories(&elves);
 /// assert_eq!(max, (3, &24000));
 /// ```
-pub fn max_calories(elves: &Vec<u64>) -> (usize, &u64) {
+pub fn max_calories(elves: &[u64]) -> Ranked<'_> {
     elves
         .iter()
         .enumerate()
-        .map(|ele| ele)
         .reduce(|acc, ele| if ele.1 > acc.1 { ele } else { acc })
         .unwrap()
 }
 
+/// An elf's position within the list along with the calories they carry
+pub type Ranked<'a> = (usize, &'a u64);
+
 /// Identifies which elves provide the most calories out of the entire set
 ///
 /// # Example:
@@ -78,7 +85,7 @@ pub fn max_calories(elves: &Vec<u64>) -> (usize, &u64) {
 /// let top_three = calorie_counting::top_three_calories(&elves);
 /// assert_eq!(top_three, ((3, &24000), (2, &11000), (4, &10000)));
 /// ```
-pub fn top_three_calories(elves: &Vec<u64>) -> ((usize, &u64), (usize, &u64), (usize, &u64)) {
+pub fn top_three_calories(elves: &[u64]) -> (Ranked<'_>, Ranked<'_>, Ranked<'_>) {
     let top_three = elves
         .iter()
         .enumerate()
@@ -120,3 +127,38 @@ pub fn top_three_calories(elves: &Vec<u64>) -> ((usize, &u64), (usize, &u64), (u
         top_three.2.unwrap(),
     )
 }
+
+/// Day 1: Calorie Counting
+///
+/// # Example:
+///
+/// ```rust
+/// # use adventofcode::solution::Solution;
+/// # use calorie_counting::CalorieCounting;
+/// let elves = CalorieCounting::parse("1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n").unwrap();
+/// assert_eq!(CalorieCounting::part1(&elves), 24000);
+/// assert_eq!(CalorieCounting::part2(&elves), 45000);
+/// ```
+pub struct CalorieCounting;
+
+impl Solution for CalorieCounting {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Calorie Counting";
+
+    type Input = Vec<u64>;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_elves(input.trim_end().lines())
+    }
+
+    fn part1(elves: &Self::Input) -> Self::Answer {
+        *max_calories(elves).1
+    }
+
+    fn part2(elves: &Self::Input) -> Self::Answer {
+        let top_three = top_three_calories(elves);
+        top_three.0 .1 + top_three.1 .1 + top_three.2 .1
+    }
+}
@@ -1,9 +1,9 @@
 #[macro_use]
 extern crate lazy_static;
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
 use regex::Regex;
 
-use std::str::Lines;
-
 /// Represents possible plays or moves in the classic game "Rock, Paper, Scissors"
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Play {
@@ -12,55 +12,62 @@ pub enum Play {
     Scissors
 }
 
-impl From<&str> for Play {
+impl TryFrom<&str> for Play {
+    type Error = ParseError;
+
     /// Decodes encrypted String values into Rock, Paper, or Scissors
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use rock_paper_scissors::Play;
-    /// let a: Play = "A".into();
+    /// let a = Play::try_from("A").unwrap();
     /// assert_eq!(a, Play::Rock);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::Play;
-    /// let b: Play = "B".into();
+    /// let b = Play::try_from("B").unwrap();
     /// assert_eq!(b, Play::Paper);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::Play;
-    /// let c: Play = "C".into();
+    /// let c = Play::try_from("C").unwrap();
     /// assert_eq!(c, Play::Scissors);
     /// ```
-
+    ///
     /// ```rust
     /// # use rock_paper_scissors::Play;
-    /// let x: Play = "X".into();
+    /// let x = Play::try_from("X").unwrap();
     /// assert_eq!(x, Play::Rock);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::Play;
-    /// let y: Play = "Y".into();
+    /// let y = Play::try_from("Y").unwrap();
     /// assert_eq!(y, Play::Paper);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::Play;
-    /// let z: Play = "Z".into();
+    /// let z = Play::try_from("Z").unwrap();
     /// assert_eq!(z, Play::Scissors);
     /// ```
-    fn from(value: &str) -> Self {
+    ///
+    /// ```rust
+    /// # use rock_paper_scissors::Play;
+    /// assert!(Play::try_from("D").is_err());
+    /// ```
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "A" => Play::Rock,
-            "B" => Play::Paper,
-            "C" => Play::Scissors,
-            "X" => Play::Rock,
-            "Y" => Play::Paper,
-            "Z" => Play::Scissors,
-            o => panic!("Encountered unexpected value {}", o)
+            "A" => Ok(Play::Rock),
+            "B" => Ok(Play::Paper),
+            "C" => Ok(Play::Scissors),
+            "X" => Ok(Play::Rock),
+            "Y" => Ok(Play::Paper),
+            "Z" => Ok(Play::Scissors),
+            o => Err(ParseError::invalid(o, "a play of A, B, C, X, Y or Z")),
         }
     }
 }
@@ -204,39 +211,41 @@ impl Outcome {
                 Play::Paper => Play::Rock,
                 Play::Scissors => Play::Paper
             },
-            Outcome::Draw => opponent.clone()
+            Outcome::Draw => *opponent
         }
     }
 }
 
-impl From<&str> for Outcome {
-    /// Decodes encrypted String values into Rock, Paper, or Scissors
+impl TryFrom<&str> for Outcome {
+    type Error = ParseError;
+
+    /// Decodes encrypted String values into a Win, Lose, or Draw
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use rock_paper_scissors::Outcome;
-    /// let x: Outcome = "X".into();
+    /// let x = Outcome::try_from("X").unwrap();
     /// assert_eq!(x, Outcome::Lose);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::Outcome;
-    /// let y: Outcome = "Y".into();
+    /// let y = Outcome::try_from("Y").unwrap();
     /// assert_eq!(y, Outcome::Draw);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::Outcome;
-    /// let z: Outcome = "Z".into();
+    /// let z = Outcome::try_from("Z").unwrap();
     /// assert_eq!(z, Outcome::Win);
     /// ```
-    fn from(value: &str) -> Self {
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "X" => Outcome::Lose,
-            "Y" => Outcome::Draw,
-            "Z" => Outcome::Win,
-            o => panic!("Unexpected outcome encrypted value {}", o)
+            "X" => Ok(Outcome::Lose),
+            "Y" => Ok(Outcome::Draw),
+            "Z" => Ok(Outcome::Win),
+            o => Err(ParseError::invalid(o, "an outcome of X, Y or Z")),
         }
     }
 }
@@ -257,37 +266,37 @@ impl Round {
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v1("A Y");
+    /// let r = Round::from_v1("A Y").unwrap();
     /// assert_eq!(r.score(), 8);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v1("B X");
+    /// let r = Round::from_v1("B X").unwrap();
     /// assert_eq!(r.score(), 1);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v1("C Z");
+    /// let r = Round::from_v1("C Z").unwrap();
     /// assert_eq!(r.score(), 6);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v2("A Y");
+    /// let r = Round::from_v2("A Y").unwrap();
     /// assert_eq!(r.score(), 4);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v2("B X");
+    /// let r = Round::from_v2("B X").unwrap();
     /// assert_eq!(r.score(), 1);
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v2("C Z");
+    /// let r = Round::from_v2("C Z").unwrap();
     /// assert_eq!(r.score(), 7);
     /// ```
     pub fn score(&self) -> u64 {
@@ -297,7 +306,7 @@ impl Round {
                 player.score() + outcome.score()
             },
             Round::V2(opponent, outcome) => {
-                let player: Play = outcome.counter_for(&opponent);
+                let player: Play = outcome.counter_for(opponent);
                 player.score() + outcome.score()
             }
         }
@@ -310,35 +319,34 @@ impl Round {
     ///
     /// ```rust
     /// # use rock_paper_scissors::{Round, Play};
-    /// let r: Round = Round::from_v1("A Y");
+    /// let r = Round::from_v1("A Y").unwrap();
     /// assert_eq!(r, Round::V1(Play::Rock, Play::Paper));
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::{Round, Play};
-    /// let r: Round = Round::from_v1("B X");
+    /// let r = Round::from_v1("B X").unwrap();
     /// assert_eq!(r, Round::V1(Play::Paper, Play::Rock));
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::{Round, Play};
-    /// let r: Round = Round::from_v1("C Z");
+    /// let r = Round::from_v1("C Z").unwrap();
     /// assert_eq!(r, Round::V1(Play::Scissors, Play::Scissors));
     /// ```
-    pub fn from_v1(value: &str) -> Self {
-        lazy_static! {
-            static ref ROUND_RE: Regex = Regex::new(r"^([A-C]) ([X-Z])$")
-        .expect("Could not compile round regular expression");
-        }
-
-        if let Some(cap) = ROUND_RE.captures(value) {
-            let opponent = cap.get(1).expect(format!("Could not find opponent play {}", value).as_str()).as_str().into();
-            let player = cap.get(2).expect(format!("Could not find player play {}", value).as_str()).as_str().into();
+    ///
+    /// ```rust
+    /// # use rock_paper_scissors::Round;
+    /// let e = Round::from_v1("C W").unwrap_err();
+    /// assert_eq!((e.column, e.text.as_str()), (3, "W"));
+    /// ```
+    pub fn from_v1(value: &str) -> Result<Self, ParseError> {
+        let (opponent, player) = split_round(value)?;
 
-            Round::V1(opponent, player)
-        } else {
-            panic!("Could not parse round \"{}\".", value)
-        }
+        Ok(Round::V1(
+            Play::try_from(opponent.as_str()).map_err(|e| e.offset(opponent.start()))?,
+            Play::try_from(player.as_str()).map_err(|e| e.offset(player.start()))?,
+        ))
     }
 
     /// Parses an encrypted round into a decoded Round struct
@@ -347,36 +355,42 @@ impl Round {
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v2("A Y");
+    /// let r = Round::from_v2("A Y").unwrap();
     /// assert_eq!(r, Round::V2(Play::Rock, Outcome::Draw));
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v2("B X");
+    /// let r = Round::from_v2("B X").unwrap();
     /// assert_eq!(r, Round::V2(Play::Paper, Outcome::Lose));
     /// ```
     ///
     /// ```rust
     /// # use rock_paper_scissors::*;
-    /// let r: Round = Round::from_v2("C Z");
+    /// let r = Round::from_v2("C Z").unwrap();
     /// assert_eq!(r, Round::V2(Play::Scissors, Outcome::Win));
     /// ```
-    pub fn from_v2(value: &str) -> Self {
-        lazy_static! {
-            static ref ROUND_RE: Regex = Regex::new(r"^([A-C]) ([X-Z])$")
-        .expect("Could not compile round regular expression");
-        }
+    pub fn from_v2(value: &str) -> Result<Self, ParseError> {
+        let (opponent, outcome) = split_round(value)?;
 
-        if let Some(cap) = ROUND_RE.captures(value) {
-            let opponent = cap.get(1).expect(format!("Could not find opponent play {}", value).as_str()).as_str().into();
-            let outcome = cap.get(2).expect(format!("Could not find outcome {}", value).as_str()).as_str().into();
+        Ok(Round::V2(
+            Play::try_from(opponent.as_str()).map_err(|e| e.offset(opponent.start()))?,
+            Outcome::try_from(outcome.as_str()).map_err(|e| e.offset(outcome.start()))?,
+        ))
+    }
+}
 
-            Round::V2(opponent, outcome)
-        } else {
-            panic!("Could not parse round \"{}\".", value)
-        }
+/// Splits an encrypted round into its two columns
+fn split_round(value: &str) -> Result<(regex::Match<'_>, regex::Match<'_>), ParseError> {
+    lazy_static! {
+        static ref ROUND_RE: Regex =
+            Regex::new(r"^(\S+) (\S+)$").expect("Could not compile round regular expression");
     }
+
+    ROUND_RE
+        .captures(value)
+        .and_then(|cap| Some((cap.get(1)?, cap.get(2)?)))
+        .ok_or_else(|| ParseError::invalid(value, "a round of two letters separated by a space"))
 }
 
 /// Parses an encrypted strategy guide into a vector of Round structs.
@@ -385,16 +399,12 @@ impl Round {
 ///
 /// ```rust
 /// # use rock_paper_scissors::*;
-/// let lines = "
-/// A Y
-/// B X
-/// C Z
-/// ".trim().lines();
-/// let rounds = parse_rounds_v1(lines);
+/// let input = "A Y\nB X\nC Z";
+/// let rounds = parse_rounds_v1(input).unwrap();
 /// assert_eq!(vec![Round::V1(Play::Rock, Play::Paper), Round::V1(Play::Paper, Play::Rock), Round::V1(Play::Scissors, Play::Scissors)], rounds);
 /// ```
-pub fn parse_rounds_v1(lines: Lines) -> Vec<Round> {
-    lines.map(|line| Round::from_v1(line)).collect()
+pub fn parse_rounds_v1(input: &str) -> Result<Vec<Round>, ParseError> {
+    parse_lines(input, Round::from_v1)
 }
 
 /// Parses an encrypted strategy guide into a vector of Round structs.
@@ -403,14 +413,59 @@ pub fn parse_rounds_v1(lines: Lines) -> Vec<Round> {
 ///
 /// ```rust
 /// # use rock_paper_scissors::*;
-/// let lines = "
-/// A Y
-/// B X
-/// C Z
-/// ".trim().lines();
-/// let rounds = parse_rounds_v2(lines);
+/// let input = "A Y\nB X\nC Z";
+/// let rounds = parse_rounds_v2(input).unwrap();
 /// assert_eq!(vec![Round::V2(Play::Rock, Outcome::Draw), Round::V2(Play::Paper, Outcome::Lose), Round::V2(Play::Scissors, Outcome::Win)], rounds);
 /// ```
-pub fn parse_rounds_v2(lines: Lines) -> Vec<Round> {
-    lines.map(|line| Round::from_v2(line)).collect()
+pub fn parse_rounds_v2(input: &str) -> Result<Vec<Round>, ParseError> {
+    parse_lines(input, Round::from_v2)
+}
+
+/// Totals the score of every round in a strategy guide
+///
+/// # Example:
+///
+/// ```rust
+/// # use rock_paper_scissors::*;
+/// let rounds = parse_rounds_v1("A Y\nB X\nC Z").unwrap();
+/// assert_eq!(total_score(&rounds), 15);
+/// ```
+pub fn total_score(rounds: &[Round]) -> u64 {
+    rounds.iter().map(|round| round.score()).sum()
+}
+
+/// Day 2: Rock Paper Scissors
+///
+/// # Example:
+///
+/// ```rust
+/// # use adventofcode::solution::Solution;
+/// # use rock_paper_scissors::RockPaperScissors;
+/// let rounds = RockPaperScissors::parse("A Y\nB X\nC Z\n").unwrap();
+/// assert_eq!(RockPaperScissors::part1(&rounds), 15);
+/// assert_eq!(RockPaperScissors::part2(&rounds), 12);
+/// ```
+pub struct RockPaperScissors;
+
+impl Solution for RockPaperScissors {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Rock Paper Scissors";
+
+    /// The strategy guide decoded under both the first and second interpretation
+    type Input = (Vec<Round>, Vec<Round>);
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let input = input.trim_end();
+        Ok((parse_rounds_v1(input)?, parse_rounds_v2(input)?))
+    }
+
+    fn part1(rounds: &Self::Input) -> Self::Answer {
+        total_score(&rounds.0)
+    }
+
+    fn part2(rounds: &Self::Input) -> Self::Answer {
+        total_score(&rounds.1)
+    }
 }
@@ -1,3 +1,6 @@
+use adventofcode::error::{parse_lines, ParseError};
+use adventofcode::solution::Solution;
+
 /// An elf's rucksack
 #[derive(Clone, Debug)]
 pub struct Rucksack {
@@ -8,30 +11,50 @@ pub struct Rucksack {
     right: u64
 }
 
-impl From<&str> for Rucksack {
+impl TryFrom<&str> for Rucksack {
+    type Error = ParseError;
+
     /// Converts a raw string from the puzzle input into a Rucksack object
     ///
     /// # Examples
     ///
     /// ```rust
     /// # use rucksack_reorganization::*;
-    /// let r: Rucksack = "vJrwpWtwJgWrhcsFMMfFFhFp".into();
+    /// let r = Rucksack::try_from("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+    ///
+    /// let e = Rucksack::try_from("vJrw-WtwJgWr").unwrap_err();
+    /// assert_eq!((e.column, e.text.as_str()), (5, "-"));
+    ///
+    /// assert!(Rucksack::try_from("abcABC").is_err());
     /// ```
-    fn from(value: &str) -> Self {
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let chars: Vec<char> = value.chars().collect();
-        let (left_compartment, right_compartment) = chars.split_at(value.len() / 2);
+        let (left_compartment, right_compartment) = chars.split_at(chars.len() / 2);
 
-        let left = left_compartment.iter().fold(0u64, |acc, ele| {
-            let code = priority(&ele);
-            acc | 1 << code
-        });
+        let left = left_compartment
+            .iter()
+            .enumerate()
+            .try_fold(0u64, |acc, (i, ele)| {
+                let code = priority(ele).map_err(|e| e.offset(i))?;
+                Ok(acc | 1 << code)
+            })?;
 
-        let right = right_compartment.iter().fold(0u64, |acc, ele| {
-            let code = priority(&ele);
-            acc | 1 << code
-        });
+        let right = right_compartment
+            .iter()
+            .enumerate()
+            .try_fold(0u64, |acc, (i, ele)| {
+                let code = priority(ele).map_err(|e| e.offset(left_compartment.len() + i))?;
+                Ok(acc | 1 << code)
+            })?;
 
-        Rucksack { left: left, right: right }
+        if left & right == 0 {
+            return Err(ParseError::invalid(
+                value,
+                "a rucksack with an item type in both compartments",
+            ));
+        }
+
+        Ok(Rucksack { left, right })
     }
 }
 
@@ -54,19 +77,133 @@ impl Rucksack {
 ///
 /// ```rust
 /// # use rucksack_reorganization::*;
-/// assert_eq!(priority(&'a'), 1);
-/// assert_eq!(priority(&'z'), 26);
-/// assert_eq!(priority(&'A'), 27);
-/// assert_eq!(priority(&'Z'), 52);
+/// assert_eq!(priority(&'a'), Ok(1));
+/// assert_eq!(priority(&'z'), Ok(26));
+/// assert_eq!(priority(&'A'), Ok(27));
+/// assert_eq!(priority(&'Z'), Ok(52));
+/// assert!(priority(&'!').is_err());
+/// ```
+pub fn priority(item: &char) -> Result<u8, ParseError> {
+    match item {
+        'A'..='Z' => Ok(*item as u8 - 38),
+        'a'..='z' => Ok(*item as u8 - 96),
+        o => Err(ParseError::invalid(o.to_string(), "an item type of a-z or A-Z")),
+    }
+}
+
+/// Finds the priority of the single item type present within a set of items, if there is one
+fn set_priority(set: u64) -> Option<u64> {
+    (1..57).find(|i| set & (1 << i) != 0)
+}
+
+/// The item types carried by every elf in a group
+fn badges(group: &[Rucksack]) -> u64 {
+    group
+        .iter()
+        .fold(u64::MAX, |acc, rucksack| acc & rucksack.combined())
+}
+
+/// Parses one rucksack per line of the puzzle input, rejecting any group of three elves with no badge in common
+///
+/// # Examples
+///
+/// ```rust
+/// # use rucksack_reorganization::*;
+/// let e = parse_rucksacks("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nxyxy").unwrap_err();
+/// assert_eq!((e.line, e.text.as_str()), (3, "xyxy"));
 /// ```
-pub fn priority(item: &char) -> u8 {
-    let code = *item as u8;
-
-    if code > 64 && code < 91 {
-        code - 38
-    } else if code > 96 && code < 123{
-        code - 96
-    } else {
-        panic!("Item type not prioritized: {}", item)
+pub fn parse_rucksacks(input: &str) -> Result<Vec<Rucksack>, ParseError> {
+    let rucksacks = parse_lines(input, |line| Rucksack::try_from(line))?;
+
+    for (n, group) in rucksacks.chunks(3).enumerate() {
+        if group.len() == 3 && badges(group) == 0 {
+            let line = n * 3 + 3;
+            let text = input.lines().nth(line - 1).unwrap_or_default();
+
+            return Err(ParseError::invalid(
+                text,
+                "a rucksack sharing an item type with the other two in its group",
+            )
+            .on_line(line));
+        }
+    }
+
+    Ok(rucksacks)
+}
+
+/// Sums the priority of the item shared between both compartments of every rucksack
+///
+/// # Examples
+///
+/// ```rust
+/// # use rucksack_reorganization::*;
+/// let rucksacks = parse_rucksacks("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL").unwrap();
+/// assert_eq!(shared_priority_sum(&rucksacks), 54);
+/// ```
+pub fn shared_priority_sum(rucksacks: &[Rucksack]) -> u64 {
+    rucksacks
+        .iter()
+        .map(|rucksack| {
+            set_priority(rucksack.shared())
+                .expect("Rucksacks always share an item type between compartments")
+        })
+        .sum()
+}
+
+/// Sums the priority of the badge carried by each group of three elves, groups with no badge count for nothing
+///
+/// # Examples
+///
+/// ```rust
+/// # use rucksack_reorganization::*;
+/// let rucksacks = parse_rucksacks("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg").unwrap();
+/// assert_eq!(badge_priority_sum(&rucksacks), 18);
+/// ```
+pub fn badge_priority_sum(rucksacks: &[Rucksack]) -> u64 {
+    rucksacks
+        .chunks(3)
+        .filter_map(|group| set_priority(badges(group)))
+        .sum()
+}
+
+/// Day 3: Rucksack Reorganization
+///
+/// # Example:
+///
+/// ```rust
+/// # use adventofcode::solution::Solution;
+/// # use rucksack_reorganization::RucksackReorganization;
+/// let input = "\
+/// vJrwpWtwJgWrhcsFMMfFFhFp
+/// jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+/// PmmdzqPrVvPwwTWBwg
+/// wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+/// ttgJtRGJQctTZtZT
+/// CrZsJsPPZsGzwwsLwLmpwMDw
+/// ";
+/// let rucksacks = RucksackReorganization::parse(input).unwrap();
+/// assert_eq!(RucksackReorganization::part1(&rucksacks), 157);
+/// assert_eq!(RucksackReorganization::part2(&rucksacks), 70);
+/// ```
+pub struct RucksackReorganization;
+
+impl Solution for RucksackReorganization {
+    const YEAR: u16 = 2022;
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Rucksack Reorganization";
+
+    type Input = Vec<Rucksack>;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_rucksacks(input.trim_end())
+    }
+
+    fn part1(rucksacks: &Self::Input) -> Self::Answer {
+        shared_priority_sum(rucksacks)
+    }
+
+    fn part2(rucksacks: &Self::Input) -> Self::Answer {
+        badge_priority_sum(rucksacks)
     }
 }